@@ -12,7 +12,11 @@ async fn main() -> io::Result<()> {
     while let Some(stream) = incoming.next().await {
         let stream = stream?;
         println!("Accepted from: {}", stream.peer_addr()?);
-        io::copy(&stream, &stream).await?;
+        // A client disconnecting, even abruptly, only ends its own
+        // connection: report it and keep serving.
+        if let Err(e) = io::copy(&stream, &stream).await {
+            eprintln!("Connection error: {e}");
+        }
     }
     Ok(())
 }
@@ -0,0 +1,317 @@
+//! Async channels for communicating between futures.
+//!
+//! WASI 0.2 is single-threaded, so these channels are built on `Rc` and
+//! `RefCell` rather than atomics: neither half is `Send`, and both must be
+//! driven by the same `wstd::runtime::block_on`. They don't use any WASI
+//! pollables; senders and receivers wake each other up directly.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use futures_lite::future::zip;
+//! use wstd::iter::AsyncIteratorExt;
+//!
+//! #[wstd::main]
+//! async fn main() {
+//!     let (sender, receiver) = wstd::channel::bounded(2);
+//!     let produce = async move {
+//!         for n in 0..5 {
+//!             sender.send(n).await.unwrap();
+//!         }
+//!         // `sender` is dropped here, which closes the channel.
+//!     };
+//!     let ((), received) = zip(produce, receiver.collect::<Vec<_>>()).await;
+//!     assert_eq!(received, [0, 1, 2, 3, 4]);
+//! }
+//! ```
+
+use crate::iter::AsyncIterator;
+use slab::Slab;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::poll_fn;
+use std::rc::Rc;
+use std::task::{Poll, Waker};
+
+/// Create a channel which holds at most `capacity` messages at a time.
+///
+/// Sending waits while the channel is full.
+///
+/// # Panics
+///
+/// This function will panic if `capacity` is zero.
+pub fn bounded<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "channel capacity must be at least one");
+    channel(Some(capacity))
+}
+
+/// Create a channel which can hold any number of messages.
+///
+/// Sending never waits.
+pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
+    channel(None)
+}
+
+fn channel<T>(capacity: Option<usize>) -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        queue: VecDeque::new(),
+        capacity,
+        senders: 1,
+        receiver_alive: true,
+        recv_waker: None,
+        send_wakers: Slab::new(),
+    }));
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// State shared between all halves of a channel.
+struct Shared<T> {
+    queue: VecDeque<T>,
+    capacity: Option<usize>,
+    senders: usize,
+    receiver_alive: bool,
+    recv_waker: Option<Waker>,
+    /// Wakers of senders waiting for capacity. Each waiting sender owns its
+    /// slot, and removes it once it stops waiting.
+    send_wakers: Slab<Waker>,
+}
+
+impl<T> Shared<T> {
+    fn is_full(&self) -> bool {
+        self.capacity.is_some_and(|cap| self.queue.len() >= cap)
+    }
+
+    fn wake_receiver(&mut self) {
+        if let Some(waker) = self.recv_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_senders(&self) {
+        for (_, waker) in self.send_wakers.iter() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+/// The sending half of a channel.
+///
+/// Senders can be cloned to send from multiple places. The channel is closed
+/// once every sender has been dropped.
+pub struct Sender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Send a message, waiting for capacity if the channel is full.
+    ///
+    /// If the receiver has been dropped, the message is handed back in the
+    /// error.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut waiter = SendWaiter {
+            shared: &self.shared,
+            key: None,
+        };
+        let mut value = Some(value);
+        poll_fn(|cx| {
+            let mut shared = waiter.shared.borrow_mut();
+            if !shared.receiver_alive {
+                let value = value.take().expect("polled after completion");
+                return Poll::Ready(Err(SendError(value)));
+            }
+            if shared.is_full() {
+                match waiter.key {
+                    Some(key) => shared.send_wakers[key].clone_from(cx.waker()),
+                    None => waiter.key = Some(shared.send_wakers.insert(cx.waker().clone())),
+                }
+                return Poll::Pending;
+            }
+            let value = value.take().expect("polled after completion");
+            shared.queue.push_back(value);
+            shared.wake_receiver();
+            Poll::Ready(Ok(()))
+        })
+        .await
+    }
+
+    /// Returns `true` if the receiver has been dropped.
+    pub fn is_closed(&self) -> bool {
+        !self.shared.borrow().receiver_alive
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.senders -= 1;
+        if shared.senders == 0 {
+            shared.wake_receiver();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+/// Removes a waiting sender's waker slot when its `send` completes or is
+/// cancelled.
+struct SendWaiter<'a, T> {
+    shared: &'a RefCell<Shared<T>>,
+    key: Option<usize>,
+}
+
+impl<T> Drop for SendWaiter<'_, T> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            self.shared.borrow_mut().send_wakers.remove(key);
+        }
+    }
+}
+
+/// The receiving half of a channel.
+///
+/// Messages are received in the order they were sent. Once every [`Sender`]
+/// has been dropped and all queued messages have been received, the channel
+/// is closed and [`recv`](Receiver::recv) returns `None`.
+pub struct Receiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Receiver<T> {
+    /// Receive the next message, waiting until one is available.
+    ///
+    /// Returns `None` once the channel is closed and empty.
+    pub async fn recv(&self) -> Option<T> {
+        poll_fn(|cx| {
+            let mut shared = self.shared.borrow_mut();
+            if let Some(value) = shared.queue.pop_front() {
+                shared.wake_senders();
+                return Poll::Ready(Some(value));
+            }
+            if shared.senders == 0 {
+                return Poll::Ready(None);
+            }
+            shared.recv_waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Returns the number of messages currently queued.
+    pub fn len(&self) -> usize {
+        self.shared.borrow().queue.len()
+    }
+
+    /// Returns `true` if no messages are currently queued.
+    pub fn is_empty(&self) -> bool {
+        self.shared.borrow().queue.is_empty()
+    }
+}
+
+impl<T> AsyncIterator for Receiver<T> {
+    type Item = T;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        self.recv().await
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.receiver_alive = false;
+        shared.wake_senders();
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An error returned from [`Sender::send`] when the receiver has been
+/// dropped. Contains the message that could not be sent.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "SendError(..)".fmt(f)
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "sending on a closed channel".fmt(f)
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::iter::AsyncIteratorExt;
+    use crate::runtime::block_on;
+    use futures_lite::future::zip;
+
+    #[test]
+    fn bounded_backpressure() {
+        block_on(async {
+            let (sender, receiver) = bounded(2);
+            let produce = async move {
+                for n in 0..10 {
+                    sender.send(n).await.unwrap();
+                }
+            };
+            let ((), received) = zip(produce, receiver.collect::<Vec<_>>()).await;
+            assert_eq!(received, (0..10).collect::<Vec<_>>());
+        })
+    }
+
+    #[test]
+    fn closed_when_senders_dropped() {
+        block_on(async {
+            let (sender, receiver) = unbounded();
+            let other = sender.clone();
+            sender.send(1).await.unwrap();
+            drop(sender);
+            other.send(2).await.unwrap();
+            drop(other);
+            assert_eq!(receiver.recv().await, Some(1));
+            assert_eq!(receiver.recv().await, Some(2));
+            assert_eq!(receiver.recv().await, None);
+        })
+    }
+
+    #[test]
+    fn send_after_receiver_dropped() {
+        block_on(async {
+            let (sender, receiver) = bounded(1);
+            drop(receiver);
+            assert!(sender.is_closed());
+            assert_eq!(sender.send("meow").await, Err(SendError("meow")));
+        })
+    }
+}
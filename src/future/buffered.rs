@@ -0,0 +1,146 @@
+use crate::iter::AsyncIterator;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::Poll;
+
+/// Run the futures from an iterator concurrently, keeping at most `limit` of
+/// them in flight at any time.
+///
+/// The returned [`AsyncIterator`] yields the output of each future in the
+/// order in which they *complete*, not the order in which they were produced
+/// by `iter`. Whenever a future completes, the next one is pulled from `iter`
+/// so that `limit` futures stay in flight until `iter` is exhausted.
+///
+/// Outputs are yielded as-is: if the futures return a `Result`, an `Err` is
+/// yielded like any other item and does not stop the remaining futures. Stop
+/// iterating (and drop the `Buffered`) to cancel the futures still in flight.
+///
+/// # Panics
+///
+/// This function will panic if `limit` is zero.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::future::buffered;
+/// use wstd::http::{Client, Request};
+/// use wstd::io::empty;
+/// use wstd::iter::AsyncIterator;
+///
+/// #[wstd::main]
+/// async fn main() {
+///     let client = Client::new();
+///     let urls = ["https://example.com/a", "https://example.com/b"];
+///     let requests = urls.into_iter().map(|url| {
+///         let client = &client;
+///         async move {
+///             let request = Request::get(url).body(empty()).unwrap();
+///             client.send(request).await
+///         }
+///     });
+///
+///     let mut responses = buffered(requests, 8);
+///     while let Some(response) = responses.next().await {
+///         println!("{:?}", response.map(|r| r.status()));
+///     }
+/// }
+/// ```
+pub fn buffered<I>(iter: I, limit: usize) -> Buffered<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Future,
+{
+    assert!(limit > 0, "buffered requires a limit of at least one");
+    Buffered {
+        iter: iter.into_iter(),
+        in_flight: Vec::with_capacity(limit),
+        limit,
+    }
+}
+
+/// An async iterator over the outputs of concurrently running futures.
+///
+/// This `struct` is created by the [`buffered`] function. See its
+/// documentation for more.
+#[must_use = "iterators do nothing unless advanced"]
+pub struct Buffered<I: Iterator> {
+    iter: I,
+    in_flight: Vec<Pin<Box<I::Item>>>,
+    limit: usize,
+}
+
+impl<I: Iterator> std::fmt::Debug for Buffered<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Buffered")
+            .field("in_flight", &self.in_flight.len())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl<I> AsyncIterator for Buffered<I>
+where
+    I: Iterator,
+    I::Item: Future,
+{
+    type Item = <I::Item as Future>::Output;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        while self.in_flight.len() < self.limit {
+            match self.iter.next() {
+                Some(fut) => self.in_flight.push(Box::pin(fut)),
+                None => break,
+            }
+        }
+        if self.in_flight.is_empty() {
+            return None;
+        }
+
+        poll_fn(|cx| {
+            for (index, fut) in self.in_flight.iter_mut().enumerate() {
+                if let Poll::Ready(output) = fut.as_mut().poll(cx) {
+                    // Order of the remaining futures doesn't matter, so avoid
+                    // shifting them all down.
+                    drop(self.in_flight.swap_remove(index));
+                    return Poll::Ready(Some(output));
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::iter::AsyncIteratorExt;
+    use crate::runtime::block_on;
+    use crate::task::sleep;
+    use crate::time::Duration;
+
+    #[test]
+    fn buffered_yields_in_completion_order() {
+        block_on(async {
+            let in_flight = std::cell::Cell::new(0);
+            let max_in_flight = std::cell::Cell::new(0);
+            let futures = [100, 10, 20, 15].map(|millis| {
+                let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+                async move {
+                    in_flight.set(in_flight.get() + 1);
+                    max_in_flight.set(max_in_flight.get().max(in_flight.get()));
+                    sleep(Duration::from_millis(millis)).await;
+                    in_flight.set(in_flight.get() - 1);
+                    millis
+                }
+            });
+
+            let out: Vec<u64> = buffered(futures, 2).collect().await;
+            // 100 and 10 start at t=0, 20 starts when 10 finishes at t=10, 15
+            // starts when 20 finishes at t=30 and finishes at t=45, and 100
+            // finishes last.
+            assert_eq!(out, [10, 20, 15, 100]);
+            assert_eq!(max_in_flight.get(), 2);
+        })
+    }
+}
@@ -26,10 +26,12 @@
 //! }
 //! ```
 
+mod buffered;
 mod delay;
 mod future_ext;
 mod timeout;
 
+pub use buffered::{buffered, Buffered};
 pub use delay::Delay;
 pub use future_ext::FutureExt;
 pub use timeout::Timeout;
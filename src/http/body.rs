@@ -1,16 +1,25 @@
 //! HTTP body types
 
 use crate::io::{AsyncInputStream, AsyncRead, Cursor, Empty};
+use crate::iter::AsyncIterator;
+use crate::runtime::{AsyncPollable, WaitFor};
 use core::fmt;
-use http::header::{CONTENT_LENGTH, TRANSFER_ENCODING};
-use wasi::http::types::IncomingBody as WasiIncomingBody;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{ready, Context, Poll, Waker};
+use wasi::http::types::{FutureTrailers, IncomingBody as WasiIncomingBody};
 
+pub use super::chunked::{ChunkedDecoder, ChunkedEncoder};
+use super::fields::{header_map_from_wasi, parse_content_length};
 pub use super::{
     error::{Error, ErrorVariant},
     HeaderMap,
 };
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub(crate) enum BodyKind {
     Fixed(u64),
     Chunked,
@@ -18,16 +27,22 @@ pub(crate) enum BodyKind {
 
 impl BodyKind {
     pub(crate) fn from_headers(headers: &HeaderMap) -> Result<BodyKind, InvalidContentLength> {
-        if let Some(value) = headers.get(CONTENT_LENGTH) {
-            let content_length = std::str::from_utf8(value.as_ref())
-                .unwrap()
-                .parse::<u64>()
-                .map_err(|_| InvalidContentLength)?;
-            Ok(BodyKind::Fixed(content_length))
-        } else if headers.contains_key(TRANSFER_ENCODING) {
-            Ok(BodyKind::Chunked)
-        } else {
-            Ok(BodyKind::Chunked)
+        match parse_content_length(headers)? {
+            Some(content_length) => Ok(BodyKind::Fixed(content_length)),
+            None => Ok(BodyKind::Chunked),
+        }
+    }
+
+    fn len(&self) -> Option<usize> {
+        match self {
+            BodyKind::Fixed(l) => {
+                if *l > (usize::MAX as u64) {
+                    None
+                } else {
+                    Some(*l as usize)
+                }
+            }
+            BodyKind::Chunked => None,
         }
     }
 }
@@ -90,6 +105,33 @@ impl IntoBody for &[u8] {
     }
 }
 
+impl<'a> IntoBody for Cow<'a, [u8]> {
+    type IntoBody = BoundedBody<Cow<'a, [u8]>>;
+    fn into_body(self) -> Self::IntoBody {
+        BoundedBody(Cursor::new(self))
+    }
+}
+
+impl<'a> IntoBody for Cow<'a, str> {
+    type IntoBody = BoundedBody<Cow<'a, [u8]>>;
+    fn into_body(self) -> Self::IntoBody {
+        let bytes = match self {
+            Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+            Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+        };
+        BoundedBody(Cursor::new(bytes))
+    }
+}
+
+/// The body holds on to the `Bytes` directly, without copying them.
+#[cfg(feature = "bytes")]
+impl IntoBody for bytes::Bytes {
+    type IntoBody = BoundedBody<bytes::Bytes>;
+    fn into_body(self) -> Self::IntoBody {
+        BoundedBody(Cursor::new(self))
+    }
+}
+
 /// An HTTP body with a known length
 #[derive(Debug)]
 pub struct BoundedBody<T>(Cursor<T>);
@@ -111,14 +153,254 @@ impl Body for Empty {
     }
 }
 
+/// An HTTP body of unknown length, streamed from an [`AsyncRead`].
+///
+/// Because the length isn't known up front, no `Content-Length` header is
+/// derived from this body, and it is sent using chunked transfer encoding.
+/// This makes it possible to send a reader such as a file or another body
+/// without buffering it first:
+///
+/// ```no_run
+/// use wstd::http::{body::StreamBody, Client, Request};
+/// use wstd::io::ChunksReader;
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let reader = ChunksReader::new(["hello, ", "world"]);
+/// let request = Request::post("https://example.com").body(StreamBody::new(reader))?;
+/// let response = Client::new().send(request).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct StreamBody<R>(R);
+
+impl<R: AsyncRead> StreamBody<R> {
+    /// Stream the body from `reader`, until it reaches the end.
+    pub fn new(reader: R) -> Self {
+        Self(reader)
+    }
+
+    /// Consume the body, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.0
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for StreamBody<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> crate::io::Result<usize> {
+        self.0.read(buf).await
+    }
+}
+
+impl<R: AsyncRead> Body for StreamBody<R> {
+    fn len(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// An HTTP body of unknown length, streamed from an [`AsyncIterator`] of
+/// chunks.
+///
+/// Each read takes bytes from the current chunk, and pulls the next chunk
+/// from the iterator once the current one has been read in full. This suits
+/// producers which naturally yield discrete chunks, such as the rows of a
+/// CSV file computed on the fly; use [`StreamBody`] for an [`AsyncRead`]
+/// instead. Like `StreamBody`, it is sent using chunked transfer encoding.
+///
+/// An error yielded by the iterator is returned from the read which pulled
+/// it, and empty chunks are skipped.
+///
+/// ```no_run
+/// use wstd::http::{body::IterBody, Client, Request};
+/// use wstd::iter::AsyncIterator;
+///
+/// struct Rows(u32);
+///
+/// impl AsyncIterator for Rows {
+///     type Item = std::io::Result<Vec<u8>>;
+///
+///     async fn next(&mut self) -> Option<Self::Item> {
+///         self.0 = self.0.checked_sub(1)?;
+///         Some(Ok(format!("{},{}\n", self.0, self.0 * self.0).into_bytes()))
+///     }
+/// }
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let request = Request::post("https://example.com").body(IterBody::new(Rows(100)))?;
+/// let response = Client::new().send(request).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct IterBody<I> {
+    iter: I,
+    /// The current chunk; the unread bytes are `chunk[pos..]`.
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl<I> IterBody<I>
+where
+    I: AsyncIterator<Item = crate::io::Result<Vec<u8>>>,
+{
+    /// Stream the body from the chunks yielded by `iter`, until it ends.
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter,
+            chunk: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Consume the body, returning the inner iterator.
+    ///
+    /// Bytes of the current chunk which haven't been read yet are lost.
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<I> AsyncRead for IterBody<I>
+where
+    I: AsyncIterator<Item = crate::io::Result<Vec<u8>>>,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> crate::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.pos == self.chunk.len() {
+            match self.iter.next().await {
+                Some(chunk) => {
+                    self.chunk = chunk?;
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let available = &self.chunk[self.pos..];
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl<I> Body for IterBody<I>
+where
+    I: AsyncIterator<Item = crate::io::Result<Vec<u8>>>,
+{
+    fn len(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A body wrapper that counts the bytes read through it, and calls a
+/// closure once the body has been read to the end.
+///
+/// This makes it possible to observe request and response bodies, for
+/// logging or metrics, without changing the code that consumes them:
+///
+/// ```no_run
+/// use wstd::http::{body::InspectBody, Client, Request};
+/// use wstd::io::{empty, AsyncRead};
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let request = Request::get("https://example.com").body(empty())?;
+/// let response = Client::new().send(request).await?;
+/// let (parts, body) = response.into_parts();
+/// let mut body = InspectBody::new(body, |bytes| {
+///     println!("{}: {bytes} bytes", parts.status);
+/// });
+/// let mut buf = Vec::new();
+/// body.read_to_end(&mut buf).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Reads always go through the wrapper, so copying out of an `InspectBody`
+/// won't take the stream-splicing fast path.
+pub struct InspectBody<B, F> {
+    body: B,
+    read: u64,
+    on_finish: Option<F>,
+}
+
+impl<B, F> InspectBody<B, F>
+where
+    B: Body,
+    F: FnOnce(u64),
+{
+    /// Wrap `body`, calling `on_finish` with the total number of bytes read
+    /// once the end of the body is reached.
+    ///
+    /// `on_finish` isn't called if the body is dropped before it was read to
+    /// the end, or if reading it fails.
+    pub fn new(body: B, on_finish: F) -> Self {
+        Self {
+            body,
+            read: 0,
+            on_finish: Some(on_finish),
+        }
+    }
+
+    /// The number of bytes read from the body so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.read
+    }
+
+    /// Consume the wrapper, returning the inner body.
+    pub fn into_inner(self) -> B {
+        self.body
+    }
+}
+
+impl<B: fmt::Debug, F> fmt::Debug for InspectBody<B, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InspectBody")
+            .field("body", &self.body)
+            .field("read", &self.read)
+            .field("finished", &self.on_finish.is_none())
+            .finish()
+    }
+}
+
+impl<B, F> AsyncRead for InspectBody<B, F>
+where
+    B: Body,
+    F: FnOnce(u64),
+{
+    async fn read(&mut self, buf: &mut [u8]) -> crate::io::Result<usize> {
+        let n = self.body.read(buf).await?;
+        self.read += n as u64;
+        if n == 0 && !buf.is_empty() {
+            if let Some(on_finish) = self.on_finish.take() {
+                on_finish(self.read);
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<B, F> Body for InspectBody<B, F>
+where
+    B: Body,
+    F: FnOnce(u64),
+{
+    fn len(&self) -> Option<usize> {
+        self.body.len()
+    }
+}
+
 /// An incoming HTTP body
 #[derive(Debug)]
 pub struct IncomingBody {
     kind: BodyKind,
-    // IMPORTANT: the order of these fields here matters. `body_stream` must
-    // be dropped before `_incoming_body`.
-    body_stream: AsyncInputStream,
-    _incoming_body: WasiIncomingBody,
+    /// The number of bytes read so far.
+    consumed: u64,
+    // IMPORTANT: the order of these fields here matters. `stream` must be
+    // dropped before `trailers`, which may own the parent `WasiIncomingBody`.
+    stream: StreamSource,
+    trailers: TrailersSource,
 }
 
 impl IncomingBody {
@@ -129,33 +411,392 @@ impl IncomingBody {
     ) -> Self {
         Self {
             kind,
-            body_stream,
-            _incoming_body: incoming_body,
+            consumed: 0,
+            stream: StreamSource::Wasi(body_stream),
+            trailers: TrailersSource::Wasi(incoming_body),
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl IncomingBody {
+    /// Create a body from bytes in memory, followed by `trailers`.
+    ///
+    /// Incoming bodies normally come from the host, so this is useful to test
+    /// code which consumes them without running it in a `wasi:http` host. The
+    /// body's [`len`](Body::len) is the number of bytes, as if they were sent
+    /// with a `Content-Length` header. See also
+    /// [`test_util::incoming_request`](super::test_util::incoming_request).
+    ///
+    /// This is only available with the `test-util` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # wstd::runtime::block_on(async {
+    /// use wstd::http::body::IncomingBody;
+    ///
+    /// let mut body = IncomingBody::from_bytes("hello", None);
+    /// assert_eq!(body.bytes_limited(1024).await?, b"hello");
+    /// # Ok::<(), wstd::http::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>, trailers: Option<HeaderMap>) -> Self {
+        let bytes = bytes.into();
+        Self {
+            kind: BodyKind::Fixed(bytes.len() as u64),
+            consumed: 0,
+            stream: StreamSource::Memory(Cursor::new(bytes)),
+            trailers: TrailersSource::Memory(trailers),
+        }
+    }
+}
+
+impl IncomingBody {
+    /// Returns the number of bytes of the body which haven't been read yet,
+    /// if it was sent with a `Content-Length`.
+    ///
+    /// This is `None` for bodies of unknown length, such as chunked ones.
+    /// Bytes read through [`AsyncRead`] methods, such as
+    /// [`read`](AsyncRead::read) and [`read_to_end`](AsyncRead::read_to_end),
+    /// are counted. Bytes which [`io::copy`](crate::io::copy) splices
+    /// straight from the underlying stream bypass the count.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wstd::http::{Client, Request};
+    /// use wstd::io::{self, AsyncRead};
+    ///
+    /// # async fn download() -> anyhow::Result<()> {
+    /// let request = Request::get("https://example.com/").body(io::empty())?;
+    /// let mut body = Client::new().send(request).await?.into_body();
+    /// let mut buf = [0; 1024];
+    /// while body.read(&mut buf).await? > 0 {
+    ///     if let Some(remaining) = body.remaining() {
+    ///         eprintln!("{remaining} bytes left");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remaining(&self) -> Option<u64> {
+        match self.kind {
+            BodyKind::Fixed(len) => Some(len.saturating_sub(self.consumed)),
+            BodyKind::Chunked => None,
+        }
+    }
+
+    /// Reads the entire body into a `Vec`, failing once more than `max` bytes
+    /// have been received.
+    ///
+    /// If the `Content-Length` header already announces a body longer than
+    /// `max`, this fails without reading anything. Otherwise the body is read
+    /// until EOF, and reading stops as soon as the limit is exceeded, so a
+    /// peer can't make us buffer more than `max` bytes (plus one read chunk).
+    ///
+    /// # Errors
+    ///
+    /// Exceeding the limit returns an [`Error`] whose variant is
+    /// [`ErrorVariant::PayloadTooLarge`]. A server should answer such a request
+    /// with [`413 Payload Too Large`](super::StatusCode::PAYLOAD_TOO_LARGE).
+    /// I/O failures while reading return [`ErrorVariant::BodyIo`].
+    pub async fn bytes_limited(&mut self, max: usize) -> Result<Vec<u8>, Error> {
+        if let BodyKind::Fixed(len) = self.kind {
+            if len > max as u64 {
+                return Err(PayloadTooLarge { limit: max }.into());
+            }
+        }
+        read_to_end_limited(self, max).await
+    }
+}
+
+impl IncomingBody {
+    /// Splits the body into a stream of its bytes and a future resolving to
+    /// its trailers.
+    ///
+    /// Trailers are only sent after the last byte of the body, so the
+    /// [`Trailers`] future resolves once the [`BodyStream`] has been read to
+    /// the end, or dropped. This makes it possible to forward a body and
+    /// then its trailers, for example in a proxy, without first buffering
+    /// the whole body. Either half may be dropped at any time.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wstd::http::{Client, Request};
+    /// use wstd::io;
+    ///
+    /// # async fn forward() -> anyhow::Result<()> {
+    /// let request = Request::get("https://example.com/").body(io::empty())?;
+    /// let response = Client::new().send(request).await?;
+    /// let (stream, trailers) = response.into_body().into_parts();
+    /// io::copy(stream, io::stdout()).await?;
+    /// if let Some(trailers) = trailers.await? {
+    ///     eprintln!("trailers: {trailers:?}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_parts(self) -> (BodyStream, Trailers) {
+        let shared = Rc::new(RefCell::new(TrailersShared {
+            body: Some(self.trailers),
+            stream_done: false,
+            waker: None,
+        }));
+        let stream = BodyStream {
+            kind: self.kind,
+            stream: Some(self.stream),
+            shared: shared.clone(),
+        };
+        let trailers = Trailers {
+            state: TrailersState::Body,
+            shared,
+        };
+        (stream, trailers)
+    }
+}
+
+async fn read_to_end_limited<R: AsyncRead>(reader: &mut R, max: usize) -> Result<Vec<u8>, Error> {
+    const CHUNK_SIZE: usize = 2048;
+    let mut buf = Vec::new();
+    let mut n = 0;
+    loop {
+        if buf.len() == n {
+            // Allow reading one byte past the limit, so that we can tell a
+            // body of exactly `max` bytes apart from one that's too long.
+            let room = (max + 1).saturating_sub(n).min(CHUNK_SIZE);
+            buf.resize(n + room, 0u8);
+        }
+        let len = reader.read(&mut buf[n..]).await?;
+        if len == 0 {
+            buf.truncate(n);
+            return Ok(buf);
+        }
+        n += len;
+        if n > max {
+            return Err(PayloadTooLarge { limit: max }.into());
         }
     }
 }
 
 impl AsyncRead for IncomingBody {
     async fn read(&mut self, out_buf: &mut [u8]) -> crate::io::Result<usize> {
-        self.body_stream.read(out_buf).await
+        let n = self.stream.read(out_buf).await?;
+        self.consumed += n as u64;
+        Ok(n)
+    }
+
+    // Overridden so that reads through the WASI stream are counted, rather
+    // than going to the stream directly.
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> crate::io::Result<usize> {
+        let n = self.stream.read_buf(buf).await?;
+        self.consumed += n as u64;
+        Ok(n)
     }
 
     fn as_async_input_stream(&self) -> Option<&AsyncInputStream> {
-        Some(&self.body_stream)
+        self.stream.as_async_input_stream()
     }
 }
 
 impl Body for IncomingBody {
     fn len(&self) -> Option<usize> {
-        match self.kind {
-            BodyKind::Fixed(l) => {
-                if l > (usize::MAX as u64) {
-                    None
-                } else {
-                    Some(l as usize)
+        self.kind.len()
+    }
+}
+
+/// The bytes of an [`IncomingBody`], split off from its trailers.
+///
+/// This `struct` is created by the [`into_parts`] method on [`IncomingBody`].
+/// See its documentation for more.
+///
+/// [`into_parts`]: IncomingBody::into_parts
+#[derive(Debug)]
+pub struct BodyStream {
+    kind: BodyKind,
+    // IMPORTANT: `stream` must be dropped before `shared`, which may hold
+    // the last reference to the parent `WasiIncomingBody`.
+    stream: Option<StreamSource>,
+    shared: Rc<RefCell<TrailersShared>>,
+}
+
+/// Where the bytes of an incoming body are read from.
+#[derive(Debug)]
+enum StreamSource {
+    Wasi(AsyncInputStream),
+    #[cfg(any(test, feature = "test-util"))]
+    Memory(Cursor<Vec<u8>>),
+}
+
+impl StreamSource {
+    async fn read(&mut self, buf: &mut [u8]) -> crate::io::Result<usize> {
+        match self {
+            StreamSource::Wasi(stream) => stream.read(buf).await,
+            #[cfg(any(test, feature = "test-util"))]
+            StreamSource::Memory(bytes) => bytes.read(buf).await,
+        }
+    }
+
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> crate::io::Result<usize> {
+        match self {
+            StreamSource::Wasi(stream) => AsyncRead::read_buf(stream, buf).await,
+            #[cfg(any(test, feature = "test-util"))]
+            StreamSource::Memory(bytes) => bytes.read_buf(buf).await,
+        }
+    }
+
+    fn as_async_input_stream(&self) -> Option<&AsyncInputStream> {
+        match self {
+            StreamSource::Wasi(stream) => Some(stream),
+            #[cfg(any(test, feature = "test-util"))]
+            StreamSource::Memory(_) => None,
+        }
+    }
+}
+
+/// Where the trailers of an incoming body come from.
+#[derive(Debug)]
+enum TrailersSource {
+    Wasi(WasiIncomingBody),
+    #[cfg(any(test, feature = "test-util"))]
+    Memory(Option<HeaderMap>),
+}
+
+impl BodyStream {
+    /// Drops the stream, which allows the trailers to be read.
+    fn finish(&mut self) {
+        if self.stream.take().is_some() {
+            let mut shared = self.shared.borrow_mut();
+            shared.stream_done = true;
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+// `as_async_input_stream` is deliberately not forwarded: doing so would let
+// `io::copy` splice from the stream directly, never noticing the end of the
+// body, so the `Trailers` would not resolve.
+
+impl AsyncRead for BodyStream {
+    async fn read(&mut self, buf: &mut [u8]) -> crate::io::Result<usize> {
+        let Some(stream) = &mut self.stream else {
+            return Ok(0);
+        };
+        let n = stream.read(buf).await?;
+        if n == 0 && !buf.is_empty() {
+            self.finish();
+        }
+        Ok(n)
+    }
+
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> crate::io::Result<usize> {
+        let Some(stream) = &mut self.stream else {
+            return Ok(0);
+        };
+        let n = stream.read_buf(buf).await?;
+        if n == 0 {
+            self.finish();
+        }
+        Ok(n)
+    }
+}
+
+impl Body for BodyStream {
+    fn len(&self) -> Option<usize> {
+        self.kind.len()
+    }
+}
+
+impl Drop for BodyStream {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// State shared between a [`BodyStream`] and its [`Trailers`].
+#[derive(Debug)]
+struct TrailersShared {
+    /// Taken by `Trailers` once the stream is done; otherwise dropped along
+    /// with the last of the two halves.
+    body: Option<TrailersSource>,
+    stream_done: bool,
+    waker: Option<Waker>,
+}
+
+/// A future which resolves to the trailers of an [`IncomingBody`].
+///
+/// This `struct` is created by the [`into_parts`] method on [`IncomingBody`].
+/// See its documentation for more.
+///
+/// [`into_parts`]: IncomingBody::into_parts
+#[must_use = "futures do nothing unless polled or .awaited"]
+#[derive(Debug)]
+pub struct Trailers {
+    state: TrailersState,
+    shared: Rc<RefCell<TrailersShared>>,
+}
+
+#[derive(Debug)]
+enum TrailersState {
+    /// Waiting for the body stream to be done.
+    Body,
+    Pending {
+        // IMPORTANT: the pollable must be dropped before `future`.
+        wait: WaitFor,
+        _pollable: AsyncPollable,
+        future: FutureTrailers,
+    },
+    Done,
+}
+
+impl Future for Trailers {
+    type Output = Result<Option<HeaderMap>, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        loop {
+            match &mut this.state {
+                TrailersState::Body => {
+                    let mut shared = this.shared.borrow_mut();
+                    if !shared.stream_done {
+                        shared.waker = Some(cx.waker().clone());
+                        return Poll::Pending;
+                    }
+                    let source = shared.body.take().unwrap();
+                    drop(shared);
+                    let future = match source {
+                        TrailersSource::Wasi(body) => WasiIncomingBody::finish(body),
+                        #[cfg(any(test, feature = "test-util"))]
+                        TrailersSource::Memory(trailers) => {
+                            this.state = TrailersState::Done;
+                            return Poll::Ready(Ok(trailers));
+                        }
+                    };
+                    let pollable = AsyncPollable::new(future.subscribe());
+                    this.state = TrailersState::Pending {
+                        wait: pollable.wait_for(),
+                        _pollable: pollable,
+                        future,
+                    };
+                }
+                TrailersState::Pending { wait, future, .. } => {
+                    ready!(Pin::new(wait).poll(cx));
+                    // NOTE: the first `expect` is to ensure readiness, the
+                    // second is to trap if we try and get the trailers more
+                    // than once.
+                    let trailers = match future.get().expect("trailers are ready").unwrap() {
+                        Ok(Some(fields)) => header_map_from_wasi(fields).map(Some),
+                        Ok(None) => Ok(None),
+                        Err(err) => Err(err.into()),
+                    };
+                    this.state = TrailersState::Done;
+                    return Poll::Ready(trailers);
                 }
+                TrailersState::Done => panic!("future polled after completing"),
             }
-            BodyKind::Chunked => None,
         }
     }
 }
@@ -177,3 +818,205 @@ impl From<InvalidContentLength> for Error {
         ErrorVariant::Other(e.to_string()).into()
     }
 }
+
+/// An incoming body was longer than the caller was willing to accept.
+///
+/// Returned by [`IncomingBody::bytes_limited`]. This corresponds to the HTTP
+/// status code [`413 Payload Too Large`](super::StatusCode::PAYLOAD_TOO_LARGE).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadTooLarge {
+    limit: usize,
+}
+
+impl PayloadTooLarge {
+    /// The maximum number of bytes that would have been accepted.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl fmt::Display for PayloadTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "body exceeds the limit of {} bytes", self.limit)
+    }
+}
+
+impl std::error::Error for PayloadTooLarge {}
+
+impl From<PayloadTooLarge> for Error {
+    fn from(e: PayloadTooLarge) -> Self {
+        ErrorVariant::PayloadTooLarge(e).into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use http::header::{HeaderValue, CONTENT_LENGTH};
+
+    fn kind(values: &[&[u8]]) -> Result<BodyKind, InvalidContentLength> {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(CONTENT_LENGTH, HeaderValue::from_bytes(value).unwrap());
+        }
+        BodyKind::from_headers(&headers)
+    }
+
+    #[test]
+    fn content_length_valid() {
+        assert_eq!(kind(&[]).unwrap(), BodyKind::Chunked);
+        assert_eq!(kind(&[b"42"]).unwrap(), BodyKind::Fixed(42));
+        assert_eq!(kind(&[b"42", b"42"]).unwrap(), BodyKind::Fixed(42));
+        assert_eq!(kind(&[b"42, 42"]).unwrap(), BodyKind::Fixed(42));
+    }
+
+    #[test]
+    fn content_length_invalid() {
+        assert!(kind(&[b"\xff\xfe"]).is_err(), "non-UTF-8");
+        assert!(kind(&[b"-1"]).is_err(), "negative");
+        assert!(kind(&[b"+1"]).is_err(), "explicit sign");
+        assert!(kind(&[b""]).is_err(), "empty");
+        assert!(kind(&[b"99999999999999999999"]).is_err(), "overflow");
+        assert!(kind(&[b"42", b"43"]).is_err(), "conflicting headers");
+        assert!(kind(&[b"42, 43"]).is_err(), "conflicting list");
+    }
+
+    fn read_limited(body: &[u8], max: usize) -> Result<Vec<u8>, Error> {
+        block_on(async { read_to_end_limited(&mut Cursor::new(body), max).await })
+    }
+
+    #[test]
+    fn within_limit() {
+        let body = vec![7u8; 5000];
+        assert_eq!(read_limited(&body, 5000).unwrap(), body);
+        assert_eq!(read_limited(&body, 10_000).unwrap(), body);
+        assert_eq!(read_limited(b"", 0).unwrap(), b"");
+    }
+
+    #[test]
+    fn over_limit() {
+        let body = vec![7u8; 5000];
+        let err = read_limited(&body, 4999).unwrap_err();
+        match err.variant() {
+            ErrorVariant::PayloadTooLarge(e) => assert_eq!(e.limit(), 4999),
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(read_limited(b"x", 0).is_err());
+    }
+
+    #[test]
+    fn cow_bodies() {
+        let borrowed = Cow::Borrowed("hello").into_body();
+        assert_eq!(borrowed.len(), Some(5));
+        let owned = Cow::<[u8]>::Owned(vec![1, 2, 3]).into_body();
+        assert_eq!(owned.len(), Some(3));
+
+        let mut body = Cow::<str>::Owned("hello".to_owned()).into_body();
+        let mut buf = Vec::new();
+        block_on(body.read_to_end(&mut buf)).unwrap();
+        assert_eq!(buf, b"hello");
+    }
+
+    #[test]
+    fn remaining_after_partial_read() {
+        block_on(async {
+            let mut body = IncomingBody::from_bytes("hello world!", None);
+            assert_eq!(body.remaining(), Some(12));
+            let mut buf = [0; 6];
+            assert_eq!(body.read(&mut buf).await.unwrap(), 6);
+            assert_eq!(body.remaining(), Some(6));
+            let mut rest = Vec::new();
+            body.read_to_end(&mut rest).await.unwrap();
+            assert_eq!(rest, b"world!");
+            assert_eq!(body.remaining(), Some(0));
+
+            let mut body = IncomingBody::from_bytes(vec![7; 5000], None);
+            let mut all = Vec::new();
+            body.read_to_end(&mut all).await.unwrap();
+            assert_eq!(all.len(), 5000);
+            assert_eq!(body.remaining(), Some(0));
+
+            body.kind = BodyKind::Chunked;
+            assert_eq!(body.remaining(), None);
+        })
+    }
+
+    /// Yields the contents of a `Vec` in order.
+    struct Chunks(std::vec::IntoIter<crate::io::Result<Vec<u8>>>);
+    impl AsyncIterator for Chunks {
+        type Item = crate::io::Result<Vec<u8>>;
+        async fn next(&mut self) -> Option<Self::Item> {
+            self.0.next()
+        }
+    }
+
+    #[test]
+    fn iter_body_chunks() {
+        block_on(async {
+            let chunks = ["id,name\n", "", "1,meow\n", "2,purr\n"];
+            let chunks = chunks.map(|chunk| Ok(chunk.as_bytes().to_vec()));
+            let mut body = IterBody::new(Chunks(Vec::from(chunks).into_iter()));
+            assert_eq!(body.len(), None);
+            // A small buffer leaves part of each chunk for the next read.
+            let mut uploaded = Cursor::new(Vec::new());
+            let mut buf = [0; 5];
+            loop {
+                match body.read(&mut buf).await.unwrap() {
+                    0 => break,
+                    n => crate::io::AsyncWrite::write_all(&mut uploaded, &buf[..n])
+                        .await
+                        .unwrap(),
+                }
+            }
+            assert_eq!(uploaded.into_inner(), b"id,name\n1,meow\n2,purr\n");
+
+            let chunks = vec![Ok(b"ok".to_vec()), Err(std::io::ErrorKind::Other.into())];
+            let mut body = IterBody::new(Chunks(chunks.into_iter()));
+            let mut buf = Vec::new();
+            assert!(body.read_to_end(&mut buf).await.is_err());
+            assert_eq!(buf, b"ok");
+        })
+    }
+
+    #[test]
+    fn incoming_body_from_bytes() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("checksum", HeaderValue::from_static("abc"));
+        block_on(async {
+            let mut body = IncomingBody::from_bytes("hello", Some(trailers.clone()));
+            assert_eq!(body.len(), Some(5));
+            assert!(body.as_async_input_stream().is_none());
+            let mut buf = [0; 3];
+            assert_eq!(body.read(&mut buf).await.unwrap(), 3);
+
+            let (mut stream, trailers_fut) = body.into_parts();
+            let mut rest = Vec::new();
+            stream.read_to_end(&mut rest).await.unwrap();
+            assert_eq!(rest, b"lo");
+            assert_eq!(trailers_fut.await.unwrap(), Some(trailers));
+
+            let (stream, trailers_fut) = IncomingBody::from_bytes("", None).into_parts();
+            drop(stream);
+            assert_eq!(trailers_fut.await.unwrap(), None);
+        })
+    }
+
+    #[test]
+    fn inspect_body_reports_once() {
+        let finished = std::cell::Cell::new(None);
+        let mut body = InspectBody::new("hello world".into_body(), |n| {
+            assert!(finished.replace(Some(n)).is_none(), "called twice");
+        });
+        assert_eq!(body.len(), Some(11));
+        block_on(async {
+            let mut buf = [0; 4];
+            while body.read(&mut buf).await.unwrap() != 0 {
+                assert_eq!(finished.get(), None);
+            }
+            assert_eq!(body.read(&mut buf).await.unwrap(), 0);
+        });
+        assert_eq!(body.bytes_read(), 11);
+        assert_eq!(finished.get(), Some(11));
+    }
+}
@@ -0,0 +1,330 @@
+use super::{HeaderMap, HeaderName, HeaderValue};
+use crate::io::{self, AsyncRead, AsyncWrite};
+
+/// The longest chunk-size or trailer line we accept, to bound how much a peer
+/// can make us buffer.
+const MAX_LINE_LEN: usize = 8 * 1024;
+const READ_AHEAD: usize = 2048;
+
+/// Decodes a body sent with `Transfer-Encoding: chunked`.
+///
+/// `wasi:http` takes care of transfer encodings when going through
+/// [`Client`](super::Client). This is for speaking HTTP/1.1 directly over a
+/// socket: wrap the reader positioned at the start of the body, and read the
+/// decoded bytes from the `ChunkedDecoder`. Reads return `0` once the
+/// terminating zero-sized chunk and any trailers have been consumed, after
+/// which [`trailers`](ChunkedDecoder::trailers) returns the trailer fields.
+///
+/// Malformed input fails with [`InvalidData`](std::io::ErrorKind::InvalidData),
+/// and input that ends before the terminating chunk fails with
+/// [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof).
+#[derive(Debug)]
+pub struct ChunkedDecoder<R> {
+    reader: R,
+    buf: Vec<u8>,
+    pos: usize,
+    state: DecodeState,
+    trailers: HeaderMap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DecodeState {
+    Size,
+    Data(u64),
+    DataEnd,
+    Trailers,
+    Done,
+}
+
+impl<R: AsyncRead> ChunkedDecoder<R> {
+    /// Create a new decoder reading a chunked body from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            pos: 0,
+            state: DecodeState::Size,
+            trailers: HeaderMap::new(),
+        }
+    }
+
+    /// Returns the trailer fields sent after the last chunk.
+    ///
+    /// Returns `None` until the whole body has been read.
+    pub fn trailers(&self) -> Option<&HeaderMap> {
+        match self.state {
+            DecodeState::Done => Some(&self.trailers),
+            _ => None,
+        }
+    }
+
+    /// Consume the decoder, returning the inner reader.
+    ///
+    /// The decoder reads ahead, so bytes following the body may have been
+    /// consumed from the reader already; they are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Returns the buffered bytes, reading more from the reader if the buffer
+    /// is empty. Fails if the reader is at EOF.
+    async fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos == self.buf.len() {
+            self.buf.resize(READ_AHEAD, 0);
+            let n = self.reader.read(&mut self.buf).await?;
+            self.buf.truncate(n);
+            self.pos = 0;
+            if n == 0 {
+                return Err(io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "chunked body ended early",
+                ));
+            }
+        }
+        Ok(&self.buf[self.pos..])
+    }
+
+    /// Reads a line terminated by CRLF, returning it without the terminator.
+    async fn read_line(&mut self) -> io::Result<Vec<u8>> {
+        let mut line = Vec::new();
+        loop {
+            let available = self.fill_buf().await?;
+            match available.iter().position(|b| *b == b'\n') {
+                Some(i) => {
+                    line.extend_from_slice(&available[..i]);
+                    self.pos += i + 1;
+                    break;
+                }
+                None => {
+                    let n = available.len();
+                    line.extend_from_slice(available);
+                    self.pos += n;
+                }
+            }
+            if line.len() > MAX_LINE_LEN {
+                return Err(invalid_data("chunked body line too long"));
+            }
+        }
+        match line.pop() {
+            Some(b'\r') => Ok(line),
+            _ => Err(invalid_data("chunked body line not terminated by CRLF")),
+        }
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for ChunkedDecoder<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            match self.state {
+                DecodeState::Size => {
+                    let line = self.read_line().await?;
+                    let size = parse_chunk_size(&line)?;
+                    self.state = match size {
+                        0 => DecodeState::Trailers,
+                        size => DecodeState::Data(size),
+                    };
+                }
+                DecodeState::Data(remaining) => {
+                    let available = self.fill_buf().await?;
+                    let n = available
+                        .len()
+                        .min(buf.len())
+                        .min(remaining.try_into().unwrap_or(usize::MAX));
+                    buf[..n].copy_from_slice(&available[..n]);
+                    self.pos += n;
+                    self.state = match remaining - n as u64 {
+                        0 => DecodeState::DataEnd,
+                        remaining => DecodeState::Data(remaining),
+                    };
+                    return Ok(n);
+                }
+                DecodeState::DataEnd => {
+                    if !self.read_line().await?.is_empty() {
+                        return Err(invalid_data("chunk data longer than its size"));
+                    }
+                    self.state = DecodeState::Size;
+                }
+                DecodeState::Trailers => {
+                    let line = self.read_line().await?;
+                    if line.is_empty() {
+                        self.state = DecodeState::Done;
+                    } else {
+                        let (name, value) = parse_trailer(&line)?;
+                        self.trailers.append(name, value);
+                    }
+                }
+                DecodeState::Done => return Ok(0),
+            }
+        }
+    }
+}
+
+fn parse_chunk_size(line: &[u8]) -> io::Result<u64> {
+    // Chunk extensions follow a `;`, and are ignored.
+    let end = line.iter().position(|b| *b == b';').unwrap_or(line.len());
+    let digits = line[..end].trim_ascii();
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_hexdigit) {
+        return Err(invalid_data("invalid chunk size"));
+    }
+    // Only ASCII hex digits at this point, so this is valid UTF-8.
+    let digits = std::str::from_utf8(digits).unwrap();
+    u64::from_str_radix(digits, 16).map_err(|_| invalid_data("chunk size too large"))
+}
+
+fn parse_trailer(line: &[u8]) -> io::Result<(HeaderName, HeaderValue)> {
+    let colon = line
+        .iter()
+        .position(|b| *b == b':')
+        .ok_or_else(|| invalid_data("trailer field without a colon"))?;
+    let name = HeaderName::from_bytes(&line[..colon])
+        .map_err(|_| invalid_data("invalid trailer field name"))?;
+    let value = HeaderValue::from_bytes(line[colon + 1..].trim_ascii())
+        .map_err(|_| invalid_data("invalid trailer field value"))?;
+    Ok((name, value))
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Encodes a body with `Transfer-Encoding: chunked`.
+///
+/// This is the counterpart to [`ChunkedDecoder`], for writing HTTP/1.1
+/// directly to a socket. Every non-empty write becomes one chunk. Call
+/// [`finish`](ChunkedEncoder::finish) or
+/// [`finish_with_trailers`](ChunkedEncoder::finish_with_trailers) to write the
+/// terminating chunk; dropping the encoder without doing so leaves the body
+/// incomplete.
+#[derive(Debug)]
+pub struct ChunkedEncoder<W> {
+    writer: W,
+}
+
+impl<W: AsyncWrite> ChunkedEncoder<W> {
+    /// Create a new encoder writing a chunked body to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Write the terminating chunk, flush, and return the inner writer.
+    pub async fn finish(self) -> io::Result<W> {
+        self.finish_with_trailers(&HeaderMap::new()).await
+    }
+
+    /// Write the terminating chunk followed by `trailers`, flush, and return
+    /// the inner writer.
+    pub async fn finish_with_trailers(mut self, trailers: &HeaderMap) -> io::Result<W> {
+        let mut end = b"0\r\n".to_vec();
+        for (name, value) in trailers {
+            end.extend_from_slice(name.as_str().as_bytes());
+            end.extend_from_slice(b": ");
+            end.extend_from_slice(value.as_bytes());
+            end.extend_from_slice(b"\r\n");
+        }
+        end.extend_from_slice(b"\r\n");
+        self.writer.write_all(&end).await?;
+        self.writer.flush().await?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for ChunkedEncoder<W> {
+    async fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // An empty chunk would mark the end of the body.
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut chunk = Vec::with_capacity(buf.len() + 20);
+        chunk.extend_from_slice(format!("{:x}\r\n", buf.len()).as_bytes());
+        chunk.extend_from_slice(buf);
+        chunk.extend_from_slice(b"\r\n");
+        self.writer.write_all(&chunk).await?;
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::ChunksReader;
+    use crate::io::Cursor;
+    use crate::runtime::block_on;
+
+    fn decode(data: &[u8], max: usize) -> io::Result<(Vec<u8>, HeaderMap)> {
+        block_on(async {
+            let mut decoder = ChunkedDecoder::new(ChunksReader::new(data.chunks(max)));
+            let mut out = Vec::new();
+            decoder.read_to_end(&mut out).await?;
+            Ok((out, decoder.trailers().unwrap().clone()))
+        })
+    }
+
+    const BODY: &[u8] =
+        b"4\r\nWiki\r\n5;name=value\r\npedia\r\nE\r\n in\r\n\r\nchunks.\r\n0\r\n\r\n";
+
+    #[test]
+    fn decode_split_reads() {
+        for max in [1, 2, 3, 7, 1024] {
+            let (out, trailers) = decode(BODY, max).unwrap();
+            assert_eq!(out, b"Wikipedia in\r\n\r\nchunks.", "max {max}");
+            assert!(trailers.is_empty());
+        }
+    }
+
+    #[test]
+    fn decode_trailers() {
+        let body = b"3\r\nabc\r\n0\r\nExpires: never\r\nx-checksum:  42 \r\n\r\n";
+        for max in [1, 5, 1024] {
+            let (out, trailers) = decode(body, max).unwrap();
+            assert_eq!(out, b"abc");
+            assert_eq!(trailers["expires"], "never");
+            assert_eq!(trailers["x-checksum"], "42");
+        }
+    }
+
+    #[test]
+    fn decode_invalid() {
+        use std::io::ErrorKind;
+        let kind = |body: &[u8]| decode(body, 1024).unwrap_err().kind();
+        assert_eq!(kind(b"zz\r\nabc\r\n0\r\n\r\n"), ErrorKind::InvalidData);
+        assert_eq!(kind(b"\r\n"), ErrorKind::InvalidData);
+        assert_eq!(kind(b"3\nabc\r\n0\r\n\r\n"), ErrorKind::InvalidData);
+        assert_eq!(kind(b"2\r\nabc\r\n0\r\n\r\n"), ErrorKind::InvalidData);
+        assert_eq!(kind(b"11111111111111111\r\n"), ErrorKind::InvalidData);
+        assert_eq!(
+            kind(b"3\r\nabc\r\n0\r\nbad\r\n\r\n"),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(kind(b"3\r\nab"), ErrorKind::UnexpectedEof);
+        assert_eq!(kind(b"3\r\nabc\r\n"), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn encode_round_trip() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("expires", HeaderValue::from_static("never"));
+        let written = block_on(async {
+            let mut encoder = ChunkedEncoder::new(Cursor::new(Vec::new()));
+            encoder.write_all(b"Wiki").await.unwrap();
+            encoder.write_all(b"").await.unwrap();
+            encoder.write_all(b"pedia in chunks.").await.unwrap();
+            let writer = encoder.finish_with_trailers(&trailers).await.unwrap();
+            writer.into_inner()
+        });
+        assert_eq!(
+            written,
+            b"4\r\nWiki\r\n10\r\npedia in chunks.\r\n0\r\nexpires: never\r\n\r\n"
+        );
+
+        let (out, decoded) = decode(&written, 3).unwrap();
+        assert_eq!(out, b"Wikipedia in chunks.");
+        assert_eq!(decoded, trailers);
+    }
+}
@@ -1,47 +1,208 @@
-use super::{body::IncomingBody, Body, Error, Request, Response, Result};
+use super::{body::IncomingBody, Body, Error, HeaderMap, HeaderValue, Request, Response, Result};
+use crate::future::FutureExt;
+use crate::http::fields::HeaderLimits;
 use crate::http::request::try_into_outgoing;
 use crate::http::response::try_from_incoming;
 use crate::io::{self, AsyncOutputStream, AsyncPollable};
-use crate::time::Duration;
-use wasi::http::types::{OutgoingBody, RequestOptions as WasiRequestOptions};
+use crate::time::{Deadline, Duration};
+use http::header::{EXPECT, USER_AGENT};
+use wasi::http::types::{
+    FutureIncomingResponse, OutgoingBody, RequestOptions as WasiRequestOptions,
+};
+
+/// The `User-Agent` a [`Client`] sends by default, made up of the crate name
+/// and version, such as `wstd/0.5.0`.
+pub const DEFAULT_USER_AGENT: &str = concat!("wstd/", env!("CARGO_PKG_VERSION"));
 
 /// An HTTP client.
-// Empty for now, but permits adding support for RequestOptions soon:
+///
+/// Use [`Client::new`] for a client with the default configuration, or
+/// [`Client::builder`] to configure one up front.
 #[derive(Debug)]
 pub struct Client {
     options: Option<RequestOptions>,
+    default_headers: HeaderMap,
+    user_agent: Option<HeaderValue>,
+    header_limits: HeaderLimits,
+    expect_continue_timeout: Duration,
 }
 
 impl Client {
     /// Create a new instance of `Client`
     pub fn new() -> Self {
-        Self { options: None }
+        Self {
+            options: None,
+            default_headers: HeaderMap::new(),
+            user_agent: Some(HeaderValue::from_static(DEFAULT_USER_AGENT)),
+            header_limits: HeaderLimits::default(),
+            // The same as curl's default.
+            expect_continue_timeout: Duration::from_secs(1),
+        }
+    }
+
+    /// Create a [`ClientBuilder`] to configure a `Client`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstd::http::{Client, HeaderValue};
+    /// use wstd::time::Duration;
+    ///
+    /// let client = Client::builder()
+    ///     .connect_timeout(Duration::from_secs(5))
+    ///     .user_agent(HeaderValue::from_static("my-app/1.0"))
+    ///     .build();
+    /// ```
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder {
+            client: Self::new(),
+        }
     }
 
     /// Send an HTTP request.
-    pub async fn send<B: Body>(&self, req: Request<B>) -> Result<Response<IncomingBody>> {
+    ///
+    /// # HTTP versions
+    ///
+    /// `wasi:http` has no way to select the protocol version: the host
+    /// decides whether to use HTTP/1.1, HTTP/2, or something else. The
+    /// request's [`version`](http::Request::version) is therefore ignored, and
+    /// the returned response always reports the `http` crate's default
+    /// version, regardless of what went over the wire.
+    ///
+    /// # TLS
+    ///
+    /// For `https` URIs the host establishes the TLS connection, and
+    /// `wasi:http` exposes no TLS settings: the SNI hostname is always taken
+    /// from the request's authority, client certificates can't be supplied,
+    /// and the server's certificate is verified by the host according to its
+    /// own policy. A response is therefore only returned if the host accepted
+    /// the server's certificate; if it didn't, the request fails with an
+    /// error for which [`Error::is_tls`] returns `true`.
+    ///
+    /// To talk to endpoints that need mutual TLS or a custom SNI hostname,
+    /// open a [`TcpStream`](crate::net::TcpStream), which implements
+    /// [`AsyncRead`](crate::io::AsyncRead) and
+    /// [`AsyncWrite`](crate::io::AsyncWrite), and run a TLS library such as
+    /// `rustls` over it.
+    ///
+    /// # `Expect: 100-continue`
+    ///
+    /// If the request has an `Expect: 100-continue` header, the body isn't
+    /// sent right away. The server can then reject the request, for example
+    /// with `401 Unauthorized` or `413 Payload Too Large`, before the body
+    /// has been transmitted, in which case its response is returned without
+    /// sending the body at all.
+    ///
+    /// `wasi:http` doesn't surface the interim `100 Continue` response which
+    /// tells the client to go ahead, so the body is sent once no final
+    /// response has arrived within a timeout, rather than as soon as the
+    /// server allows it. The timeout is one second by default, and can be
+    /// set with [`ClientBuilder::expect_continue_timeout`].
+    ///
+    /// # Default headers
+    ///
+    /// Headers configured with [`ClientBuilder::default_headers`] are added
+    /// to the request, unless it already has a header of the same name.
+    ///
+    /// A `User-Agent` header is added in the same way, set to
+    /// [`DEFAULT_USER_AGENT`] unless configured otherwise with
+    /// [`ClientBuilder::user_agent`]. Use [`ClientBuilder::no_user_agent`] to
+    /// send requests without one.
+    ///
+    /// A `Content-Length` header is added too when the body's length is
+    /// known up front, unless the request already has a `Content-Length` or
+    /// a `Transfer-Encoding` header. Headers set on the request always take
+    /// precedence.
+    pub async fn send<B: Body>(&self, mut req: Request<B>) -> Result<Response<IncomingBody>> {
+        self.apply_default_headers(req.headers_mut());
+        let expect_continue = expects_continue(req.headers());
         let (wasi_req, body) = try_into_outgoing(req)?;
         let wasi_body = wasi_req.body().unwrap();
         let body_stream = wasi_body.write().unwrap();
 
         // 1. Start sending the request head
         let res = wasi::http::outgoing_handler::handle(wasi_req, self.wasi_options()?).unwrap();
+        let res_ready = AsyncPollable::new(res.subscribe());
 
-        // 2. Start sending the request body
-        io::copy(body, AsyncOutputStream::new(body_stream)).await?;
+        // 1a. With `Expect: 100-continue`, give the server a chance to reject
+        // the request before sending the body. `wasi:http` doesn't surface the
+        // interim `100 Continue`, so all we can do is wait for a final
+        // response for a while. If one arrives, the body is dropped without
+        // being finished, which aborts it.
+        if expect_continue {
+            let wait = res_ready.wait_for().timeout(self.expect_continue_timeout);
+            if wait.await.is_ok() {
+                drop(body_stream);
+                drop(wasi_body);
+                return self.take_response(res, res_ready);
+            }
+        }
+
+        // 2. Start sending the request body. If this fails, returning early
+        // drops `wasi_body` without finishing it, which wasi-http treats as
+        // the body being aborted rather than trapping.
+        let mut body_stream = AsyncOutputStream::new(body_stream);
+        io::copy(body, &mut body_stream).await?;
+
+        // 3. Wait for the host to accept everything we wrote, so that a
+        // failure to send the tail of the body is reported here instead of
+        // being lost. Hosts may close the stream as soon as they have the
+        // whole body, which isn't an error: a body that really was cut short
+        // makes `finish` below fail. The stream is a child of `wasi_body`,
+        // so it has to be dropped before the body is finished.
+        match body_stream.flush().await {
+            Err(err) if err.kind() == std::io::ErrorKind::ConnectionReset => {}
+            res => res?,
+        }
+        drop(body_stream);
 
-        // 3. Finish sending the request body
+        // 4. Finish sending the request body. This fails if the amount
+        // written doesn't match the request's Content-Length.
         let trailers = None;
-        OutgoingBody::finish(wasi_body, trailers).unwrap();
+        OutgoingBody::finish(wasi_body, trailers)?;
 
-        // 4. Receive the response
-        AsyncPollable::new(res.subscribe()).wait_for().await;
+        // 5. Receive the response
+        res_ready.wait_for().await;
+        self.take_response(res, res_ready)
+    }
 
+    /// Take the response out of `res`, which must be ready.
+    fn take_response(
+        &self,
+        res: FutureIncomingResponse,
+        res_ready: AsyncPollable,
+    ) -> Result<Response<IncomingBody>> {
+        // The pollable is a child of `res`, so it has to be dropped first.
+        drop(res_ready);
         // NOTE: the first `unwrap` is to ensure readiness, the second `unwrap`
         // is to trap if we try and get the response more than once. The final
         // `?` is to raise the actual error if there is one.
         let res = res.get().unwrap().unwrap()?;
-        try_from_incoming(res)
+        try_from_incoming(res, &self.header_limits)
+    }
+
+    /// Send an HTTP request, failing if the response head hasn't been
+    /// received by `deadline`.
+    ///
+    /// This is the same as [`send`](Self::send), but bounded by an overall
+    /// time budget rather than the per-step timeouts of the client's
+    /// options. Pass the same [`Deadline`] to later steps, such as reading
+    /// the response body with [`Deadline::run`], to bound the whole exchange.
+    ///
+    /// # Errors
+    ///
+    /// If the deadline passes first, the request is cancelled and an error
+    /// with the I/O error kind `TimedOut` is returned.
+    pub async fn send_with_deadline<B: Body>(
+        &self,
+        req: Request<B>,
+        deadline: impl Into<Deadline>,
+    ) -> Result<Response<IncomingBody>> {
+        let deadline = deadline.into();
+        deadline
+            .run(self.send(req))
+            .await
+            .map_err(std::io::Error::from)?
     }
 
     /// Set timeout on connecting to HTTP server
@@ -59,6 +220,46 @@ impl Client {
         self.options_mut().between_bytes_timeout = Some(d.into());
     }
 
+    /// Returns `true` if the host supports `option` on outgoing requests.
+    ///
+    /// Requests from a client configured with an unsupported option fail,
+    /// unless [`ClientBuilder::ignore_unsupported_options`] is set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wstd::http::{Client, RequestOption};
+    /// use wstd::time::Duration;
+    ///
+    /// let mut builder = Client::builder();
+    /// if Client::supports_option(RequestOption::ConnectTimeout) {
+    ///     builder = builder.connect_timeout(Duration::from_secs(5));
+    /// }
+    /// let client = builder.build();
+    /// ```
+    pub fn supports_option(option: RequestOption) -> bool {
+        // Setting an option on a throwaway set of options is the only way to
+        // find out whether the host supports it.
+        option
+            .set(&WasiRequestOptions::new(), Duration::from_secs(1))
+            .is_ok()
+    }
+
+    fn apply_default_headers(&self, headers: &mut HeaderMap) {
+        for name in self.default_headers.keys() {
+            if !headers.contains_key(name) {
+                for value in self.default_headers.get_all(name) {
+                    headers.append(name, value.clone());
+                }
+            }
+        }
+        if let Some(user_agent) = &self.user_agent {
+            if !headers.contains_key(USER_AGENT) {
+                headers.insert(USER_AGENT, user_agent.clone());
+            }
+        }
+    }
+
     fn options_mut(&mut self) -> &mut RequestOptions {
         match &mut self.options {
             Some(o) => o,
@@ -74,34 +275,271 @@ impl Client {
     }
 }
 
+fn expects_continue(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(EXPECT)
+        .iter()
+        .any(|value| value.as_bytes().eq_ignore_ascii_case(b"100-continue"))
+}
+
+/// A builder to configure a [`Client`].
+///
+/// This `struct` is created by the [`builder`] method on [`Client`]. See its
+/// documentation for more.
+///
+/// [`builder`]: Client::builder
+#[derive(Debug)]
+#[must_use = "builders do nothing unless `build` is called"]
+pub struct ClientBuilder {
+    client: Client,
+}
+
+impl ClientBuilder {
+    /// Set timeout on connecting to HTTP server
+    pub fn connect_timeout(mut self, d: impl Into<Duration>) -> Self {
+        self.client.set_connect_timeout(d);
+        self
+    }
+
+    /// Set timeout on recieving first byte of the Response body
+    pub fn first_byte_timeout(mut self, d: impl Into<Duration>) -> Self {
+        self.client.set_first_byte_timeout(d);
+        self
+    }
+
+    /// Set timeout on recieving subsequent chunks of bytes in the Response body stream
+    pub fn between_bytes_timeout(mut self, d: impl Into<Duration>) -> Self {
+        self.client.set_between_bytes_timeout(d);
+        self
+    }
+
+    /// Send requests without the options the host doesn't support, instead
+    /// of failing them.
+    ///
+    /// By default, a request fails if the client has an option set which the
+    /// host doesn't support, such as a timeout. With this set, the option is
+    /// left out of the request instead, which then behaves as if the option
+    /// wasn't set. See [`Client::supports_option`] to check for support
+    /// up front.
+    pub fn ignore_unsupported_options(mut self, ignore: bool) -> Self {
+        self.client.options_mut().ignore_unsupported = ignore;
+        self
+    }
+
+    /// Set how long to wait for the server to reject a request with an
+    /// `Expect: 100-continue` header, before sending its body anyway.
+    ///
+    /// The default is one second. See [`Client::send`] for more.
+    pub fn expect_continue_timeout(mut self, d: impl Into<Duration>) -> Self {
+        self.client.expect_continue_timeout = d.into();
+        self
+    }
+
+    /// Set headers to send with every request.
+    ///
+    /// These replace any default headers set before. A `User-Agent` header
+    /// among them takes precedence over the one set by
+    /// [`user_agent`](ClientBuilder::user_agent).
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.client.default_headers = headers;
+        self
+    }
+
+    /// Set the `User-Agent` header to send with every request.
+    ///
+    /// The default is [`DEFAULT_USER_AGENT`].
+    pub fn user_agent(mut self, value: HeaderValue) -> Self {
+        self.client.user_agent = Some(value);
+        self
+    }
+
+    /// Don't send a `User-Agent` header, unless the request or the
+    /// [`default_headers`](ClientBuilder::default_headers) have one.
+    pub fn no_user_agent(mut self) -> Self {
+        self.client.user_agent = None;
+        self
+    }
+
+    /// Fail responses whose headers, counting the length of every name and
+    /// value, add up to more than `bytes`.
+    ///
+    /// No limit is applied by default. The host usually enforces a limit of
+    /// its own, and rejects larger responses with an error before `wstd`
+    /// gets to see them; this limit is checked in addition to that, and is
+    /// only useful if it is lower. Exceeding it fails the request with an
+    /// [`ErrorVariant::HeadersTooLarge`](super::error::ErrorVariant::HeadersTooLarge)
+    /// error.
+    pub fn max_header_size(mut self, bytes: usize) -> Self {
+        self.client.header_limits.max_total_size = Some(bytes);
+        self
+    }
+
+    /// Fail responses with a header value longer than `bytes`.
+    ///
+    /// See [`max_header_size`](ClientBuilder::max_header_size) for how this
+    /// interacts with the host's limits.
+    pub fn max_header_value_len(mut self, bytes: usize) -> Self {
+        self.client.header_limits.max_value_len = Some(bytes);
+        self
+    }
+
+    /// Create the configured [`Client`].
+    pub fn build(self) -> Client {
+        self.client
+    }
+}
+
+/// An option on outgoing requests which the host may not support.
+///
+/// `wasi:http` lets hosts reject any of the request options, and more
+/// options may be added in the future. Use [`Client::supports_option`] to
+/// find out whether the host supports an option, and
+/// [`ClientBuilder::ignore_unsupported_options`] to send requests without the
+/// options it doesn't support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RequestOption {
+    /// The timeout set by [`ClientBuilder::connect_timeout`].
+    ConnectTimeout,
+    /// The timeout set by [`ClientBuilder::first_byte_timeout`].
+    FirstByteTimeout,
+    /// The timeout set by [`ClientBuilder::between_bytes_timeout`].
+    BetweenBytesTimeout,
+}
+
+impl RequestOption {
+    /// Set this option on `wasi`, failing if the host doesn't support it.
+    fn set(self, wasi: &WasiRequestOptions, timeout: Duration) -> std::result::Result<(), ()> {
+        match self {
+            Self::ConnectTimeout => wasi.set_connect_timeout(Some(*timeout)),
+            Self::FirstByteTimeout => wasi.set_first_byte_timeout(Some(*timeout)),
+            Self::BetweenBytesTimeout => wasi.set_between_bytes_timeout(Some(*timeout)),
+        }
+    }
+}
+
+impl std::fmt::Display for RequestOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ConnectTimeout => "connect timeout",
+            Self::FirstByteTimeout => "first byte timeout",
+            Self::BetweenBytesTimeout => "between bytes timeout",
+        })
+    }
+}
+
 #[derive(Default, Debug)]
 struct RequestOptions {
     connect_timeout: Option<Duration>,
     first_byte_timeout: Option<Duration>,
     between_bytes_timeout: Option<Duration>,
+    /// Leave out options the host doesn't support, instead of failing.
+    ignore_unsupported: bool,
 }
 
 impl RequestOptions {
     fn to_wasi(&self) -> Result<WasiRequestOptions> {
         let wasi = WasiRequestOptions::new();
-        if let Some(timeout) = self.connect_timeout {
-            wasi.set_connect_timeout(Some(*timeout)).map_err(|()| {
-                Error::other("wasi-http implementation does not support connect timeout option")
-            })?;
-        }
-        if let Some(timeout) = self.first_byte_timeout {
-            wasi.set_first_byte_timeout(Some(*timeout)).map_err(|()| {
-                Error::other("wasi-http implementation does not support first byte timeout option")
-            })?;
-        }
-        if let Some(timeout) = self.between_bytes_timeout {
-            wasi.set_between_bytes_timeout(Some(*timeout))
-                .map_err(|()| {
-                    Error::other(
-                        "wasi-http implementation does not support between byte timeout option",
-                    )
-                })?;
+        for (option, timeout) in [
+            (RequestOption::ConnectTimeout, self.connect_timeout),
+            (RequestOption::FirstByteTimeout, self.first_byte_timeout),
+            (
+                RequestOption::BetweenBytesTimeout,
+                self.between_bytes_timeout,
+            ),
+        ] {
+            let Some(timeout) = timeout else { continue };
+            if option.set(&wasi, timeout).is_err() && !self.ignore_unsupported {
+                return Err(Error::other(format!(
+                    "wasi-http implementation does not support {option} option"
+                )));
+            }
         }
         Ok(wasi)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use http::header::ACCEPT;
+
+    #[test]
+    fn default_headers_do_not_override() {
+        let mut defaults = HeaderMap::new();
+        defaults.append(ACCEPT, HeaderValue::from_static("text/html"));
+        defaults.append(ACCEPT, HeaderValue::from_static("text/plain"));
+        let client = Client::builder()
+            .default_headers(defaults)
+            .user_agent(HeaderValue::from_static("wstd-test"))
+            .build();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("custom"));
+        client.apply_default_headers(&mut headers);
+        assert_eq!(headers[USER_AGENT], "custom");
+        let accept: Vec<_> = headers.get_all(ACCEPT).iter().collect();
+        assert_eq!(accept, ["text/html", "text/plain"]);
+    }
+
+    #[test]
+    fn user_agent() {
+        let mut headers = HeaderMap::new();
+        Client::new().apply_default_headers(&mut headers);
+        assert_eq!(
+            headers[USER_AGENT],
+            concat!("wstd/", env!("CARGO_PKG_VERSION"))
+        );
+
+        let mut headers = HeaderMap::new();
+        Client::builder()
+            .no_user_agent()
+            .build()
+            .apply_default_headers(&mut headers);
+        assert!(headers.is_empty());
+
+        let mut defaults = HeaderMap::new();
+        defaults.insert(USER_AGENT, HeaderValue::from_static("from-defaults"));
+        let mut headers = HeaderMap::new();
+        Client::builder()
+            .user_agent(HeaderValue::from_static("from-builder"))
+            .default_headers(defaults)
+            .build()
+            .apply_default_headers(&mut headers);
+        assert_eq!(headers[USER_AGENT], "from-defaults");
+    }
+
+    #[test]
+    fn builder_sets_timeouts() {
+        let client = Client::builder()
+            .connect_timeout(Duration::from_secs(1))
+            .between_bytes_timeout(Duration::from_millis(10))
+            .build();
+        let options = client.options.unwrap();
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(1)));
+        assert_eq!(options.first_byte_timeout, None);
+        assert_eq!(
+            options.between_bytes_timeout,
+            Some(Duration::from_millis(10))
+        );
+        assert!(!options.ignore_unsupported);
+    }
+
+    #[test]
+    fn supported_options() {
+        // Wasmtime supports all of them.
+        for option in [
+            RequestOption::ConnectTimeout,
+            RequestOption::FirstByteTimeout,
+            RequestOption::BetweenBytesTimeout,
+        ] {
+            assert!(Client::supports_option(option), "{option}");
+        }
+        let client = Client::builder()
+            .first_byte_timeout(Duration::from_secs(1))
+            .ignore_unsupported_options(true)
+            .build();
+        assert!(client.options.as_ref().unwrap().ignore_unsupported);
+        assert!(client.wasi_options().unwrap().is_some());
+    }
+}
@@ -0,0 +1,307 @@
+use super::{HeaderValue, Method, Request, Response};
+use crate::time::SystemTime;
+use http::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use std::fmt;
+use std::str::FromStr;
+
+/// An entity tag, as found in the `ETag` and `If-None-Match` headers.
+///
+/// Entity tags identify a particular version of a resource. A weak tag,
+/// written `W/"..."`, only promises that two versions are semantically
+/// equivalent rather than byte-for-byte identical. See
+/// [RFC 7232, section 2.3](https://www.rfc-editor.org/rfc/rfc7232#section-2.3).
+///
+/// # Example
+///
+/// ```
+/// use wstd::http::ETag;
+///
+/// let tag: ETag = "W/\"v1\"".parse().unwrap();
+/// assert!(tag.is_weak());
+/// assert!(tag.weak_eq(&ETag::strong("v1").unwrap()));
+/// assert!(!tag.strong_eq(&ETag::strong("v1").unwrap()));
+/// assert_eq!(tag.to_string(), "W/\"v1\"");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ETag {
+    tag: String,
+    weak: bool,
+}
+
+impl ETag {
+    /// Create a strong entity tag from its opaque value, without quotes.
+    ///
+    /// Fails if `tag` contains a `"`, whitespace, or control characters.
+    pub fn strong(tag: impl Into<String>) -> Result<Self, InvalidETag> {
+        Self::new(tag.into(), false)
+    }
+
+    /// Create a weak entity tag from its opaque value, without quotes.
+    ///
+    /// Fails if `tag` contains a `"`, whitespace, or control characters.
+    pub fn weak(tag: impl Into<String>) -> Result<Self, InvalidETag> {
+        Self::new(tag.into(), true)
+    }
+
+    fn new(tag: String, weak: bool) -> Result<Self, InvalidETag> {
+        // etagc = %x21 / %x23-7E / obs-text
+        if tag
+            .bytes()
+            .all(|b| b == 0x21 || (0x23..=0x7e).contains(&b) || b >= 0x80)
+        {
+            Ok(Self { tag, weak })
+        } else {
+            Err(InvalidETag)
+        }
+    }
+
+    /// The opaque value of the tag, without quotes or the weakness prefix.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns `true` if this is a weak entity tag.
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// Strong comparison: both tags are strong and their values are equal.
+    ///
+    /// This is the comparison used by `If-Match` and for range requests.
+    pub fn strong_eq(&self, other: &ETag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Weak comparison: the values are equal, whether or not either tag is
+    /// weak.
+    ///
+    /// This is the comparison used by `If-None-Match`.
+    pub fn weak_eq(&self, other: &ETag) -> bool {
+        self.tag == other.tag
+    }
+
+    /// Encode the tag as a header value, for use in an `ETag` header.
+    pub fn to_header_value(&self) -> HeaderValue {
+        HeaderValue::from_bytes(self.to_string().as_bytes())
+            .expect("entity tags are valid header values")
+    }
+}
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weak {
+            f.write_str("W/")?;
+        }
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+impl FromStr for ETag {
+    type Err = InvalidETag;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_etag(s.trim()) {
+            Some((tag, "")) => Ok(tag),
+            _ => Err(InvalidETag),
+        }
+    }
+}
+
+/// The string was not a valid entity tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidETag;
+
+impl fmt::Display for InvalidETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid entity tag")
+    }
+}
+
+impl std::error::Error for InvalidETag {}
+
+/// Parses one entity tag from the start of `s`, returning it along with the
+/// rest of the string.
+fn parse_etag(s: &str) -> Option<(ETag, &str)> {
+    let (weak, s) = match s.strip_prefix("W/") {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (tag, rest) = s.strip_prefix('"')?.split_once('"')?;
+    Some((ETag::new(tag.to_owned(), weak).ok()?, rest))
+}
+
+/// Evaluates `If-None-Match` and `If-Modified-Since` against the current
+/// version of a resource, following RFC 7232, section 6.
+pub(super) fn is_not_modified<B>(
+    request: &Request<B>,
+    etag: Option<&ETag>,
+    last_modified: Option<SystemTime>,
+) -> bool {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return false;
+    }
+    let headers = request.headers();
+
+    // When `If-None-Match` is present, `If-Modified-Since` must be ignored.
+    if headers.contains_key(IF_NONE_MATCH) {
+        return headers.get_all(IF_NONE_MATCH).iter().any(|value| {
+            let Ok(mut list) = value.to_str() else {
+                return false;
+            };
+            loop {
+                list = list.trim_start_matches([' ', '\t', ',']);
+                if list.is_empty() {
+                    return false;
+                }
+                if let Some(rest) = list.strip_prefix('*') {
+                    if rest.trim().is_empty() {
+                        // `*` matches any current representation.
+                        return true;
+                    }
+                    return false;
+                }
+                let Some((tag, rest)) = parse_etag(list) else {
+                    return false;
+                };
+                if etag.is_some_and(|etag| etag.weak_eq(&tag)) {
+                    return true;
+                }
+                list = rest;
+            }
+        });
+    }
+
+    // An invalid date, or one we can't compare against, is ignored.
+    let since = headers
+        .get(IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(super::parse_http_date);
+    match (since, last_modified) {
+        (Some(since), Some(last_modified)) => last_modified.unix_seconds() <= since.unix_seconds(),
+        _ => false,
+    }
+}
+
+/// Copies the validators of a cached response into the conditional headers
+/// of a request.
+pub(super) fn revalidate<B, T>(request: &mut Request<B>, cached: &Response<T>) {
+    let cached = cached.headers();
+    let headers = request.headers_mut();
+    if let Some(etag) = cached.get(ETAG) {
+        headers.insert(IF_NONE_MATCH, etag.clone());
+    }
+    if let Some(last_modified) = cached.get(LAST_MODIFIED) {
+        headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn strong(tag: &str) -> ETag {
+        ETag::strong(tag).unwrap()
+    }
+
+    fn weak(tag: &str) -> ETag {
+        ETag::weak(tag).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!("\"xyzzy\"".parse(), Ok(strong("xyzzy")));
+        assert_eq!("W/\"xyzzy\"".parse(), Ok(weak("xyzzy")));
+        assert_eq!("\"\"".parse(), Ok(strong("")));
+        assert_eq!("xyzzy".parse::<ETag>(), Err(InvalidETag));
+        assert_eq!("w/\"xyzzy\"".parse::<ETag>(), Err(InvalidETag));
+        assert_eq!("\"a\" \"b\"".parse::<ETag>(), Err(InvalidETag));
+        assert_eq!(ETag::strong("a b"), Err(InvalidETag));
+        assert_eq!(weak("1").to_string(), "W/\"1\"");
+        assert_eq!(strong("1").to_header_value(), "\"1\"");
+    }
+
+    #[test]
+    fn comparison() {
+        // The table from RFC 7232, section 2.3.2.
+        assert!(!weak("1").strong_eq(&weak("1")));
+        assert!(weak("1").weak_eq(&weak("1")));
+        assert!(!weak("1").strong_eq(&weak("2")));
+        assert!(!weak("1").weak_eq(&weak("2")));
+        assert!(!weak("1").strong_eq(&strong("1")));
+        assert!(weak("1").weak_eq(&strong("1")));
+        assert!(strong("1").strong_eq(&strong("1")));
+        assert!(strong("1").weak_eq(&strong("1")));
+    }
+
+    fn get(headers: &[(&str, &str)]) -> Request<()> {
+        let mut req = Request::get("/");
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        req.body(()).unwrap()
+    }
+
+    #[test]
+    fn if_none_match() {
+        let tag = weak("v2");
+        let check = |value| is_not_modified(&get(&[("if-none-match", value)]), Some(&tag), None);
+        assert!(check("\"v2\""));
+        assert!(check("W/\"v2\""));
+        assert!(check("\"v1\", W/\"v2\""));
+        assert!(check("\"a,b\",\"v2\""));
+        assert!(check("*"));
+        assert!(!check("\"v1\""));
+        assert!(!check("v2"));
+
+        let both = get(&[("if-none-match", "\"v1\""), ("if-none-match", "\"v2\"")]);
+        assert!(is_not_modified(&both, Some(&tag), None));
+
+        let post = Request::post("/")
+            .header("if-none-match", "*")
+            .body(())
+            .unwrap();
+        assert!(!is_not_modified(&post, Some(&tag), None));
+    }
+
+    #[test]
+    fn if_modified_since() {
+        // Sun, 06 Nov 1994 08:49:37 GMT
+        let modified = SystemTime::from_unix_seconds(784_111_777);
+        let check =
+            |value| is_not_modified(&get(&[("if-modified-since", value)]), None, Some(modified));
+        assert!(check("Sun, 06 Nov 1994 08:49:37 GMT"));
+        assert!(check("Mon, 07 Nov 1994 00:00:00 GMT"));
+        assert!(!check("Sun, 06 Nov 1994 08:49:36 GMT"));
+        assert!(!check("not a date"));
+
+        // `If-None-Match` takes precedence.
+        let req = get(&[
+            ("if-none-match", "\"v1\""),
+            ("if-modified-since", "Mon, 07 Nov 1994 00:00:00 GMT"),
+        ]);
+        assert!(!is_not_modified(&req, Some(&strong("v2")), Some(modified)));
+        assert!(!is_not_modified(
+            &get(&[]),
+            Some(&strong("v2")),
+            Some(modified)
+        ));
+    }
+
+    #[test]
+    fn revalidate_copies_validators() {
+        let cached = Response::builder()
+            .header("etag", "W/\"v1\"")
+            .header("last-modified", "Sun, 06 Nov 1994 08:49:37 GMT")
+            .body(())
+            .unwrap();
+        let mut req = get(&[]);
+        revalidate(&mut req, &cached);
+        assert_eq!(req.headers()["if-none-match"], "W/\"v1\"");
+        assert_eq!(
+            req.headers()["if-modified-since"],
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+        let modified = SystemTime::from_unix_seconds(784_111_777);
+        assert!(is_not_modified(&req, Some(&weak("v1")), Some(modified)));
+    }
+}
@@ -0,0 +1,271 @@
+use super::HeaderValue;
+use crate::time::Duration;
+use std::fmt;
+
+/// A cookie to set with a `Set-Cookie` response header.
+///
+/// Attributes are formatted following
+/// [RFC 6265, section 4.1](https://www.rfc-editor.org/rfc/rfc6265#section-4.1),
+/// plus the `SameSite` attribute, which most browsers support. Use
+/// [`parse_cookie_header`] to read the cookies a client sends back.
+///
+/// # Example
+///
+/// ```
+/// use wstd::http::{Cookie, SameSite};
+/// use wstd::time::Duration;
+///
+/// let cookie = Cookie::new("session", "abc123")
+///     .path("/")
+///     .max_age(Duration::from_secs(3600))
+///     .http_only(true)
+///     .secure(true)
+///     .same_site(SameSite::Lax);
+/// assert_eq!(
+///     cookie.to_set_cookie_value(),
+///     "session=abc123; Path=/; Max-Age=3600; Secure; HttpOnly; SameSite=Lax",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    name: String,
+    value: String,
+    path: Option<String>,
+    domain: Option<String>,
+    max_age: Option<Duration>,
+    http_only: bool,
+    secure: bool,
+    same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    /// Create a cookie with the given name and value, and no attributes.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `name` is empty or contains characters
+    /// other than token characters, or if `value` contains whitespace, `"`,
+    /// `,`, `;`, `\`, or non-ASCII characters. Encode values which may
+    /// contain those first, for example with
+    /// [`encode_uri_component`](super::encode_uri_component).
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = value.into();
+        assert!(
+            !name.is_empty() && name.bytes().all(is_token),
+            "invalid cookie name: {name:?}"
+        );
+        assert!(
+            value.bytes().all(is_cookie_octet),
+            "invalid cookie value: {value:?}"
+        );
+        Self {
+            name,
+            value,
+            path: None,
+            domain: None,
+            max_age: None,
+            http_only: false,
+            secure: false,
+            same_site: None,
+        }
+    }
+
+    /// The name of the cookie.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of the cookie.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Only send the cookie for request paths at or below `path`.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `path` contains `;` or control characters.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(attribute_value(path.into(), "path"));
+        self
+    }
+
+    /// Send the cookie to `domain` and its subdomains, rather than only to
+    /// the host which set it.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `domain` contains `;` or control
+    /// characters.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(attribute_value(domain.into(), "domain"));
+        self
+    }
+
+    /// Expire the cookie after `max_age`, rounded down to whole seconds.
+    ///
+    /// Without a maximum age, the cookie lasts until the browser session
+    /// ends. A maximum age of zero deletes the cookie.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Hide the cookie from scripts running in the browser.
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Only send the cookie over secure connections, such as HTTPS.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Restrict sending the cookie with cross-site requests.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Format the cookie and its attributes as the value of a `Set-Cookie`
+    /// header.
+    pub fn to_set_cookie_value(&self) -> HeaderValue {
+        HeaderValue::try_from(self.to_string()).expect("cookies are validated on construction")
+    }
+}
+
+impl fmt::Display for Cookie {
+    /// Formats the cookie as the value of a `Set-Cookie` header.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(path) = &self.path {
+            write!(f, "; Path={path}")?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={domain}")?;
+        }
+        if let Some(max_age) = self.max_age {
+            let max_age: std::time::Duration = max_age.into();
+            write!(f, "; Max-Age={}", max_age.as_secs())?;
+        }
+        if self.secure {
+            f.write_str("; Secure")?;
+        }
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={same_site}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a cookie is sent with cross-site requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SameSite {
+    /// Only send the cookie with requests from the same site.
+    Strict,
+    /// Also send the cookie when navigating to the site from another one.
+    Lax,
+    /// Send the cookie with all requests. Browsers require such cookies to
+    /// be [`secure`](Cookie::secure).
+    None,
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        })
+    }
+}
+
+/// Parses the value of a `Cookie` request header into name/value pairs.
+///
+/// Pairs are yielded in the order they were sent, and may repeat a name, for
+/// example when cookies with different paths share it. Surrounding
+/// whitespace is trimmed, and so are the double quotes around a quoted
+/// value. Pairs without a `=` or with an empty name are skipped, since
+/// clients don't send those.
+///
+/// # Example
+///
+/// ```
+/// use wstd::http::parse_cookie_header;
+///
+/// let cookies: Vec<_> = parse_cookie_header("session=abc123; theme=\"dark\"").collect();
+/// assert_eq!(cookies, [("session", "abc123"), ("theme", "dark")]);
+/// ```
+pub fn parse_cookie_header(header: &str) -> impl Iterator<Item = (&str, &str)> {
+    header.split(';').filter_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        Some((name, value))
+    })
+}
+
+fn attribute_value(value: String, attribute: &str) -> String {
+    assert!(
+        value.bytes().all(|b| b != b';' && !b.is_ascii_control()),
+        "invalid cookie {attribute}: {value:?}"
+    );
+    value
+}
+
+/// tchar, from RFC 9110, section 5.6.2.
+fn is_token(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+/// cookie-octet, from RFC 6265, section 4.1.1.
+fn is_cookie_octet(b: u8) -> bool {
+    matches!(b, 0x21 | 0x23..=0x2b | 0x2d..=0x3a | 0x3c..=0x5b | 0x5d..=0x7e)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set_cookie_attributes() {
+        assert_eq!(Cookie::new("a", "").to_set_cookie_value(), "a=");
+        let cookie = Cookie::new("id", "a3fWa")
+            .domain("example.com")
+            .path("/docs")
+            .max_age(Duration::from_millis(2500))
+            .same_site(SameSite::Strict);
+        assert_eq!(cookie.name(), "id");
+        assert_eq!(cookie.value(), "a3fWa");
+        assert_eq!(
+            cookie.to_set_cookie_value(),
+            "id=a3fWa; Path=/docs; Domain=example.com; Max-Age=2; SameSite=Strict"
+        );
+        let deleted = Cookie::new("id", "").max_age(Duration::from_secs(0));
+        assert_eq!(deleted.to_string(), "id=; Max-Age=0");
+    }
+
+    #[test]
+    fn parse_header() {
+        let cookies: Vec<_> = parse_cookie_header(" a=1;b = 2 ;c=\"3\"; ; junk; =4; a=5").collect();
+        assert_eq!(cookies, [("a", "1"), ("b", "2"), ("c", "3"), ("a", "5")]);
+        assert_eq!(parse_cookie_header("").count(), 0);
+        assert_eq!(
+            parse_cookie_header("e=x=y").collect::<Vec<_>>(),
+            [("e", "x=y")]
+        );
+    }
+}
@@ -0,0 +1,385 @@
+//! Cross-Origin Resource Sharing (CORS) support for HTTP servers.
+//!
+//! Browsers only let scripts read cross-origin responses which carry the
+//! right `Access-Control-*` headers, and send an `OPTIONS` "preflight"
+//! request before most cross-origin requests. A [`CorsConfig`] describes
+//! which cross-origin requests are allowed, answers preflight requests, and
+//! adds the appropriate headers to actual responses.
+//!
+//! # Example
+//!
+//! ```
+//! use wstd::http::cors::CorsConfig;
+//! use wstd::http::{Method, Request, Response};
+//!
+//! let cors = CorsConfig::new()
+//!     .allow_origin("https://example.com")
+//!     .allow_methods([Method::GET, Method::POST])
+//!     .allow_headers(["content-type"]);
+//!
+//! let request = Request::options("/api")
+//!     .header("origin", "https://example.com")
+//!     .header("access-control-request-method", "POST")
+//!     .body(())
+//!     .unwrap();
+//! if cors.is_preflight(&request) {
+//!     let response = cors.preflight_response(&request);
+//!     assert_eq!(response.headers()["access-control-allow-origin"], "https://example.com");
+//! }
+//! ```
+
+use super::{HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode};
+use crate::io::{empty, Empty};
+use crate::time::Duration;
+use http::header::{
+    ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+    ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN, VARY,
+};
+
+/// Which cross-origin requests a server allows.
+///
+/// By default nothing is allowed; use the builder methods to open things up.
+#[derive(Debug, Clone, Default)]
+pub struct CorsConfig {
+    origins: AllowedOrigins,
+    methods: Vec<Method>,
+    headers: Vec<HeaderName>,
+    expose_headers: Vec<HeaderName>,
+    credentials: bool,
+    max_age: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Default)]
+enum AllowedOrigins {
+    #[default]
+    None,
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+impl CorsConfig {
+    /// Create a configuration which doesn't allow any cross-origin requests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow requests from `origin`, e.g. `https://example.com`.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `origin` is not a valid header value.
+    pub fn allow_origin(mut self, origin: &str) -> Self {
+        let origin = HeaderValue::from_str(origin).expect("origin is a valid header value");
+        match &mut self.origins {
+            AllowedOrigins::List(list) => list.push(origin),
+            AllowedOrigins::Any => {}
+            none => *none = AllowedOrigins::List(vec![origin]),
+        }
+        self
+    }
+
+    /// Allow requests from any origin.
+    pub fn allow_any_origin(mut self) -> Self {
+        self.origins = AllowedOrigins::Any;
+        self
+    }
+
+    /// Allow requests using these methods.
+    pub fn allow_methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.methods.extend(methods);
+        self
+    }
+
+    /// Allow requests to send these headers.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if any of the names is not a valid header name.
+    pub fn allow_headers<H>(mut self, headers: impl IntoIterator<Item = H>) -> Self
+    where
+        H: TryInto<HeaderName>,
+        H::Error: std::fmt::Debug,
+    {
+        self.headers.extend(
+            headers
+                .into_iter()
+                .map(|h| h.try_into().expect("valid header name")),
+        );
+        self
+    }
+
+    /// Let scripts read these response headers.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if any of the names is not a valid header name.
+    pub fn expose_headers<H>(mut self, headers: impl IntoIterator<Item = H>) -> Self
+    where
+        H: TryInto<HeaderName>,
+        H::Error: std::fmt::Debug,
+    {
+        self.expose_headers.extend(
+            headers
+                .into_iter()
+                .map(|h| h.try_into().expect("valid header name")),
+        );
+        self
+    }
+
+    /// Allow requests to include credentials such as cookies.
+    ///
+    /// Browsers reject a wildcard `Access-Control-Allow-Origin` on
+    /// credentialed requests, so when any origin is allowed the request's
+    /// origin is echoed back instead.
+    pub fn allow_credentials(mut self, allow: bool) -> Self {
+        self.credentials = allow;
+        self
+    }
+
+    /// Let browsers cache preflight responses for this long.
+    pub fn max_age(mut self, max_age: impl Into<Duration>) -> Self {
+        self.max_age = Some(max_age.into());
+        self
+    }
+
+    /// Returns `true` if `request` is a CORS preflight request.
+    ///
+    /// A preflight is an `OPTIONS` request carrying both an `Origin` and an
+    /// `Access-Control-Request-Method` header.
+    pub fn is_preflight<B>(&self, request: &Request<B>) -> bool {
+        request.method() == Method::OPTIONS
+            && request.headers().contains_key(ORIGIN)
+            && request
+                .headers()
+                .contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+    }
+
+    /// Answer a preflight request.
+    ///
+    /// Returns a `204 No Content` response. If the origin, method, and
+    /// headers of the request are all allowed, the response carries the
+    /// `Access-Control-Allow-*` headers; otherwise it carries none, and the
+    /// browser will refuse to send the actual request.
+    pub fn preflight_response<B>(&self, request: &Request<B>) -> Response<Empty> {
+        let mut response = Response::new(empty());
+        *response.status_mut() = StatusCode::NO_CONTENT;
+        let headers = response.headers_mut();
+        add_vary(headers, &ORIGIN);
+        add_vary(headers, &ACCESS_CONTROL_REQUEST_METHOD);
+        add_vary(headers, &ACCESS_CONTROL_REQUEST_HEADERS);
+
+        let Some(origin) = self.allowed_origin(request.headers()) else {
+            return response;
+        };
+        if !self.preflight_allowed(request.headers()) {
+            return response;
+        }
+
+        let headers = response.headers_mut();
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        if self.credentials {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+        if let Some(methods) = join(self.methods.iter().map(Method::as_str)) {
+            headers.insert(ACCESS_CONTROL_ALLOW_METHODS, methods);
+        }
+        if let Some(allowed) = join(self.headers.iter().map(HeaderName::as_str)) {
+            headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, allowed);
+        }
+        if let Some(max_age) = self.max_age {
+            let max_age: std::time::Duration = max_age.into();
+            headers.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age.as_secs()));
+        }
+        response
+    }
+
+    /// Add CORS headers to the response to an actual (non-preflight)
+    /// request.
+    ///
+    /// If the request's origin is not allowed, no `Access-Control-*` headers
+    /// are added, and the browser will hide the response from the script.
+    pub fn apply<B, C>(&self, request: &Request<B>, response: &mut Response<C>) {
+        let headers = response.headers_mut();
+        if !matches!(self.origins, AllowedOrigins::Any) || self.credentials {
+            // The response depends on the request's origin.
+            add_vary(headers, &ORIGIN);
+        }
+        let Some(origin) = self.allowed_origin(request.headers()) else {
+            return;
+        };
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        if self.credentials {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+        if let Some(exposed) = join(self.expose_headers.iter().map(HeaderName::as_str)) {
+            headers.insert(ACCESS_CONTROL_EXPOSE_HEADERS, exposed);
+        }
+    }
+
+    /// Returns the value for `Access-Control-Allow-Origin`, if the request's
+    /// origin is allowed.
+    fn allowed_origin(&self, headers: &HeaderMap) -> Option<HeaderValue> {
+        let origin = headers.get(ORIGIN)?;
+        match &self.origins {
+            AllowedOrigins::None => None,
+            AllowedOrigins::Any if self.credentials => Some(origin.clone()),
+            AllowedOrigins::Any => Some(HeaderValue::from_static("*")),
+            AllowedOrigins::List(list) => list.contains(origin).then(|| origin.clone()),
+        }
+    }
+
+    /// Returns `true` if the method and headers requested by a preflight are
+    /// all allowed.
+    fn preflight_allowed(&self, headers: &HeaderMap) -> bool {
+        let method = headers
+            .get(ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|m| Method::from_bytes(m.as_bytes()).ok());
+        match method {
+            Some(method) if self.methods.contains(&method) => {}
+            _ => return false,
+        }
+        headers
+            .get_all(ACCESS_CONTROL_REQUEST_HEADERS)
+            .iter()
+            .all(|value| match value.to_str() {
+                Ok(value) => value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .all(|name| {
+                        self.headers
+                            .iter()
+                            .any(|h| h.as_str().eq_ignore_ascii_case(name))
+                    }),
+                Err(_) => false,
+            })
+    }
+}
+
+/// Add `name` to the `Vary` header.
+fn add_vary(headers: &mut HeaderMap, name: &HeaderName) {
+    headers.append(VARY, HeaderValue::from_str(name.as_str()).unwrap());
+}
+
+/// Join names into a comma-separated header value, or `None` if there are no
+/// names.
+fn join<'a>(names: impl Iterator<Item = &'a str>) -> Option<HeaderValue> {
+    let joined = names.collect::<Vec<_>>().join(", ");
+    if joined.is_empty() {
+        None
+    } else {
+        Some(HeaderValue::from_str(&joined).expect("names are valid header values"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config() -> CorsConfig {
+        CorsConfig::new()
+            .allow_origin("https://example.com")
+            .allow_methods([Method::GET, Method::PUT])
+            .allow_headers(["content-type", "x-requested-with"])
+            .expose_headers(["x-request-id"])
+            .max_age(Duration::from_secs(600))
+    }
+
+    #[test]
+    fn preflight() {
+        let request = Request::options("/api")
+            .header("origin", "https://example.com")
+            .header("access-control-request-method", "PUT")
+            .header(
+                "access-control-request-headers",
+                "Content-Type, X-Requested-With",
+            )
+            .body(())
+            .unwrap();
+        let cors = config();
+        assert!(cors.is_preflight(&request));
+
+        let response = cors.preflight_response(&request);
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let headers = response.headers();
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_METHODS], "GET, PUT");
+        assert_eq!(
+            headers[ACCESS_CONTROL_ALLOW_HEADERS],
+            "content-type, x-requested-with"
+        );
+        assert_eq!(headers[ACCESS_CONTROL_MAX_AGE], "600");
+        assert!(!headers.contains_key(ACCESS_CONTROL_ALLOW_CREDENTIALS));
+    }
+
+    #[test]
+    fn preflight_rejected() {
+        let cors = config();
+        for (origin, method, headers) in [
+            ("https://evil.example", "PUT", "content-type"),
+            ("https://example.com", "DELETE", "content-type"),
+            ("https://example.com", "PUT", "authorization"),
+        ] {
+            let request = Request::options("/api")
+                .header("origin", origin)
+                .header("access-control-request-method", method)
+                .header("access-control-request-headers", headers)
+                .body(())
+                .unwrap();
+            let response = cors.preflight_response(&request);
+            assert!(
+                !response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN),
+                "{origin} {method} {headers} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn actual_request() {
+        let cors = config();
+        let request = Request::get("/api")
+            .header("origin", "https://example.com")
+            .body(())
+            .unwrap();
+        let mut response = Response::new(());
+        cors.apply(&request, &mut response);
+        let headers = response.headers();
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(headers[ACCESS_CONTROL_EXPOSE_HEADERS], "x-request-id");
+        assert_eq!(headers[VARY], "origin");
+
+        let request = Request::get("/api")
+            .header("origin", "https://evil.example")
+            .body(())
+            .unwrap();
+        let mut response = Response::new(());
+        cors.apply(&request, &mut response);
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[test]
+    fn any_origin_with_credentials() {
+        let cors = CorsConfig::new().allow_any_origin();
+        let request = Request::get("/")
+            .header("origin", "https://example.com")
+            .body(())
+            .unwrap();
+        let mut response = Response::new(());
+        cors.apply(&request, &mut response);
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+
+        let cors = cors.allow_credentials(true);
+        let mut response = Response::new(());
+        cors.apply(&request, &mut response);
+        let headers = response.headers();
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    }
+}
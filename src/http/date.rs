@@ -0,0 +1,131 @@
+use crate::time::utils::{self, CivilDateTime};
+use crate::time::SystemTime;
+
+/// Parse an HTTP date, as found in headers such as `Date`, `Expires`,
+/// `If-Modified-Since`, and `Retry-After`.
+///
+/// Senders must use the IMF-fixdate format, but recipients are expected to
+/// accept two obsolete formats as well, so all three are supported:
+///
+/// | Format      | Example                          |
+/// |-------------|----------------------------------|
+/// | IMF-fixdate | `Sun, 06 Nov 1994 08:49:37 GMT`  |
+/// | RFC 850     | `Sunday, 06-Nov-94 08:49:37 GMT` |
+/// | asctime     | `Sun Nov  6 08:49:37 1994`       |
+///
+/// Following RFC 9110, a two-digit RFC 850 year which would be more than 50
+/// years in the future is taken to be in the past century instead. The
+/// weekday isn't checked against the date. Returns `None` if `s` isn't a
+/// valid date in any of the formats, or is before the Unix epoch.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::http::{format_http_date, parse_http_date};
+///
+/// let date = parse_http_date("Sun Nov  6 08:49:37 1994").unwrap();
+/// assert_eq!(format_http_date(date), "Sun, 06 Nov 1994 08:49:37 GMT");
+/// ```
+pub fn parse_http_date(s: &str) -> Option<SystemTime> {
+    let current_year = CivilDateTime::from_unix_seconds(SystemTime::now().unix_seconds()).year;
+    utils::parse_http_date(s, current_year).map(SystemTime::from_unix_seconds)
+}
+
+/// Format a time as an HTTP date in the IMF-fixdate format, such as
+/// `Sun, 06 Nov 1994 08:49:37 GMT`.
+///
+/// Fractional seconds are dropped. This is the same as
+/// [`SystemTime::to_http_date`].
+pub fn format_http_date(time: SystemTime) -> String {
+    time.to_http_date()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXPECTED: u64 = 784_111_777;
+
+    #[test]
+    fn imf_fixdate() {
+        let date = parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(date.unix_seconds(), EXPECTED);
+        assert_eq!(format_http_date(date), "Sun, 06 Nov 1994 08:49:37 GMT");
+    }
+
+    #[test]
+    fn rfc850() {
+        let date = parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT").unwrap();
+        assert_eq!(date.unix_seconds(), EXPECTED);
+        assert!(parse_http_date("Sunday, 06-Nov-1994 08:49:37 GMT").is_none());
+        assert!(parse_http_date("Sunday, 06-Nov-94 08:49:37 UTC").is_none());
+    }
+
+    #[test]
+    fn rfc850_two_digit_years() {
+        // Two-digit years are at most 50 years in the future.
+        let rfc850 = "Sunday, 06-Nov-94 08:49:37 GMT";
+        assert_eq!(utils::parse_http_date(rfc850, 2024), Some(EXPECTED));
+        assert_eq!(utils::parse_http_date(rfc850, 2043), Some(EXPECTED));
+        assert_eq!(
+            utils::parse_http_date("Thursday, 06-Nov-70 00:00:00 GMT", 2024),
+            utils::parse_http_date("Thu, 06 Nov 2070 00:00:00 GMT", 2024),
+        );
+        assert_eq!(
+            utils::parse_http_date("Thursday, 01-Jan-70 00:00:00 GMT", 2019),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for secs in [0, EXPECTED, 951_782_400, 1_709_164_799] {
+            let date = SystemTime::from_unix_seconds(secs).to_http_date();
+            let parsed = parse_http_date(&date).map(|date| date.unix_seconds());
+            assert_eq!(parsed, Some(secs), "{date}");
+        }
+    }
+
+    #[test]
+    fn asctime() {
+        let date = parse_http_date("Sun Nov  6 08:49:37 1994").unwrap();
+        assert_eq!(date.unix_seconds(), EXPECTED);
+        let date = parse_http_date("Fri Nov 25 08:49:37 1994").unwrap();
+        assert_eq!(date.unix_seconds(), EXPECTED + 19 * 86_400);
+        assert!(parse_http_date("Sun Nov 6 08:49:37 1994").is_none());
+        assert!(parse_http_date("Sun Nov  6 08:49:37 94").is_none());
+    }
+
+    #[test]
+    fn leap_days() {
+        assert!(parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT").is_some());
+        assert!(parse_http_date("Tue, 29 Feb 2000 00:00:00 GMT").is_some());
+        assert!(parse_http_date("Wed, 30 Apr 2024 00:00:00 GMT").is_some());
+    }
+
+    #[test]
+    fn invalid() {
+        for s in [
+            "",
+            "yesterday",
+            "Sun, 06 Nov 1994 08:49:37",
+            "Sun, 06 Nov 1994 08:49 GMT",
+            "Sun, 06 Nov 1994 08:49:37 UTC",
+            "Sun, 6 Nov 1994 08:49:37 GMT",
+            "Sun, 06 Nov 1994 24:00:00 GMT",
+            "Sun, 32 Nov 1994 08:49:37 GMT",
+            "Sun, 31 Nov 1994 08:49:37 GMT",
+            "Sat, 31 Feb 2024 00:00:00 GMT",
+            "Sat, 30 Feb 2024 00:00:00 GMT",
+            "Thu, 29 Feb 2023 00:00:00 GMT",
+            "Thu, 29 Feb 2100 00:00:00 GMT",
+            "Thu, 31 Apr 2024 00:00:00 GMT",
+            "Thursday, 31-Apr-24 00:00:00 GMT",
+            "Thu Apr 31 00:00:00 2024",
+            "Sun, 06 Foo 1994 08:49:37 GMT",
+            "Sun, 06 Nov 1969 08:49:37 GMT",
+        ] {
+            assert!(parse_http_date(s).is_none(), "{s:?}");
+        }
+    }
+}
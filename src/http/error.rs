@@ -25,6 +25,8 @@ impl fmt::Debug for Error {
             ErrorVariant::HeaderValue(e) => write!(f, "header value error: {e:?}"),
             ErrorVariant::Method(e) => write!(f, "method error: {e:?}"),
             ErrorVariant::BodyIo(e) => write!(f, "body error: {e:?}"),
+            ErrorVariant::PayloadTooLarge(e) => write!(f, "{e:?}"),
+            ErrorVariant::HeadersTooLarge(e) => write!(f, "{e:?}"),
             ErrorVariant::Other(e) => write!(f, "{e}"),
         }
     }
@@ -39,6 +41,8 @@ impl fmt::Display for Error {
             ErrorVariant::HeaderValue(e) => write!(f, "header value error: {e}"),
             ErrorVariant::Method(e) => write!(f, "method error: {e}"),
             ErrorVariant::BodyIo(e) => write!(f, "body error: {e}"),
+            ErrorVariant::PayloadTooLarge(e) => write!(f, "{e}"),
+            ErrorVariant::HeadersTooLarge(e) => write!(f, "{e}"),
             ErrorVariant::Other(e) => write!(f, "{e}"),
         }
     }
@@ -50,6 +54,18 @@ impl Error {
     pub fn variant(&self) -> &ErrorVariant {
         &self.variant
     }
+    /// Returns `true` if the host failed to establish a TLS connection, for
+    /// example because it didn't accept the server's certificate.
+    pub fn is_tls(&self) -> bool {
+        matches!(
+            self.variant,
+            ErrorVariant::WasiHttp(
+                WasiHttpErrorCode::TlsProtocolError
+                    | WasiHttpErrorCode::TlsCertificateError
+                    | WasiHttpErrorCode::TlsAlertReceived(_)
+            )
+        )
+    }
     pub(crate) fn other(s: impl Into<String>) -> Self {
         ErrorVariant::Other(s.into()).into()
     }
@@ -116,5 +132,7 @@ pub enum ErrorVariant {
     HeaderValue(InvalidHeaderValue),
     Method(InvalidMethod),
     BodyIo(std::io::Error),
+    PayloadTooLarge(super::body::PayloadTooLarge),
+    HeadersTooLarge(super::fields::HeadersTooLarge),
     Other(String),
 }
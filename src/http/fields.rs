@@ -1,15 +1,61 @@
 pub use http::header::{HeaderMap, HeaderName, HeaderValue};
 
-use super::{Error, Result};
+use super::{
+    body::InvalidContentLength, error::ErrorVariant, Error, Mime, Request, Response, Result,
+};
+use http::header::{AsHeaderName, CONTENT_LENGTH, CONTENT_TYPE};
+use std::fmt;
 use wasi::http::types::Fields;
 
+// Converting between `HeaderMap` and wasi `Fields` keeps every value of a
+// header that appears more than once, e.g. `Set-Cookie` or `Via`, in the order
+// they were given. `HeaderMap` groups all values of a header together, so the
+// relative order of *different* headers is not preserved, and header names are
+// normalized to lowercase; neither is significant in HTTP.
+
 pub(crate) fn header_map_from_wasi(wasi_fields: Fields) -> Result<HeaderMap> {
+    header_map_from_wasi_limited(wasi_fields, &HeaderLimits::default())
+}
+
+/// Optional limits on the size of incoming headers.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct HeaderLimits {
+    /// The maximum sum of the lengths of all header names and values.
+    pub(crate) max_total_size: Option<usize>,
+    /// The maximum length of a single header value.
+    pub(crate) max_value_len: Option<usize>,
+}
+
+pub(crate) fn header_map_from_wasi_limited(
+    wasi_fields: Fields,
+    limits: &HeaderLimits,
+) -> Result<HeaderMap> {
+    let entries = wasi_fields.entries();
+    let mut total = 0usize;
+    for (key, value) in &entries {
+        if let Some(limit) = limits.max_value_len {
+            if value.len() > limit {
+                return Err(HeadersTooLarge {
+                    limit,
+                    name: Some(key.clone()),
+                }
+                .into());
+            }
+        }
+        total = total.saturating_add(key.len() + value.len());
+        if let Some(limit) = limits.max_total_size {
+            if total > limit {
+                return Err(HeadersTooLarge { limit, name: None }.into());
+            }
+        }
+    }
+
     let mut output = HeaderMap::new();
-    for (key, value) in wasi_fields.entries() {
+    for (key, value) in entries {
         let key = HeaderName::from_bytes(key.as_bytes())
-            .map_err(|e| Error::from(e).context("header name {key}"))?;
+            .map_err(|e| Error::from(e).context(format!("header name {key}")))?;
         let value = HeaderValue::from_bytes(&value)
-            .map_err(|e| Error::from(e).context("header value for {key}"))?;
+            .map_err(|e| Error::from(e).context(format!("header value for {key}")))?;
         output.append(key, value);
     }
     Ok(output)
@@ -20,7 +66,210 @@ pub(crate) fn header_map_to_wasi(header_map: &HeaderMap) -> Result<Fields> {
     for (key, value) in header_map {
         wasi_fields
             .append(&key.as_str().to_owned(), &value.as_bytes().to_owned())
-            .map_err(|e| Error::from(e).context("header named {key}"))?;
+            .map_err(|e| Error::from(e).context(format!("header named {key}")))?;
     }
     Ok(wasi_fields)
 }
+
+/// Parse the `Content-Length` header, following RFC 9110 section 8.6.
+///
+/// The header may appear multiple times, or hold a comma-separated list, as
+/// long as every value is identical.
+pub(crate) fn parse_content_length(
+    headers: &HeaderMap,
+) -> std::result::Result<Option<u64>, InvalidContentLength> {
+    let mut content_length = None;
+    for value in headers.get_all(CONTENT_LENGTH) {
+        let value = value.to_str().map_err(|_| InvalidContentLength)?;
+        for value in value.split(',') {
+            let value = value.trim();
+            // `u64::from_str` accepts a leading `+`, which HTTP doesn't allow.
+            if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(InvalidContentLength);
+            }
+            let value = value.parse().map_err(|_| InvalidContentLength)?;
+            match content_length {
+                Some(prev) if prev != value => return Err(InvalidContentLength),
+                _ => content_length = Some(value),
+            }
+        }
+    }
+    Ok(content_length)
+}
+
+/// Convenience accessors for commonly used headers.
+///
+/// This is implemented for [`HeaderMap`], as well as for [`Request`] and
+/// [`Response`](super::Response) so headers can be read without first calling
+/// `.headers()`.
+pub trait HeadersExt {
+    /// Returns the headers to read from.
+    fn header_map(&self) -> &HeaderMap;
+
+    /// Returns the first value of the header `name`, decoded as a string.
+    ///
+    /// Returns `None` if the header is absent, or if its value is not
+    /// visible ASCII.
+    fn header_str<K: AsHeaderName>(&self, name: K) -> Option<&str> {
+        self.header_map().get(name)?.to_str().ok()
+    }
+
+    /// Returns the value of the `Content-Type` header.
+    fn content_type(&self) -> Option<&str> {
+        self.header_str(CONTENT_TYPE)
+    }
+
+    /// Returns the value of the `Content-Type` header, parsed as a [`Mime`].
+    ///
+    /// Returns `None` if the header is absent or is not a valid media type.
+    fn content_type_mime(&self) -> Option<Mime> {
+        self.content_type()?.parse().ok()
+    }
+
+    /// Returns the value of the `Content-Length` header.
+    ///
+    /// Returns `None` if the header is absent, if it is not a valid
+    /// non-negative integer, or if it is given multiple times with
+    /// conflicting values.
+    fn content_length(&self) -> Option<u64> {
+        parse_content_length(self.header_map()).ok().flatten()
+    }
+}
+
+impl HeadersExt for HeaderMap {
+    fn header_map(&self) -> &HeaderMap {
+        self
+    }
+}
+
+impl<B> HeadersExt for Request<B> {
+    fn header_map(&self) -> &HeaderMap {
+        self.headers()
+    }
+}
+
+impl<B> HeadersExt for Response<B> {
+    fn header_map(&self) -> &HeaderMap {
+        self.headers()
+    }
+}
+
+/// Incoming headers were larger than the configured limit.
+///
+/// Returned when receiving a response whose headers exceed the limits set
+/// with [`ClientBuilder::max_header_size`] or
+/// [`ClientBuilder::max_header_value_len`]. A server would answer such a
+/// request with [`431 Request Header Fields Too Large`].
+///
+/// [`ClientBuilder::max_header_size`]: super::ClientBuilder::max_header_size
+/// [`ClientBuilder::max_header_value_len`]: super::ClientBuilder::max_header_value_len
+/// [`431 Request Header Fields Too Large`]: super::StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadersTooLarge {
+    limit: usize,
+    /// The header whose value was too long, or `None` if all headers
+    /// together were too large.
+    name: Option<String>,
+}
+
+impl HeadersTooLarge {
+    /// The limit, in bytes, which was exceeded.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The name of the header whose value exceeded the limit on a single
+    /// value, or `None` if the limit on the total size was exceeded.
+    pub fn header_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
+
+impl fmt::Display for HeadersTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(
+                f,
+                "value of header {name} exceeds the limit of {} bytes",
+                self.limit
+            ),
+            None => write!(f, "headers exceed the limit of {} bytes", self.limit),
+        }
+    }
+}
+
+impl std::error::Error for HeadersTooLarge {}
+
+impl From<HeadersTooLarge> for Error {
+    fn from(e: HeadersTooLarge) -> Self {
+        ErrorVariant::HeadersTooLarge(e).into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip_repeated_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append("set-cookie", HeaderValue::from_static("a=1"));
+        headers.append("via", HeaderValue::from_static("1.1 first"));
+        headers.append("set-cookie", HeaderValue::from_static("b=2; Path=/"));
+        headers.append("via", HeaderValue::from_static("1.1 second"));
+        headers.append("set-cookie", HeaderValue::from_static("c=3"));
+
+        let fields = header_map_to_wasi(&headers).unwrap();
+        assert_eq!(
+            fields.get(&"set-cookie".to_owned()),
+            [b"a=1".to_vec(), b"b=2; Path=/".to_vec(), b"c=3".to_vec()]
+        );
+
+        let round_tripped = header_map_from_wasi(fields).unwrap();
+        assert_eq!(round_tripped, headers);
+        let cookies: Vec<_> = round_tripped.get_all("set-cookie").iter().collect();
+        assert_eq!(cookies, ["a=1", "b=2; Path=/", "c=3"]);
+        let via: Vec<_> = round_tripped.get_all("via").iter().collect();
+        assert_eq!(via, ["1.1 first", "1.1 second"]);
+    }
+
+    fn oversized() -> Fields {
+        let mut headers = HeaderMap::new();
+        headers.append("x-small", HeaderValue::from_static("abc"));
+        headers.append("x-large", HeaderValue::from_str(&"x".repeat(100)).unwrap());
+        header_map_to_wasi(&headers).unwrap()
+    }
+
+    fn too_large(limits: HeaderLimits) -> Option<HeadersTooLarge> {
+        match header_map_from_wasi_limited(oversized(), &limits) {
+            Ok(_) => None,
+            Err(err) => match err.variant() {
+                ErrorVariant::HeadersTooLarge(e) => Some(e.clone()),
+                other => panic!("unexpected error {other:?}"),
+            },
+        }
+    }
+
+    #[test]
+    fn header_limits() {
+        assert_eq!(too_large(HeaderLimits::default()), None);
+
+        let err = too_large(HeaderLimits {
+            max_value_len: Some(99),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(err.limit(), 99);
+        assert_eq!(err.header_name(), Some("x-large"));
+
+        // "x-small" + "abc" + "x-large" + 100 bytes = 117 bytes in total.
+        let total = |limit| HeaderLimits {
+            max_total_size: Some(limit),
+            ..Default::default()
+        };
+        assert_eq!(too_large(total(117)), None);
+        let err = too_large(total(116)).unwrap();
+        assert_eq!(err.header_name(), None);
+        assert_eq!(err.to_string(), "headers exceed the limit of 116 bytes");
+    }
+}
@@ -0,0 +1,209 @@
+use super::multipart::{header_param, Multipart};
+use super::{percent::decode_query, Body, Error, HeadersExt, Request, Response, Result};
+
+const FORM_URLENCODED: &str = "application/x-www-form-urlencoded";
+const MULTIPART_FORM_DATA: &str = "multipart/form-data";
+
+/// Read form bodies, as submitted by HTML forms.
+///
+/// This is implemented for [`Request`] and [`Response`] with any [`Body`].
+/// All methods fail without reading anything if the `Content-Type` header is
+/// missing or names another media type.
+pub trait FormExt {
+    /// The type of the message body.
+    type Body: Body;
+
+    /// Parse a `multipart/form-data` body, as used to upload files.
+    ///
+    /// The boundary separating the parts is taken from the `Content-Type`
+    /// header. The parts are parsed as they are read, see [`Multipart`].
+    fn multipart(&mut self) -> Result<Multipart<&mut Self::Body>>;
+
+    /// Read the body and decode it into a list of name-value pairs, in the
+    /// order they appear.
+    ///
+    /// Names and values are percent-decoded, with `+` decoded as a space.
+    /// Fields without an `=` get an empty value.
+    async fn form_pairs(&mut self) -> Result<Vec<(String, String)>>;
+
+    /// Read the body and deserialize it into `T` using `serde_urlencoded`.
+    #[cfg(feature = "form")]
+    async fn form<T: serde::de::DeserializeOwned>(&mut self) -> Result<T>;
+}
+
+impl<B: Body> FormExt for Request<B> {
+    type Body = B;
+
+    fn multipart(&mut self) -> Result<Multipart<&mut B>> {
+        let boundary = multipart_boundary(self.content_type())?.to_owned();
+        Ok(Multipart::new(self.body_mut(), &boundary))
+    }
+
+    async fn form_pairs(&mut self) -> Result<Vec<(String, String)>> {
+        check_content_type(self.content_type(), FORM_URLENCODED)?;
+        let body = read_body(self.body_mut()).await?;
+        parse_pairs(&body)
+    }
+
+    #[cfg(feature = "form")]
+    async fn form<T: serde::de::DeserializeOwned>(&mut self) -> Result<T> {
+        check_content_type(self.content_type(), FORM_URLENCODED)?;
+        let body = read_body(self.body_mut()).await?;
+        deserialize(&body)
+    }
+}
+
+impl<B: Body> FormExt for Response<B> {
+    type Body = B;
+
+    fn multipart(&mut self) -> Result<Multipart<&mut B>> {
+        let boundary = multipart_boundary(self.content_type())?.to_owned();
+        Ok(Multipart::new(self.body_mut(), &boundary))
+    }
+
+    async fn form_pairs(&mut self) -> Result<Vec<(String, String)>> {
+        check_content_type(self.content_type(), FORM_URLENCODED)?;
+        let body = read_body(self.body_mut()).await?;
+        parse_pairs(&body)
+    }
+
+    #[cfg(feature = "form")]
+    async fn form<T: serde::de::DeserializeOwned>(&mut self) -> Result<T> {
+        check_content_type(self.content_type(), FORM_URLENCODED)?;
+        let body = read_body(self.body_mut()).await?;
+        deserialize(&body)
+    }
+}
+
+fn check_content_type(content_type: Option<&str>, expected: &str) -> Result<()> {
+    // Only the media type matters; parameters such as `charset` are ignored.
+    let media_type = content_type.map(|ct| ct.split(';').next().unwrap_or_default().trim());
+    match media_type {
+        Some(media_type) if media_type.eq_ignore_ascii_case(expected) => Ok(()),
+        Some(media_type) => Err(Error::other(format!(
+            "expected content-type {expected}, found {media_type}"
+        ))),
+        None => Err(Error::other(format!(
+            "expected content-type {expected}, found none"
+        ))),
+    }
+}
+
+fn multipart_boundary(content_type: Option<&str>) -> Result<&str> {
+    check_content_type(content_type, MULTIPART_FORM_DATA)?;
+    // RFC 2046 allows boundaries of 1 to 70 characters.
+    match content_type.and_then(|ct| header_param(ct, "boundary")) {
+        Some(boundary) if (1..=70).contains(&boundary.len()) => Ok(boundary),
+        _ => Err(Error::other(
+            "multipart content-type without a valid boundary",
+        )),
+    }
+}
+
+async fn read_body<B: Body>(body: &mut B) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    body.read_to_end(&mut buf).await?;
+    Ok(buf)
+}
+
+fn parse_pairs(body: &[u8]) -> Result<Vec<(String, String)>> {
+    let body = std::str::from_utf8(body).map_err(|_| Error::other("form body is not UTF-8"))?;
+    body.split('&')
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            let (name, value) = field.split_once('=').unwrap_or((field, ""));
+            let decoded = decode_query(name).and_then(|name| Ok((name, decode_query(value)?)));
+            decoded.map_err(|e| Error::other(e.to_string()).context(format!("form field {field}")))
+        })
+        .collect()
+}
+
+#[cfg(feature = "form")]
+fn deserialize<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T> {
+    serde_urlencoded::from_bytes(body).map_err(|e| Error::other(e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::http::IntoBody;
+    use crate::runtime::block_on;
+
+    fn request(content_type: Option<&str>, body: &str) -> Request<impl Body> {
+        let mut builder = Request::post("/submit");
+        if let Some(content_type) = content_type {
+            builder = builder.header("content-type", content_type);
+        }
+        builder.body(body.to_owned().into_body()).unwrap()
+    }
+
+    #[test]
+    fn form_pairs() {
+        let mut req = request(
+            Some("application/x-www-form-urlencoded; charset=utf-8"),
+            "name=J%C3%BCrgen+Doe&empty=&flag&&tag=a&tag=b",
+        );
+        let pairs = block_on(req.form_pairs()).unwrap();
+        let expected = [
+            ("name", "Jürgen Doe"),
+            ("empty", ""),
+            ("flag", ""),
+            ("tag", "a"),
+            ("tag", "b"),
+        ];
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn form_content_type_mismatch() {
+        let mut req = request(Some("application/json"), "{}");
+        let err = block_on(req.form_pairs()).unwrap_err();
+        assert!(err.to_string().contains("application/json"), "{err}");
+        let mut req = request(None, "a=b");
+        assert!(block_on(req.form_pairs()).is_err());
+    }
+
+    #[test]
+    fn form_invalid_encoding() {
+        let mut req = request(Some(FORM_URLENCODED), "a=%zz");
+        assert!(block_on(req.form_pairs()).is_err());
+    }
+
+    #[cfg(feature = "form")]
+    #[test]
+    fn form_deserialize() {
+        use std::collections::HashMap;
+        let mut req = request(Some(FORM_URLENCODED), "name=Jane+Doe&age=42");
+        let form: HashMap<String, String> = block_on(req.form()).unwrap();
+        assert_eq!(form["name"], "Jane Doe");
+        assert_eq!(form["age"], "42");
+    }
+
+    #[test]
+    fn multipart_from_content_type() {
+        use crate::io::AsyncRead;
+        let body = "--b0undary\r\n\
+            Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+            one\r\n\
+            --b0undary--\r\n";
+        let mut req = request(Some("multipart/form-data; boundary=\"b0undary\""), body);
+        block_on(async {
+            let mut multipart = req.multipart().unwrap();
+            let mut part = multipart.next_part().await.unwrap().unwrap();
+            assert_eq!(part.name(), Some("a"));
+            let mut buf = Vec::new();
+            part.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(buf, b"one");
+            assert!(multipart.next_part().await.unwrap().is_none());
+        });
+
+        let mut req = request(Some("multipart/form-data"), body);
+        assert!(req.multipart().is_err(), "missing boundary");
+        let mut req = request(Some(FORM_URLENCODED), body);
+        assert!(req.multipart().is_err(), "wrong media type");
+    }
+}
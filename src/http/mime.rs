@@ -0,0 +1,219 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed media type, as found in the `Content-Type` header.
+///
+/// The type, subtype, and parameter names are case-insensitive, so they are
+/// stored in lowercase. Parameter values keep their case, and quoted values
+/// are unquoted. See
+/// [RFC 9110, section 8.3.1](https://www.rfc-editor.org/rfc/rfc9110#section-8.3.1).
+///
+/// # Example
+///
+/// ```
+/// use wstd::http::Mime;
+///
+/// let mime: Mime = "Text/HTML; Charset=\"UTF-8\"".parse().unwrap();
+/// assert_eq!(mime.type_(), "text");
+/// assert_eq!(mime.subtype(), "html");
+/// assert_eq!(mime.essence(), "text/html");
+/// assert_eq!(mime.get_param("charset"), Some("UTF-8"));
+/// assert_eq!(mime.to_string(), "text/html; charset=UTF-8");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mime {
+    /// `type/subtype`, in lowercase.
+    essence: String,
+    slash: usize,
+    params: Vec<(String, String)>,
+}
+
+impl Mime {
+    /// The top-level type, such as `text` in `text/html`.
+    pub fn type_(&self) -> &str {
+        &self.essence[..self.slash]
+    }
+
+    /// The subtype, such as `html` in `text/html`.
+    pub fn subtype(&self) -> &str {
+        &self.essence[self.slash + 1..]
+    }
+
+    /// The type and subtype without parameters, such as `text/html`.
+    pub fn essence(&self) -> &str {
+        &self.essence
+    }
+
+    /// Returns the value of the parameter `name`, compared
+    /// case-insensitively.
+    ///
+    /// If the parameter is given more than once, the first value is returned.
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns an iterator over the parameters, in the order they were given.
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+}
+
+impl fmt::Display for Mime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.essence)?;
+        for (key, value) in &self.params {
+            if !value.is_empty() && value.bytes().all(is_token) {
+                write!(f, "; {key}={value}")?;
+            } else {
+                write!(f, "; {key}=\"")?;
+                for c in value.chars() {
+                    if c == '"' || c == '\\' {
+                        f.write_str("\\")?;
+                    }
+                    write!(f, "{c}")?;
+                }
+                f.write_str("\"")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Mime {
+    type Err = InvalidMime;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (essence, mut rest) = s.split_once(';').unwrap_or((s, ""));
+        let (type_, subtype) = essence.trim().split_once('/').ok_or(InvalidMime)?;
+        if !is_token_str(type_) || !is_token_str(subtype) {
+            return Err(InvalidMime);
+        }
+        let mut params = Vec::new();
+        loop {
+            rest = rest.trim_start_matches([' ', '\t', ';']);
+            if rest.is_empty() {
+                break;
+            }
+            let (name, value) = rest.split_once('=').ok_or(InvalidMime)?;
+            if !is_token_str(name) {
+                return Err(InvalidMime);
+            }
+            let (value, after) = match value.strip_prefix('"') {
+                Some(quoted) => parse_quoted(quoted)?,
+                None => {
+                    let end = value.find(';').unwrap_or(value.len());
+                    let token = value[..end].trim_end();
+                    if !is_token_str(token) {
+                        return Err(InvalidMime);
+                    }
+                    (token.to_owned(), &value[end..])
+                }
+            };
+            // Only whitespace may come between a value and the next `;`.
+            rest = after.trim_start();
+            if !rest.is_empty() && !rest.starts_with(';') {
+                return Err(InvalidMime);
+            }
+            params.push((name.to_ascii_lowercase(), value));
+        }
+        Ok(Self {
+            essence: format!("{type_}/{subtype}").to_ascii_lowercase(),
+            slash: type_.len(),
+            params,
+        })
+    }
+}
+
+/// The string was not a valid media type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidMime;
+
+impl fmt::Display for InvalidMime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid media type")
+    }
+}
+
+impl std::error::Error for InvalidMime {}
+
+/// Parses the rest of a quoted string after its opening quote, returning the
+/// unescaped value along with what follows the closing quote.
+fn parse_quoted(s: &str) -> Result<(String, &str), InvalidMime> {
+    let mut value = String::new();
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &s[i + 1..])),
+            '\\' => value.push(chars.next().ok_or(InvalidMime)?.1),
+            c => value.push(c),
+        }
+    }
+    Err(InvalidMime)
+}
+
+fn is_token_str(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(is_token)
+}
+
+/// tchar, from RFC 9110, section 5.6.2.
+fn is_token(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mime(s: &str) -> Mime {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let m = mime("Application/JSON");
+        assert_eq!(m.type_(), "application");
+        assert_eq!(m.subtype(), "json");
+        assert_eq!(m, mime("application/json"));
+        assert_eq!(
+            mime("text/plain; CHARSET=utf-8").get_param("Charset"),
+            Some("utf-8")
+        );
+    }
+
+    #[test]
+    fn quoted_params() {
+        let m = mime(r#"multipart/form-data; boundary="a; b=\"c\"" ; charset=utf-8;"#);
+        assert_eq!(m.essence(), "multipart/form-data");
+        assert_eq!(m.get_param("boundary"), Some(r#"a; b="c""#));
+        assert_eq!(m.get_param("charset"), Some("utf-8"));
+        assert_eq!(
+            m.to_string(),
+            r#"multipart/form-data; boundary="a; b=\"c\""; charset=utf-8"#
+        );
+        assert_eq!(mime(&m.to_string()), m);
+        assert_eq!(mime(r#"text/plain; x="""#).get_param("x"), Some(""));
+    }
+
+    #[test]
+    fn invalid() {
+        for s in [
+            "",
+            "text",
+            "text/",
+            "/html",
+            "text/html/x",
+            "text/html; charset",
+            "text/html; charset=\"utf-8",
+            "text/html; charset=\"utf-8\"x",
+            "text/html; charset=a b",
+            "te xt/html",
+        ] {
+            assert_eq!(s.parse::<Mime>(), Err(InvalidMime), "{s}");
+        }
+    }
+}
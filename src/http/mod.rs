@@ -5,18 +5,44 @@ pub use http::uri::Uri;
 
 #[doc(inline)]
 pub use body::{Body, IntoBody};
-pub use client::Client;
+pub use client::{Client, ClientBuilder, RequestOption, DEFAULT_USER_AGENT};
+pub use conditional::{ETag, InvalidETag};
+pub use cookie::{parse_cookie_header, Cookie, SameSite};
+pub use date::{format_http_date, parse_http_date};
 pub use error::{Error, Result};
-pub use fields::{HeaderMap, HeaderName, HeaderValue};
+pub use fields::{HeaderMap, HeaderName, HeaderValue, HeadersExt, HeadersTooLarge};
+pub use form::FormExt;
 pub use method::Method;
-pub use request::Request;
-pub use response::Response;
+pub use mime::{InvalidMime, Mime};
+pub use multipart::{Multipart, Part};
+pub use percent::{
+    decode_query, decode_uri_component, encode_path, encode_query, encode_uri_component,
+    InvalidPercentEncoding,
+};
+pub use problem::Problem;
+pub use range::RangeReader;
+pub use request::{Request, RequestExt};
+pub use response::{Response, ResponseExt};
 
 pub mod body;
+pub mod cors;
 
+mod chunked;
 mod client;
+mod conditional;
+mod cookie;
+mod date;
 pub mod error;
 mod fields;
+mod form;
 mod method;
+mod mime;
+mod multipart;
+mod percent;
+mod problem;
+mod range;
 mod request;
 mod response;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+pub mod tunnel;
@@ -0,0 +1,361 @@
+use super::{HeaderMap, HeaderName, HeaderValue};
+use crate::io::{self, AsyncRead};
+use http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+
+/// The longest header line in a part we accept, to bound how much a peer can
+/// make us buffer.
+const MAX_LINE_LEN: usize = 8 * 1024;
+/// The most headers a single part may have.
+const MAX_HEADERS: usize = 64;
+const READ_AHEAD: usize = 8 * 1024;
+
+/// A streaming parser for `multipart/form-data` bodies, as submitted by HTML
+/// forms with file uploads.
+///
+/// Parts are read one at a time with [`next_part`](Multipart::next_part).
+/// Each [`Part`] exposes its headers and implements [`AsyncRead`] for its
+/// body, which is read straight from the underlying reader, so large uploads
+/// are never buffered in full. Because a part borrows the parser, this is a
+/// method rather than an [`AsyncIterator`](crate::iter::AsyncIterator).
+///
+/// A `Multipart` is usually created with
+/// [`FormExt::multipart`](super::FormExt::multipart), which takes the boundary
+/// from the `Content-Type` header.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::http::{FormExt, Request, body::IncomingBody};
+/// use wstd::io::AsyncRead;
+///
+/// # async fn handle(mut request: Request<IncomingBody>) -> anyhow::Result<()> {
+/// let mut multipart = request.multipart()?;
+/// while let Some(mut part) = multipart.next_part().await? {
+///     println!("field {:?}, file {:?}", part.name(), part.filename());
+///     let mut contents = Vec::new();
+///     part.read_to_end(&mut contents).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Multipart<R> {
+    reader: R,
+    /// `\r\n--` followed by the boundary.
+    delimiter: Vec<u8>,
+    buf: Vec<u8>,
+    pos: usize,
+    state: State,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the first delimiter, or in the body of a part.
+    Body,
+    /// Right after a delimiter.
+    Delimiter,
+    Done,
+}
+
+impl<R: AsyncRead> Multipart<R> {
+    /// Create a parser for a multipart body read from `reader`, with parts
+    /// separated by `boundary`.
+    pub fn new(reader: R, boundary: &str) -> Self {
+        let mut delimiter = b"\r\n--".to_vec();
+        delimiter.extend_from_slice(boundary.as_bytes());
+        Self {
+            reader,
+            delimiter,
+            // The first delimiter needn't be preceded by a line break, so
+            // pretend there is one: then every delimiter looks the same.
+            buf: b"\r\n".to_vec(),
+            pos: 0,
+            state: State::Body,
+        }
+    }
+
+    /// Returns the next part, or `None` after the last one.
+    ///
+    /// If the body of the previous part wasn't read to the end, the rest of it
+    /// is skipped.
+    pub async fn next_part(&mut self) -> io::Result<Option<Part<'_, R>>> {
+        // Skip the preamble, or whatever is left of the previous part.
+        let mut scratch = [0; 1024];
+        while self.state == State::Body {
+            self.read_body(&mut scratch).await?;
+        }
+        if self.state == State::Done {
+            return Ok(None);
+        }
+
+        // The delimiter is followed either by `--` for the last one, or by
+        // optional whitespace and a line break.
+        let line = self.read_line().await?;
+        if line.starts_with(b"--") {
+            self.state = State::Done;
+            return Ok(None);
+        }
+        if !line.iter().all(|b| *b == b' ' || *b == b'\t') {
+            return Err(invalid_data("multipart boundary followed by garbage"));
+        }
+
+        let mut headers = HeaderMap::new();
+        loop {
+            let line = self.read_line().await?;
+            if line.is_empty() {
+                break;
+            }
+            if headers.len() == MAX_HEADERS {
+                return Err(invalid_data("too many headers in multipart part"));
+            }
+            let (name, value) = parse_header(&line)?;
+            headers.append(name, value);
+        }
+        self.state = State::Body;
+        Ok(Some(Part {
+            multipart: self,
+            headers,
+        }))
+    }
+
+    /// Consume the parser, returning the inner reader.
+    ///
+    /// The parser reads ahead, so bytes following the body may have been
+    /// consumed from the reader already; they are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads more data into the buffer, dropping what has been consumed.
+    /// Returns `false` at EOF.
+    async fn fill_buf(&mut self) -> io::Result<bool> {
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+        let len = self.buf.len();
+        self.buf.resize(len + READ_AHEAD, 0);
+        let n = self.reader.read(&mut self.buf[len..]).await?;
+        self.buf.truncate(len + n);
+        Ok(n > 0)
+    }
+
+    /// Reads from the body of the current part. Returns `0` once the next
+    /// delimiter has been reached, and moves on to `State::Delimiter`.
+    async fn read_body(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.state != State::Body || out.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let available = &self.buf[self.pos..];
+            if let Some(i) = find(available, &self.delimiter) {
+                if i == 0 {
+                    self.pos += self.delimiter.len();
+                    self.state = State::Delimiter;
+                    return Ok(0);
+                }
+                let n = i.min(out.len());
+                out[..n].copy_from_slice(&available[..n]);
+                self.pos += n;
+                return Ok(n);
+            }
+            // The end of the buffer might be the start of a delimiter, so
+            // hold back enough bytes to check once more data arrives.
+            let safe = available
+                .len()
+                .saturating_sub(self.delimiter.len() - 1)
+                .min(out.len());
+            if safe > 0 {
+                out[..safe].copy_from_slice(&available[..safe]);
+                self.pos += safe;
+                return Ok(safe);
+            }
+            if !self.fill_buf().await? {
+                return Err(unexpected_eof());
+            }
+        }
+    }
+
+    /// Reads a line terminated by CRLF, returning it without the terminator.
+    async fn read_line(&mut self) -> io::Result<Vec<u8>> {
+        loop {
+            let available = &self.buf[self.pos..];
+            if let Some(i) = find(available, b"\r\n") {
+                let line = available[..i].to_vec();
+                self.pos += i + 2;
+                return Ok(line);
+            }
+            if available.len() > MAX_LINE_LEN {
+                return Err(invalid_data("multipart header line too long"));
+            }
+            if !self.fill_buf().await? {
+                return Err(unexpected_eof());
+            }
+        }
+    }
+}
+
+/// A part of a multipart body.
+///
+/// This `struct` is created by the [`next_part`](Multipart::next_part) method
+/// on [`Multipart`]. Reading from it yields the body of the part.
+#[derive(Debug)]
+pub struct Part<'a, R> {
+    multipart: &'a mut Multipart<R>,
+    headers: HeaderMap,
+}
+
+impl<R> Part<'_, R> {
+    /// Returns the headers of this part.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the name of the form field, from the `Content-Disposition`
+    /// header.
+    pub fn name(&self) -> Option<&str> {
+        self.disposition_param("name")
+    }
+
+    /// Returns the name of the uploaded file, from the `Content-Disposition`
+    /// header.
+    pub fn filename(&self) -> Option<&str> {
+        self.disposition_param("filename")
+    }
+
+    /// Returns the value of the `Content-Type` header of this part.
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers.get(CONTENT_TYPE)?.to_str().ok()
+    }
+
+    fn disposition_param(&self, name: &str) -> Option<&str> {
+        let disposition = self.headers.get(CONTENT_DISPOSITION)?.to_str().ok()?;
+        header_param(disposition, name)
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for Part<'_, R> {
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.multipart.read_body(buf).await
+    }
+}
+
+/// Returns the value of the parameter `name` in a header value like
+/// `form-data; name="field"; filename="a.txt"`.
+pub(crate) fn header_param<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case(name) {
+            return None;
+        }
+        let value = value.trim();
+        Some(
+            value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value),
+        )
+    })
+}
+
+fn parse_header(line: &[u8]) -> io::Result<(HeaderName, HeaderValue)> {
+    let colon = line
+        .iter()
+        .position(|b| *b == b':')
+        .ok_or_else(|| invalid_data("multipart header without a colon"))?;
+    let name = HeaderName::from_bytes(&line[..colon])
+        .map_err(|_| invalid_data("invalid multipart header name"))?;
+    let value = HeaderValue::from_bytes(line[colon + 1..].trim_ascii())
+        .map_err(|_| invalid_data("invalid multipart header value"))?;
+    Ok((name, value))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+fn unexpected_eof() -> io::Error {
+    io::Error::new(
+        std::io::ErrorKind::UnexpectedEof,
+        "multipart body ended before the closing boundary",
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::ChunksReader;
+    use crate::runtime::block_on;
+
+    const BODY: &[u8] = b"preamble\r\n\
+        --XyZ\r\n\
+        Content-Disposition: form-data; name=\"title\"\r\n\
+        \r\n\
+        Hello, --XyZ world\r\n\
+        --XyZ\r\n\
+        Content-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        line one\r\nline two\r\n\
+        --XyZ--\r\n\
+        epilogue";
+
+    type Parsed = Vec<(Option<String>, Option<String>, Option<String>, Vec<u8>)>;
+
+    fn parse(data: &[u8], max: usize, read_bodies: bool) -> io::Result<Parsed> {
+        block_on(async {
+            let mut multipart = Multipart::new(ChunksReader::new(data.chunks(max)), "XyZ");
+            let mut parts = Vec::new();
+            while let Some(mut part) = multipart.next_part().await? {
+                let name = part.name().map(str::to_owned);
+                let filename = part.filename().map(str::to_owned);
+                let content_type = part.content_type().map(str::to_owned);
+                let mut body = Vec::new();
+                if read_bodies {
+                    part.read_to_end(&mut body).await?;
+                }
+                parts.push((name, filename, content_type, body));
+            }
+            Ok(parts)
+        })
+    }
+
+    #[test]
+    fn two_parts() {
+        for max in [1, 2, 5, 13, 4096] {
+            let parts = parse(BODY, max, true).unwrap();
+            assert_eq!(parts.len(), 2, "max {max}");
+            let (name, filename, content_type, body) = &parts[0];
+            assert_eq!(name.as_deref(), Some("title"));
+            assert_eq!(*filename, None);
+            assert_eq!(*content_type, None);
+            assert_eq!(body, b"Hello, --XyZ world");
+            let (name, filename, content_type, body) = &parts[1];
+            assert_eq!(name.as_deref(), Some("upload"));
+            assert_eq!(filename.as_deref(), Some("a.txt"));
+            assert_eq!(content_type.as_deref(), Some("text/plain"));
+            assert_eq!(body, b"line one\r\nline two");
+        }
+    }
+
+    #[test]
+    fn skips_unread_bodies() {
+        let parts = parse(BODY, 3, false).unwrap();
+        let names: Vec<_> = parts.iter().map(|p| p.0.as_deref()).collect();
+        assert_eq!(names, [Some("title"), Some("upload")]);
+    }
+
+    #[test]
+    fn truncated() {
+        use std::io::ErrorKind;
+        let truncated = &BODY[..BODY.len() - 20];
+        let err = parse(truncated, 4096, true).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let err = parse(b"--XyZ\r\nno colon\r\n\r\n", 4096, true).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,184 @@
+//! Percent-encoding, following the rules of RFC 3986.
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// Which bytes, besides the unreserved characters, to leave unencoded.
+#[derive(Clone, Copy)]
+enum Component {
+    /// Encode everything except unreserved characters.
+    Strict,
+    /// A URI path: `pchar` and `/` are allowed.
+    Path,
+    /// A key or value in a query string: like a path, plus `?`, but without
+    /// the `&`, `=`, and `+` separators used by query strings.
+    Query,
+}
+
+impl Component {
+    fn allows(self, byte: u8) -> bool {
+        let unreserved = byte.is_ascii_alphanumeric() || b"-._~".contains(&byte);
+        match self {
+            Component::Strict => unreserved,
+            Component::Path => unreserved || b"!$&'()*+,;=:@/".contains(&byte),
+            Component::Query => unreserved || b"!$'()*,;:@/?".contains(&byte),
+        }
+    }
+}
+
+fn encode(input: &str, component: Component) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let mut out = String::with_capacity(input.len());
+    for &byte in input.as_bytes() {
+        if component.allows(byte) {
+            out.push(byte as char);
+        } else {
+            out.push('%');
+            out.push(HEX[usize::from(byte >> 4)] as char);
+            out.push(HEX[usize::from(byte & 0xf)] as char);
+        }
+    }
+    out
+}
+
+fn decode(input: &str, plus_as_space: bool) -> Result<String, InvalidPercentEncoding> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.bytes();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'%' => {
+                let hi = bytes.next().and_then(hex_value);
+                let lo = bytes.next().and_then(hex_value);
+                match (hi, lo) {
+                    (Some(hi), Some(lo)) => out.push(hi << 4 | lo),
+                    _ => return Err(InvalidPercentEncoding),
+                }
+            }
+            b'+' if plus_as_space => out.push(b' '),
+            byte => out.push(byte),
+        }
+    }
+    String::from_utf8(out).map_err(|_| InvalidPercentEncoding)
+}
+
+/// Decodes a single path segment, leaving it as-is if it isn't valid
+/// percent-encoding.
+pub(crate) fn decode_path_segment(input: &str) -> Cow<'_, str> {
+    if !input.contains('%') {
+        return Cow::Borrowed(input);
+    }
+    match decode(input, false) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(InvalidPercentEncoding) => Cow::Borrowed(input),
+    }
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Percent-encode a string for use as any single URI component.
+///
+/// Every byte except the RFC 3986 unreserved characters (`A-Z`, `a-z`, `0-9`,
+/// `-`, `.`, `_` and `~`) is encoded, so the result can be safely placed
+/// anywhere in a URI. Non-ASCII characters are encoded as their UTF-8 bytes.
+///
+/// # Example
+///
+/// ```
+/// use wstd::http::encode_uri_component;
+///
+/// assert_eq!(encode_uri_component("a b/c?d"), "a%20b%2Fc%3Fd");
+/// ```
+pub fn encode_uri_component(input: &str) -> String {
+    encode(input, Component::Strict)
+}
+
+/// Percent-encode a string for use as a URI path.
+///
+/// Unlike [`encode_uri_component`], this leaves `/` and the other characters
+/// RFC 3986 allows in a path unencoded.
+pub fn encode_path(input: &str) -> String {
+    encode(input, Component::Path)
+}
+
+/// Percent-encode a string for use as a key or a value in a query string.
+///
+/// The `&`, `=` and `+` characters, which separate and encode query
+/// parameters, are encoded; other characters RFC 3986 allows in a query are
+/// left as-is.
+pub fn encode_query(input: &str) -> String {
+    encode(input, Component::Query)
+}
+
+/// Decode a percent-encoded URI component.
+///
+/// Fails if a `%` is not followed by two hex digits, or if the decoded bytes
+/// are not valid UTF-8.
+pub fn decode_uri_component(input: &str) -> Result<String, InvalidPercentEncoding> {
+    decode(input, false)
+}
+
+/// Decode a key or a value from a query string.
+///
+/// This is like [`decode_uri_component`], but additionally decodes `+` as a
+/// space, as is common for form-encoded query strings.
+pub fn decode_query(input: &str) -> Result<String, InvalidPercentEncoding> {
+    decode(input, true)
+}
+
+/// An error returned when decoding malformed percent-encoded input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidPercentEncoding;
+
+impl fmt::Display for InvalidPercentEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "invalid percent-encoding".fmt(f)
+    }
+}
+
+impl std::error::Error for InvalidPercentEncoding {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode_components() {
+        assert_eq!(encode_uri_component("AZaz09-._~"), "AZaz09-._~");
+        assert_eq!(encode_uri_component("a b&c=d/e"), "a%20b%26c%3Dd%2Fe");
+        assert_eq!(encode_path("/a b/c@d:e"), "/a%20b/c@d:e");
+        assert_eq!(encode_query("a b&c=d+e/f?"), "a%20b%26c%3Dd%2Be/f?");
+        assert_eq!(encode_uri_component("ü"), "%C3%BC");
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in [
+            "",
+            "plain",
+            "a b&c=d+e",
+            "100%",
+            "ü日本語🦀",
+            "/?#[]@!$&'()*+,;=",
+        ] {
+            assert_eq!(
+                decode_uri_component(&encode_uri_component(input)).unwrap(),
+                input
+            );
+            assert_eq!(decode_uri_component(&encode_path(input)).unwrap(), input);
+            assert_eq!(decode_query(&encode_query(input)).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(decode_uri_component("%e6%97%A5"), Ok("日".to_owned()));
+        assert_eq!(decode_query("a+b"), Ok("a b".to_owned()));
+        assert_eq!(decode_uri_component("a+b"), Ok("a+b".to_owned()));
+        assert!(decode_uri_component("%").is_err());
+        assert!(decode_uri_component("%4").is_err());
+        assert!(decode_uri_component("%zz").is_err());
+        assert!(decode_uri_component("%ff").is_err(), "invalid UTF-8");
+    }
+}
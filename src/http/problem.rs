@@ -0,0 +1,218 @@
+use super::body::BoundedBody;
+use super::{HeaderValue, IntoBody, Response, StatusCode};
+use http::header::CONTENT_TYPE;
+use std::fmt::Write;
+
+/// An error response body in the "problem details" format of [RFC 7807].
+///
+/// Problems are sent as `application/problem+json`, which gives clients a
+/// standard way to find out what went wrong:
+///
+/// ```json
+/// {"type":"about:blank","title":"Not Found","status":404,"detail":"no user with id 7"}
+/// ```
+///
+/// The title defaults to the status code's reason phrase. Fields specific to
+/// an application can be added with [`field`](Self::field) and
+/// [`field_json`](Self::field_json).
+///
+/// [RFC 7807]: https://www.rfc-editor.org/rfc/rfc7807
+///
+/// # Example
+///
+/// ```
+/// use wstd::http::{Problem, StatusCode};
+///
+/// let response = Problem::new(StatusCode::FORBIDDEN)
+///     .detail("your account doesn't have enough credit")
+///     .field_json("balance", "30")
+///     .into_response();
+/// assert_eq!(response.status(), StatusCode::FORBIDDEN);
+/// assert_eq!(response.headers()["content-type"], "application/problem+json");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    status: StatusCode,
+    type_uri: Option<String>,
+    title: Option<String>,
+    detail: Option<String>,
+    instance: Option<String>,
+    /// Extension members, with their values already encoded as JSON.
+    fields: Vec<(String, String)>,
+}
+
+impl Problem {
+    /// Create a problem with the given status code.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            type_uri: None,
+            title: None,
+            detail: None,
+            instance: None,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Set the `type` member, a URI identifying the kind of problem.
+    ///
+    /// Defaults to `about:blank`, meaning the problem has no meaning beyond
+    /// its status code.
+    pub fn type_uri(mut self, type_uri: impl Into<String>) -> Self {
+        self.type_uri = Some(type_uri.into());
+        self
+    }
+
+    /// Set the `title` member, a short summary of the kind of problem.
+    ///
+    /// Defaults to the status code's reason phrase, such as `Not Found`.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the `detail` member, an explanation specific to this occurrence
+    /// of the problem.
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Set the `instance` member, a URI identifying this occurrence of the
+    /// problem.
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Add an extension member with a string value.
+    pub fn field(mut self, name: impl Into<String>, value: &str) -> Self {
+        let mut json = String::new();
+        push_json_string(&mut json, value);
+        self.fields.push((name.into(), json));
+        self
+    }
+
+    /// Add an extension member whose value is already encoded as JSON, such
+    /// as a number, `true`, or an array.
+    ///
+    /// The value is included as-is, so it must be valid JSON.
+    pub fn field_json(mut self, name: impl Into<String>, json: impl Into<String>) -> Self {
+        self.fields.push((name.into(), json.into()));
+        self
+    }
+
+    /// The status code of the problem.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Encode the problem as a JSON object.
+    pub fn to_json(&self) -> String {
+        let type_uri = self.type_uri.as_deref().unwrap_or("about:blank");
+        let title = self
+            .title
+            .as_deref()
+            .or(self.status.canonical_reason())
+            .unwrap_or_default();
+
+        let mut json = String::from("{\"type\":");
+        push_json_string(&mut json, type_uri);
+        json.push_str(",\"title\":");
+        push_json_string(&mut json, title);
+        write!(json, ",\"status\":{}", self.status.as_u16()).unwrap();
+        for (name, value) in [("detail", &self.detail), ("instance", &self.instance)] {
+            if let Some(value) = value {
+                write!(json, ",\"{name}\":").unwrap();
+                push_json_string(&mut json, value);
+            }
+        }
+        for (name, value) in &self.fields {
+            json.push(',');
+            push_json_string(&mut json, name);
+            json.push(':');
+            json.push_str(value);
+        }
+        json.push('}');
+        json
+    }
+
+    /// Create a response with the problem's status code and an
+    /// `application/problem+json` body.
+    pub fn into_response(self) -> Response<BoundedBody<Vec<u8>>> {
+        let mut response = Response::new(self.to_json().into_bytes().into_body());
+        *response.status_mut() = self.status;
+        response.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/problem+json"),
+        );
+        response
+    }
+}
+
+/// Appends `s` to `out` as a quoted JSON string.
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let problem = Problem::new(StatusCode::NOT_FOUND);
+        assert_eq!(
+            problem.to_json(),
+            r#"{"type":"about:blank","title":"Not Found","status":404}"#
+        );
+    }
+
+    #[test]
+    fn members_and_fields() {
+        let problem = Problem::new(StatusCode::UNPROCESSABLE_ENTITY)
+            .type_uri("https://example.com/probs/invalid")
+            .title("Invalid input")
+            .detail("name must not contain \"quotes\"\nor newlines\u{1}")
+            .instance("/users/7")
+            .field("field", "name")
+            .field_json("limits", "[1,2]");
+        let json: serde_json::Value = serde_json::from_str(&problem.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "https://example.com/probs/invalid",
+                "title": "Invalid input",
+                "status": 422,
+                "detail": "name must not contain \"quotes\"\nor newlines\u{1}",
+                "instance": "/users/7",
+                "field": "name",
+                "limits": [1, 2],
+            })
+        );
+    }
+
+    #[test]
+    fn response() {
+        let response = Problem::new(StatusCode::BAD_GATEWAY).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/problem+json");
+        let len = crate::http::Body::len(response.body());
+        assert_eq!(
+            len,
+            Some(Problem::new(StatusCode::BAD_GATEWAY).to_json().len())
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use super::{body::IncomingBody, Client, HeadersExt, Request, StatusCode, Uri};
+use crate::io::{self, empty, AsyncRead, AsyncSeek, SeekFrom};
+use http::header::{ACCEPT_RANGES, CONTENT_RANGE, RANGE};
+use std::io::ErrorKind;
+
+/// Random access to a remote resource, using HTTP range requests.
+///
+/// A `RangeReader` behaves like a file opened for reading: it implements
+/// [`AsyncRead`] and [`AsyncSeek`], and fetches the bytes at the current
+/// position with a `GET` request carrying a `Range: bytes=<pos>-` header.
+/// Seeking is free until the next read, which then starts a new request at
+/// the new position. Reading sequentially reuses the response of the
+/// previous read.
+///
+/// # Status codes
+///
+/// A server which honors the range answers `206 Partial Content`, and its
+/// `Content-Range` must start at the requested position, or the read fails
+/// with [`ErrorKind::InvalidData`]. A server which doesn't support ranges
+/// answers `200 OK` with the whole resource instead. In that case the bytes
+/// before the requested position are read and discarded, so reading still
+/// works, just less efficiently. A `416 Range Not Satisfiable` answer means
+/// the position is at or past the end, and reads return `0`. Any other
+/// status fails the read.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::http::{Client, RangeReader};
+/// use wstd::io::{AsyncRead, AsyncSeek, SeekFrom};
+///
+/// # async fn run() -> std::io::Result<()> {
+/// let uri = "https://example.com/video.mp4".parse().unwrap();
+/// let mut reader = RangeReader::new(Client::new(), uri).await?;
+/// // Read the last kilobyte of the file.
+/// reader.seek(SeekFrom::End(-1024)).await?;
+/// let mut tail = Vec::new();
+/// reader.read_to_end(&mut tail).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RangeReader {
+    client: Client,
+    uri: Uri,
+    pos: u64,
+    len: Option<u64>,
+    accepts_ranges: bool,
+    /// The response currently being read, whose next byte is at `pos`.
+    body: Option<IncomingBody>,
+}
+
+impl RangeReader {
+    /// Open the resource at `uri`.
+    ///
+    /// This sends a `HEAD` request to learn the length of the resource, and
+    /// whether the server advertises support for range requests with
+    /// `Accept-Ranges: bytes`. It fails if the server doesn't answer with a
+    /// success status.
+    pub async fn new(client: Client, uri: Uri) -> io::Result<Self> {
+        let request = Request::head(uri.clone())
+            .body(empty())
+            .map_err(io::Error::other)?;
+        let response = client.send(request).await.map_err(io::Error::other)?;
+        if !response.status().is_success() {
+            return Err(io::Error::other(format!(
+                "unexpected status {} for HEAD {uri}",
+                response.status()
+            )));
+        }
+        let accepts_ranges = response
+            .header_str(ACCEPT_RANGES)
+            .is_some_and(|value| value.split(',').any(|unit| unit.trim() == "bytes"));
+        Ok(Self {
+            client,
+            uri,
+            pos: 0,
+            len: response.content_length(),
+            accepts_ranges,
+            body: None,
+        })
+    }
+
+    /// The length of the resource in bytes, if the server reported it.
+    pub fn content_length(&self) -> Option<u64> {
+        self.len
+    }
+
+    /// Returns `true` if the server supports range requests.
+    ///
+    /// This starts out as whether the server advertised `Accept-Ranges:
+    /// bytes`, and becomes `false` once the server has ignored a range.
+    pub fn accepts_ranges(&self) -> bool {
+        self.accepts_ranges
+    }
+
+    /// Request the resource from the current position onwards, returning
+    /// `None` if the position is past its end.
+    async fn open(&mut self) -> io::Result<Option<IncomingBody>> {
+        let mut request = Request::get(self.uri.clone());
+        // A range starting at 0 is the whole resource, so don't ask for
+        // one: this way an empty resource isn't answered with a 416.
+        if self.pos > 0 {
+            request = request.header(RANGE, format!("bytes={}-", self.pos));
+        }
+        let request = request.body(empty()).map_err(io::Error::other)?;
+        let response = self.client.send(request).await.map_err(io::Error::other)?;
+        match response.status() {
+            StatusCode::PARTIAL_CONTENT => {
+                let (start, total) = response
+                    .header_str(CONTENT_RANGE)
+                    .and_then(parse_content_range)
+                    .ok_or_else(|| invalid_data("missing or invalid Content-Range"))?;
+                if start != Some(self.pos) {
+                    return Err(invalid_data("Content-Range doesn't match the request"));
+                }
+                self.len = total.or(self.len);
+                Ok(Some(response.into_body()))
+            }
+            StatusCode::OK => {
+                if self.pos > 0 {
+                    self.accepts_ranges = false;
+                }
+                self.len = response.content_length().or(self.len);
+                let mut body = response.into_body();
+                let skipped = discard(&mut body, self.pos).await?;
+                if skipped < self.pos {
+                    self.len = Some(skipped);
+                    return Ok(None);
+                }
+                Ok(Some(body))
+            }
+            StatusCode::RANGE_NOT_SATISFIABLE => {
+                if let Some((None, Some(total))) = response
+                    .header_str(CONTENT_RANGE)
+                    .and_then(parse_content_range)
+                {
+                    self.len = Some(total);
+                }
+                Ok(None)
+            }
+            status => Err(io::Error::other(format!(
+                "unexpected status {status} for GET {}",
+                self.uri
+            ))),
+        }
+    }
+}
+
+impl AsyncRead for RangeReader {
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.len.is_some_and(|len| self.pos >= len) {
+            return Ok(0);
+        }
+        if self.body.is_none() {
+            self.body = self.open().await?;
+        }
+        let Some(body) = self.body.as_mut() else {
+            return Ok(0);
+        };
+        let n = body.read(buf).await?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl AsyncSeek for RangeReader {
+    /// Move the position the next read starts at.
+    ///
+    /// Seeking relative to the end fails if the length of the resource is
+    /// unknown.
+    async fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+            SeekFrom::End(offset) => {
+                let len = self.len.ok_or_else(|| {
+                    io::Error::new(ErrorKind::Unsupported, "length of the resource is unknown")
+                })?;
+                len.checked_add_signed(offset)
+            }
+        };
+        let new = new.ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            )
+        })?;
+        if new != self.pos {
+            // The next read starts a new request at the new position.
+            self.body = None;
+            self.pos = new;
+        }
+        Ok(new)
+    }
+}
+
+/// Reads and drops up to `n` bytes, returning how many there were.
+async fn discard<R: AsyncRead>(reader: &mut R, n: u64) -> io::Result<u64> {
+    let mut scratch = [0; 8 * 1024];
+    let mut skipped = 0;
+    while skipped < n {
+        let max = (n - skipped).min(scratch.len() as u64) as usize;
+        match reader.read(&mut scratch[..max]).await? {
+            0 => break,
+            read => skipped += read as u64,
+        }
+    }
+    Ok(skipped)
+}
+
+/// Parses a `Content-Range` header such as `bytes 0-499/1234`, returning the
+/// first byte position and the complete length, if known. The position is
+/// `None` for an unsatisfied range, `bytes */1234`.
+fn parse_content_range(value: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let total = match total {
+        "*" => None,
+        total => Some(total.parse().ok()?),
+    };
+    let start = match range {
+        "*" => None,
+        range => {
+            let (start, end) = range.split_once('-')?;
+            let (start, end): (u64, u64) = (start.parse().ok()?, end.parse().ok()?);
+            if end < start {
+                return None;
+            }
+            Some(start)
+        }
+    };
+    Some((start, total))
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn content_range() {
+        assert_eq!(
+            parse_content_range("bytes 0-499/1234"),
+            Some((Some(0), Some(1234)))
+        );
+        assert_eq!(
+            parse_content_range("bytes 500-999/*"),
+            Some((Some(500), None))
+        );
+        assert_eq!(
+            parse_content_range("bytes */1234"),
+            Some((None, Some(1234)))
+        );
+        assert_eq!(parse_content_range("bytes 5-4/10"), None);
+        assert_eq!(parse_content_range("items 0-4/10"), None);
+        assert_eq!(parse_content_range("bytes 0-4"), None);
+    }
+
+    #[test]
+    fn discard_stops_at_eof() {
+        crate::runtime::block_on(async {
+            let mut reader = io::ChunksReader::new([vec![1; 10_000], vec![2; 10]]);
+            assert_eq!(discard(&mut reader, 10_005).await.unwrap(), 10_005);
+            assert_eq!(reader.remaining(), 5);
+            assert_eq!(discard(&mut reader, 100).await.unwrap(), 5);
+        })
+    }
+}
@@ -1,10 +1,223 @@
-use super::{fields::header_map_to_wasi, method::to_wasi_method, Error, Result};
+use super::percent::decode_path_segment;
+use super::{
+    body::Body, conditional, fields::header_map_to_wasi, method::to_wasi_method, ETag, Error,
+    HeaderValue, Method, Response, Result,
+};
+use crate::time::SystemTime;
+use http::header::{CONTENT_LENGTH, FORWARDED, TRANSFER_ENCODING};
+use std::borrow::Cow;
+use std::net::{IpAddr, SocketAddr};
 use wasi::http::outgoing_handler::OutgoingRequest;
 use wasi::http::types::Scheme;
 
 pub use http::Request;
 
-pub(crate) fn try_into_outgoing<T>(request: Request<T>) -> Result<(OutgoingRequest, T)> {
+/// Convenience methods for routing requests.
+pub trait RequestExt {
+    /// Returns the path of the request URI, without the query string.
+    ///
+    /// Unlike `uri().path_and_query().unwrap()`, this never panics: a URI
+    /// without a path, such as `*` or an authority on its own, yields `/`.
+    fn path(&self) -> &str;
+
+    /// Returns the segments of the request path, percent-decoded.
+    ///
+    /// The path is split on `/`, and empty segments are skipped, so
+    /// `/users/42/posts`, `/users/42/posts/` and `//users//42/posts` all
+    /// yield `["users", "42", "posts"]`, and `/` yields nothing. The query
+    /// string isn't included.
+    ///
+    /// Each segment is decoded after the path has been split, so an encoded
+    /// slash, `%2F`, is part of its segment rather than separating two.
+    /// A segment which isn't valid percent-encoding, or doesn't decode to
+    /// UTF-8, is yielded as-is.
+    ///
+    /// The path isn't normalized: `.` and `..` segments, whether literal or
+    /// encoded as `%2E`, are yielded like any other segment. Check for them
+    /// before mapping segments to something hierarchical, like a file path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstd::http::{Request, RequestExt};
+    ///
+    /// let request = Request::get("/files/my%20notes/a%2Fb.txt?v=2").body(()).unwrap();
+    /// let segments: Vec<_> = request.path_segments().collect();
+    /// assert_eq!(segments, ["files", "my notes", "a/b.txt"]);
+    /// ```
+    fn path_segments(&self) -> impl Iterator<Item = Cow<'_, str>>;
+
+    /// Returns `true` if the request has the given method and its path, not
+    /// counting the query string, is exactly `path`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstd::http::{Method, Request, RequestExt};
+    ///
+    /// let request = Request::get("https://example.com/users?page=2").body(()).unwrap();
+    /// assert!(request.matches(Method::GET, "/users"));
+    /// assert!(!request.matches(Method::POST, "/users"));
+    /// ```
+    fn matches(&self, method: Method, path: &str) -> bool;
+
+    /// Returns `true` if the request URI's scheme is `https`.
+    fn is_secure(&self) -> bool;
+
+    /// Returns the address of the client which originally sent the request,
+    /// as reported by proxies in front of this one.
+    ///
+    /// This reads the `for` parameter of the first element of the standard
+    /// `Forwarded` header ([RFC 7239]), and falls back to the first address
+    /// in the `X-Forwarded-For` header. Ports are ignored. `None` is returned
+    /// if neither header holds an IP address, for example when the proxy
+    /// reports an obfuscated identifier such as `for=_hidden`.
+    ///
+    /// These headers are set by whoever sent the request, so they can only
+    /// be trusted if every request reaches this component through a proxy
+    /// which overwrites them.
+    ///
+    /// [RFC 7239]: https://www.rfc-editor.org/rfc/rfc7239
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstd::http::{Request, RequestExt};
+    ///
+    /// let request = Request::get("https://example.com/")
+    ///     .header("x-forwarded-for", "203.0.113.7, 10.0.0.1")
+    ///     .body(())
+    ///     .unwrap();
+    /// assert_eq!(request.client_ip(), Some([203, 0, 113, 7].into()));
+    /// ```
+    fn client_ip(&self) -> Option<IpAddr>;
+
+    /// Returns `true` if the client's cached copy of the resource is still
+    /// current, so a server can answer with `304 Not Modified`.
+    ///
+    /// `etag` and `last_modified` describe the current version of the
+    /// resource. Following [RFC 7232, section 6], `If-None-Match` is
+    /// evaluated using weak comparison, and `If-Modified-Since` is only
+    /// considered when `If-None-Match` is absent. This only returns `true`
+    /// for `GET` and `HEAD` requests.
+    ///
+    /// [RFC 7232, section 6]: https://www.rfc-editor.org/rfc/rfc7232#section-6
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstd::http::{ETag, Request, RequestExt};
+    ///
+    /// let etag = ETag::strong("v1").unwrap();
+    /// let request = Request::get("/")
+    ///     .header("if-none-match", "W/\"v1\"")
+    ///     .body(())
+    ///     .unwrap();
+    /// assert!(request.is_not_modified(Some(&etag), None));
+    /// ```
+    fn is_not_modified(&self, etag: Option<&ETag>, last_modified: Option<SystemTime>) -> bool;
+
+    /// Makes the request conditional on a previously received response
+    /// having changed.
+    ///
+    /// The `ETag` and `Last-Modified` headers of `cached` are sent as
+    /// `If-None-Match` and `If-Modified-Since`, so the server can answer
+    /// with `304 Not Modified` if the cached response is still current.
+    fn revalidate<T>(&mut self, cached: &Response<T>);
+}
+
+impl<B> RequestExt for Request<B> {
+    fn path(&self) -> &str {
+        match self.uri().path() {
+            "" => "/",
+            path => path,
+        }
+    }
+
+    fn path_segments(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.path()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(decode_path_segment)
+    }
+
+    fn matches(&self, method: Method, path: &str) -> bool {
+        *self.method() == method && self.path() == path
+    }
+
+    fn is_secure(&self) -> bool {
+        self.uri().scheme() == Some(&http::uri::Scheme::HTTPS)
+    }
+
+    fn client_ip(&self) -> Option<IpAddr> {
+        let headers = self.headers();
+        if let Some(forwarded) = headers.get(FORWARDED) {
+            let ip = forwarded.to_str().ok().and_then(forwarded_for);
+            if ip.is_some() {
+                return ip;
+            }
+        }
+        let forwarded_for = headers.get("x-forwarded-for")?.to_str().ok()?;
+        parse_node(forwarded_for.split(',').next()?)
+    }
+
+    fn is_not_modified(&self, etag: Option<&ETag>, last_modified: Option<SystemTime>) -> bool {
+        conditional::is_not_modified(self, etag, last_modified)
+    }
+
+    fn revalidate<T>(&mut self, cached: &Response<T>) {
+        conditional::revalidate(self, cached)
+    }
+}
+
+/// Parses the `for` parameter of the first element of a `Forwarded` header.
+fn forwarded_for(value: &str) -> Option<IpAddr> {
+    let element = value.split(',').next()?;
+    element.split(';').find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case("for") {
+            parse_node(value.trim().trim_matches('"'))
+        } else {
+            None
+        }
+    })
+}
+
+/// Parses an IP address with an optional port, where IPv6 addresses with a
+/// port are enclosed in brackets.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim();
+    if let Ok(ip) = node.parse() {
+        return Some(ip);
+    }
+    if let Some(rest) = node.strip_prefix('[') {
+        let (ip, _port) = rest.split_once(']')?;
+        return ip.parse().ok();
+    }
+    node.parse::<SocketAddr>().ok().map(|addr| addr.ip())
+}
+
+/// Add a `Content-Length` header for bodies whose length is known up front.
+///
+/// Some servers reject requests without a Content-Length, even when the body
+/// is empty. Headers set on the request take precedence: an existing
+/// `Content-Length` is kept as it is, and none is added if a
+/// `Transfer-Encoding` is set, since a message must not have both.
+fn set_content_length<T: Body>(request: &mut Request<T>) {
+    let Some(len) = request.body().len() else {
+        return;
+    };
+    let headers = request.headers_mut();
+    if !headers.contains_key(TRANSFER_ENCODING) {
+        headers
+            .entry(CONTENT_LENGTH)
+            .or_insert_with(|| HeaderValue::from(len));
+    }
+}
+
+pub(crate) fn try_into_outgoing<T: Body>(mut request: Request<T>) -> Result<(OutgoingRequest, T)> {
+    set_content_length(&mut request);
+
     let wasi_req = OutgoingRequest::new(header_map_to_wasi(request.headers())?);
 
     let (parts, body) = request.into_parts();
@@ -40,6 +253,158 @@ pub(crate) fn try_into_outgoing<T>(request: Request<T>) -> Result<(OutgoingReque
             })?;
     }
 
+    // `parts.version` is deliberately not used: wasi-http doesn't expose the
+    // protocol version, it is negotiated by the host.
+
     // All done; request is ready for send-off
     Ok((wasi_req, body))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::http::IntoBody;
+
+    fn request(method: Method, uri: &str) -> Request<()> {
+        Request::builder().method(method).uri(uri).body(()).unwrap()
+    }
+
+    #[test]
+    fn path_defaults_to_root() {
+        assert_eq!(request(Method::GET, "/a/b?c=d").path(), "/a/b");
+        assert_eq!(request(Method::GET, "https://example.com").path(), "/");
+        assert_eq!(request(Method::GET, "example.com:443").path(), "/");
+        assert_eq!(request(Method::OPTIONS, "*").path(), "*");
+    }
+
+    #[test]
+    fn path_segments() {
+        let segments = |uri| -> Vec<String> {
+            let req = request(Method::GET, uri);
+            req.path_segments().map(Cow::into_owned).collect()
+        };
+        assert_eq!(segments("/users/42/posts"), ["users", "42", "posts"]);
+        assert_eq!(segments("/users/42/posts/"), ["users", "42", "posts"]);
+        assert_eq!(segments("//users//42"), ["users", "42"]);
+        assert!(segments("/").is_empty());
+        assert!(segments("https://example.com").is_empty());
+        assert_eq!(segments("/a%20b/%E6%97%A5?q=%2F"), ["a b", "日"]);
+        assert_eq!(segments("/a%2Fb/c"), ["a/b", "c"]);
+        assert_eq!(segments("/a/./../%2E%2E"), ["a", ".", "..", ".."]);
+        assert_eq!(segments("/100%/%ff"), ["100%", "%ff"]);
+
+        let req = request(Method::GET, "/plain/en%63oded");
+        let mut segments = req.path_segments();
+        assert!(matches!(segments.next(), Some(Cow::Borrowed("plain"))));
+        assert!(matches!(segments.next(), Some(Cow::Owned(s)) if s == "encoded"));
+    }
+
+    #[test]
+    fn matches_method_and_path() {
+        let req = request(Method::POST, "https://example.com/submit?x=1");
+        assert!(req.matches(Method::POST, "/submit"));
+        assert!(!req.matches(Method::GET, "/submit"));
+        assert!(!req.matches(Method::POST, "/submit?x=1"));
+        assert!(!req.matches(Method::POST, "/"));
+    }
+
+    #[test]
+    fn is_secure() {
+        assert!(request(Method::GET, "https://example.com/").is_secure());
+        assert!(!request(Method::GET, "http://example.com/").is_secure());
+        assert!(!request(Method::GET, "/relative").is_secure());
+    }
+
+    fn client_ip(headers: &[(&str, &str)]) -> Option<IpAddr> {
+        let mut req = Request::builder().uri("/");
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        req.body(()).unwrap().client_ip()
+    }
+
+    #[test]
+    fn client_ip_from_forwarded() {
+        let v4: IpAddr = [192, 0, 2, 60].into();
+        let v6: IpAddr = "2001:db8:cafe::17".parse().unwrap();
+        let forwarded = |value| client_ip(&[("forwarded", value)]);
+        assert_eq!(
+            forwarded("for=192.0.2.60;proto=http;by=203.0.113.43"),
+            Some(v4)
+        );
+        assert_eq!(forwarded("proto=https; For=\"192.0.2.60:4711\""), Some(v4));
+        assert_eq!(forwarded("for=\"[2001:db8:cafe::17]:4711\""), Some(v6));
+        assert_eq!(
+            forwarded("for=\"[2001:db8:cafe::17]\", for=10.0.0.1"),
+            Some(v6)
+        );
+        assert_eq!(forwarded("for=_hidden, for=192.0.2.60"), None);
+        assert_eq!(forwarded("by=192.0.2.60"), None);
+    }
+
+    #[test]
+    fn client_ip_from_x_forwarded_for() {
+        let v4: IpAddr = [203, 0, 113, 7].into();
+        let xff = |value| client_ip(&[("x-forwarded-for", value)]);
+        assert_eq!(xff("203.0.113.7"), Some(v4));
+        assert_eq!(xff(" 203.0.113.7:8080 , 10.0.0.1"), Some(v4));
+        assert_eq!(xff("2001:db8::1"), "2001:db8::1".parse().ok());
+        assert_eq!(xff("garbage"), None);
+        assert_eq!(client_ip(&[]), None);
+        assert_eq!(
+            client_ip(&[
+                ("forwarded", "for=unknown"),
+                ("x-forwarded-for", "203.0.113.7")
+            ]),
+            Some(v4),
+            "falls back when Forwarded has no address"
+        );
+    }
+
+    fn content_length<B: Body>(request: Request<B>) -> Vec<Vec<u8>> {
+        let (wasi_req, _body) = try_into_outgoing(request).unwrap();
+        let headers = wasi_req.headers();
+        let values = headers.get(&CONTENT_LENGTH.to_string());
+        // The headers are a child resource and must be dropped first.
+        drop(headers);
+        values
+    }
+
+    #[test]
+    fn content_length_from_body() {
+        let req = Request::post("https://example.com/").body(crate::io::empty());
+        assert_eq!(content_length(req.unwrap()), [b"0"]);
+
+        let req = Request::post("https://example.com/").body("hello".into_body());
+        assert_eq!(content_length(req.unwrap()), [b"5"]);
+    }
+
+    #[test]
+    fn content_length_not_overridden() {
+        let req = Request::post("https://example.com/")
+            .header(CONTENT_LENGTH, "5")
+            .body("hello".into_body())
+            .unwrap();
+        assert_eq!(content_length(req), [b"5"]);
+    }
+
+    #[test]
+    fn content_length_not_added_with_transfer_encoding() {
+        let mut req = Request::post("https://example.com/")
+            .header(TRANSFER_ENCODING, "chunked")
+            .body("hello".into_body())
+            .unwrap();
+        set_content_length(&mut req);
+        assert!(!req.headers().contains_key(CONTENT_LENGTH));
+        assert_eq!(req.headers()[TRANSFER_ENCODING], "chunked");
+    }
+
+    #[test]
+    fn content_length_unknown() {
+        let reader = crate::io::ChunksReader::new(["hello"]);
+        let req = Request::post("https://example.com/")
+            .body(crate::http::body::StreamBody::new(reader))
+            .unwrap();
+        assert!(content_length(req).is_empty());
+    }
+}
@@ -2,16 +2,83 @@ use wasi::http::types::IncomingResponse;
 
 use super::{
     body::{BodyKind, IncomingBody},
-    fields::header_map_from_wasi,
+    fields::{header_map_from_wasi_limited, HeaderLimits},
     Error, HeaderMap, Result,
 };
-use crate::io::AsyncInputStream;
+use crate::io::{self, AsyncInputStream, AsyncWrite};
 use http::StatusCode;
 
 pub use http::Response;
 
-pub(crate) fn try_from_incoming(incoming: IncomingResponse) -> Result<Response<IncomingBody>> {
-    let headers: HeaderMap = header_map_from_wasi(incoming.headers())?;
+use super::Body;
+
+/// Convenience methods for consuming responses.
+pub trait ResponseExt {
+    /// The reason phrase of the response's status code, such as `Not Found`
+    /// for a 404.
+    ///
+    /// This is always the canonical phrase for the status code, or `None` if
+    /// the code doesn't have one. WASI 0.2 only transmits the numeric status
+    /// code, in both directions: a custom reason phrase sent by a server is
+    /// dropped by the host before the response reaches the client, and
+    /// outgoing responses can't carry one, so custom phrases can't
+    /// round-trip.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wstd::http::{Response, ResponseExt, StatusCode};
+    ///
+    /// let mut response = Response::new(wstd::io::empty());
+    /// *response.status_mut() = StatusCode::IM_A_TEAPOT;
+    /// assert_eq!(response.reason_phrase(), Some("I'm a teapot"));
+    /// ```
+    fn reason_phrase(&self) -> Option<&'static str>;
+
+    /// Streams the response body into `writer`, returning the number of
+    /// bytes written.
+    ///
+    /// The response is consumed: once this returns, the body has been read
+    /// to the end and released, and `writer` has been flushed. When the body
+    /// is an [`IncomingBody`] and `writer` is backed by a WASI output stream,
+    /// such as a [`TcpStream`](crate::net::TcpStream) or
+    /// [`stdout`](crate::io::stdout), the bytes are spliced between the
+    /// streams without being copied through a buffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wstd::http::{Client, Request, ResponseExt};
+    ///
+    /// # async fn download() -> anyhow::Result<()> {
+    /// let request = Request::get("https://example.com/").body(wstd::io::empty())?;
+    /// let response = Client::new().send(request).await?;
+    /// let written = response.copy_to(wstd::io::stdout()).await?;
+    /// eprintln!("downloaded {written} bytes");
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn copy_to<W: AsyncWrite>(self, writer: W) -> io::Result<u64>;
+}
+
+impl<B: Body> ResponseExt for Response<B> {
+    fn reason_phrase(&self) -> Option<&'static str> {
+        self.status().canonical_reason()
+    }
+
+    async fn copy_to<W: AsyncWrite>(self, mut writer: W) -> io::Result<u64> {
+        let mut copied = 0;
+        io::copy_with_progress(self.into_body(), &mut writer, |n| copied = n).await?;
+        writer.flush().await?;
+        Ok(copied)
+    }
+}
+
+pub(crate) fn try_from_incoming(
+    incoming: IncomingResponse,
+    limits: &HeaderLimits,
+) -> Result<Response<IncomingBody>> {
+    let headers: HeaderMap = header_map_from_wasi_limited(incoming.headers(), limits)?;
     // TODO: Does WASI guarantee that the incoming status is valid?
     let status =
         StatusCode::from_u16(incoming.status()).map_err(|err| Error::other(err.to_string()))?;
@@ -38,3 +105,35 @@ pub(crate) fn try_from_incoming(incoming: IncomingResponse) -> Result<Response<I
         .body(body)
         .map_err(|err| Error::other(err.to_string()))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::http::IntoBody;
+    use crate::io::Cursor;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn copy_to_writer() {
+        block_on(async {
+            let response = Response::new("hello, world".into_body());
+            let mut out = Cursor::new(Vec::new());
+            assert_eq!(response.copy_to(&mut out).await.unwrap(), 12);
+            assert_eq!(out.into_inner(), b"hello, world");
+
+            let response = Response::new(crate::io::empty());
+            let mut out = Cursor::new(Vec::new());
+            assert_eq!(response.copy_to(&mut out).await.unwrap(), 0);
+        })
+    }
+
+    #[test]
+    fn reason_phrase_is_canonical() {
+        let mut response = Response::new(crate::io::empty());
+        assert_eq!(response.reason_phrase(), Some("OK"));
+        *response.status_mut() = StatusCode::NOT_FOUND;
+        assert_eq!(response.reason_phrase(), Some("Not Found"));
+        *response.status_mut() = StatusCode::from_u16(599).unwrap();
+        assert_eq!(response.reason_phrase(), None);
+    }
+}
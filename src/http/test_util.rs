@@ -0,0 +1,44 @@
+//! Helpers for testing code which handles HTTP requests.
+//!
+//! Requests and responses received from the host carry an
+//! [`IncomingBody`], which can't be created outside a `wasi:http` host. This
+//! module builds them from bytes in memory instead, so handlers can be unit
+//! tested on their own.
+//!
+//! This module is only available with the `test-util` feature.
+
+use super::body::IncomingBody;
+use super::{Request, Response};
+
+/// Turn a request with an in-memory body into one with an [`IncomingBody`],
+/// as a handler would receive it.
+///
+/// See [`IncomingBody::from_bytes`] for how the body behaves.
+///
+/// # Example
+///
+/// ```
+/// # wstd::runtime::block_on(async {
+/// use wstd::http::body::IncomingBody;
+/// use wstd::http::{test_util, Request};
+///
+/// async fn handler(mut request: Request<IncomingBody>) -> usize {
+///     request.body_mut().bytes_limited(1024).await.unwrap().len()
+/// }
+///
+/// let request = Request::post("/upload").body("hello").unwrap();
+/// assert_eq!(handler(test_util::incoming_request(request)).await, 5);
+/// # })
+/// ```
+pub fn incoming_request<B: Into<Vec<u8>>>(request: Request<B>) -> Request<IncomingBody> {
+    request.map(|body| IncomingBody::from_bytes(body, None))
+}
+
+/// Turn a response with an in-memory body into one with an
+/// [`IncomingBody`], as [`Client::send`](super::Client::send) would return
+/// it.
+///
+/// See [`IncomingBody::from_bytes`] for how the body behaves.
+pub fn incoming_response<B: Into<Vec<u8>>>(response: Response<B>) -> Response<IncomingBody> {
+    response.map(|body| IncomingBody::from_bytes(body, None))
+}
@@ -0,0 +1,386 @@
+//! Tunneling TCP connections through an HTTP proxy with `CONNECT`.
+//!
+//! A client which wants to reach `example.com:443` through a forward proxy
+//! sends it `CONNECT example.com:443 HTTP/1.1`. Once the proxy has opened a
+//! connection to that target, it answers `200` and from then on copies bytes
+//! in both directions, without looking at them. This is how HTTPS is
+//! proxied.
+//!
+//! `wasi-http` can't express this, so a tunneling proxy reads requests from
+//! a [`TcpStream`] directly:
+//!
+//! ```no_run
+//! use wstd::http::tunnel::ConnectRequest;
+//! use wstd::iter::AsyncIterator;
+//! use wstd::net::TcpListener;
+//!
+//! # async fn run() -> std::io::Result<()> {
+//! let listener = TcpListener::bind("127.0.0.1:8080").await?;
+//! let mut incoming = listener.incoming();
+//! let client = incoming.next().await.unwrap()?;
+//! let request = ConnectRequest::read(client).await?;
+//! println!("tunneling to {}", request.target());
+//! let tunnel = request.accept().await?;
+//! let (sent, received) = tunnel.splice().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{HeaderName, HeaderValue, Method, Request, StatusCode};
+use crate::io::{self, AsyncRead, AsyncWrite};
+use crate::net::{connect_any, lookup_host, TcpStream};
+use http::uri::Authority;
+use http::Version;
+use std::fmt;
+use std::io::ErrorKind;
+
+/// The largest request head [`ConnectRequest::read`] accepts.
+const MAX_HEAD_SIZE: usize = 8 * 1024;
+
+/// Returns the target of a `CONNECT` request.
+///
+/// The target must be in authority form, a host and a port such as
+/// `example.com:443`, as required by
+/// [RFC 9110, section 9.3.6](https://www.rfc-editor.org/rfc/rfc9110#section-9.3.6).
+///
+/// # Example
+///
+/// ```
+/// use wstd::http::{tunnel::connect_target, Method, Request};
+///
+/// let request = Request::builder()
+///     .method(Method::CONNECT)
+///     .uri("example.com:443")
+///     .body(())
+///     .unwrap();
+/// let target = connect_target(&request).unwrap();
+/// assert_eq!(target.host(), "example.com");
+/// assert_eq!(target.port_u16(), Some(443));
+/// ```
+pub fn connect_target<B>(request: &Request<B>) -> Result<&Authority, InvalidConnectTarget> {
+    let uri = request.uri();
+    if request.method() != Method::CONNECT
+        || uri.scheme().is_some()
+        || !matches!(uri.path_and_query().map(|p| p.as_str()), None | Some(""))
+    {
+        return Err(InvalidConnectTarget);
+    }
+    match uri.authority() {
+        Some(authority) if authority.port().is_some() && !authority.as_str().contains('@') => {
+            Ok(authority)
+        }
+        _ => Err(InvalidConnectTarget),
+    }
+}
+
+/// The request was not a `CONNECT` request with a `host:port` target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidConnectTarget;
+
+impl fmt::Display for InvalidConnectTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("not a CONNECT request with a host:port target")
+    }
+}
+
+impl std::error::Error for InvalidConnectTarget {}
+
+/// A `CONNECT` request received from a client, which hasn't been answered
+/// yet.
+pub struct ConnectRequest {
+    client: TcpStream,
+    request: Request<()>,
+    /// Bytes the client sent after the request head, meant for the target.
+    buffered: Vec<u8>,
+}
+
+impl ConnectRequest {
+    /// Read a `CONNECT` request from a client.
+    ///
+    /// If the client doesn't send a valid `CONNECT` request, it is answered
+    /// with `400 Bad Request`, or with `405 Method Not Allowed` for other
+    /// methods, and an [`InvalidData`](ErrorKind::InvalidData) error is
+    /// returned.
+    pub async fn read(mut client: TcpStream) -> io::Result<Self> {
+        let (request, buffered) = match read_head(&mut client).await {
+            Ok(head) => head,
+            Err(err) if err.kind() == ErrorKind::InvalidData => {
+                write_status(&mut client, StatusCode::BAD_REQUEST).await?;
+                return Err(err);
+            }
+            Err(err) => return Err(err),
+        };
+        if let Err(err) = connect_target(&request) {
+            let status = if request.method() == Method::CONNECT {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::METHOD_NOT_ALLOWED
+            };
+            write_status(&mut client, status).await?;
+            return Err(io::Error::new(ErrorKind::InvalidData, err));
+        }
+        Ok(Self {
+            client,
+            request,
+            buffered,
+        })
+    }
+
+    /// The request, including headers such as `Proxy-Authorization`.
+    pub fn request(&self) -> &Request<()> {
+        &self.request
+    }
+
+    /// The `host:port` the client wants to connect to.
+    pub fn target(&self) -> &Authority {
+        connect_target(&self.request).expect("target was validated when reading the request")
+    }
+
+    /// Connect to the target, and tell the client the tunnel is established.
+    ///
+    /// If the target's host can't be resolved or no connection can be
+    /// made, the client is answered with `502 Bad Gateway` and the error is
+    /// returned.
+    pub async fn accept(mut self) -> io::Result<Tunnel> {
+        let target = self.target();
+        let host = target.host().trim_start_matches('[').trim_end_matches(']');
+        let port = target.port_u16().expect("target has a port");
+        let upstream = match lookup_host(host).await {
+            Ok(addrs) => connect_any(host, addrs, port).await,
+            Err(err) => Err(err),
+        };
+        let mut upstream = match upstream {
+            Ok(upstream) => upstream,
+            Err(err) => {
+                write_status(&mut self.client, StatusCode::BAD_GATEWAY).await?;
+                return Err(err);
+            }
+        };
+        // Anything the client sent early is already meant for the target.
+        if !self.buffered.is_empty() {
+            upstream.write_all(&self.buffered).await?;
+        }
+        self.client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+        self.client.flush().await?;
+        Ok(Tunnel {
+            client: self.client,
+            upstream,
+            early: self.buffered.len() as u64,
+        })
+    }
+
+    /// Refuse to open the tunnel, answering the client with `status`.
+    pub async fn reject(mut self, status: StatusCode) -> io::Result<()> {
+        write_status(&mut self.client, status).await
+    }
+}
+
+/// An established tunnel between a client and the target of its `CONNECT`
+/// request.
+pub struct Tunnel {
+    client: TcpStream,
+    upstream: TcpStream,
+    /// The number of bytes the client sent along with its request, which
+    /// were already forwarded to the target.
+    early: u64,
+}
+
+impl Tunnel {
+    /// Copy bytes in both directions until both sides have closed their end.
+    ///
+    /// Returns the number of bytes sent from the client to the target, and
+    /// from the target to the client, including any bytes the client sent
+    /// along with its request. See [`io::copy_bidirectional`].
+    pub async fn splice(self) -> io::Result<(u64, u64)> {
+        let (sent, received) =
+            io::copy_bidirectional(self.client.split(), self.upstream.split()).await?;
+        Ok((self.early + sent, received))
+    }
+
+    /// Returns the client and target streams, to copy between them some
+    /// other way.
+    pub fn into_streams(self) -> (TcpStream, TcpStream) {
+        (self.client, self.upstream)
+    }
+}
+
+impl fmt::Debug for ConnectRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectRequest")
+            .field("request", &self.request)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for Tunnel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tunnel").finish_non_exhaustive()
+    }
+}
+
+/// Answer the client with an empty response and the given status.
+async fn write_status(client: &mut TcpStream, status: StatusCode) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {} {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+        status.as_str(),
+        status.canonical_reason().unwrap_or_default()
+    );
+    client.write_all(response.as_bytes()).await?;
+    client.flush().await
+}
+
+/// Reads an HTTP/1 request head, returning it along with any bytes read
+/// past its end.
+async fn read_head<R: AsyncRead>(reader: &mut R) -> io::Result<(Request<()>, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 1024];
+    let end = loop {
+        // The end of the head may straddle two reads.
+        let searched = buf.len().saturating_sub(3);
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "connection closed before the request head was complete",
+            ));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf[searched..].windows(4).position(|w| w == b"\r\n\r\n") {
+            break searched + pos + 4;
+        }
+        if buf.len() > MAX_HEAD_SIZE {
+            return Err(invalid("request head is too large"));
+        }
+    };
+    let rest = buf.split_off(end);
+    Ok((parse_head(&buf)?, rest))
+}
+
+fn parse_head(head: &[u8]) -> io::Result<Request<()>> {
+    let head = std::str::from_utf8(head).map_err(|_| invalid("request head is not UTF-8"))?;
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target), Some(version), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid("malformed request line"));
+    };
+    let version = match version {
+        "HTTP/1.1" => Version::HTTP_11,
+        "HTTP/1.0" => Version::HTTP_10,
+        _ => return Err(invalid("unsupported HTTP version")),
+    };
+    let mut request = Request::builder()
+        .method(Method::from_bytes(method.as_bytes()).map_err(|_| invalid("invalid method"))?)
+        .uri(target)
+        .version(version);
+    for line in lines.take_while(|line| !line.is_empty()) {
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid("malformed header"))?;
+        let name =
+            HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid("invalid header"))?;
+        let value = HeaderValue::from_str(value.trim()).map_err(|_| invalid("invalid header"))?;
+        request = request.header(name, value);
+    }
+    request
+        .body(())
+        .map_err(|_| invalid("invalid request target"))
+}
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::ChunksReader;
+    use crate::runtime::block_on;
+
+    fn request(method: Method, uri: &str) -> Request<()> {
+        Request::builder().method(method).uri(uri).body(()).unwrap()
+    }
+
+    #[test]
+    fn targets() {
+        let target = |method, uri| connect_target(&request(method, uri)).map(|a| a.to_string());
+        assert_eq!(
+            target(Method::CONNECT, "example.com:443"),
+            Ok("example.com:443".to_owned())
+        );
+        assert_eq!(
+            target(Method::CONNECT, "[::1]:8443"),
+            Ok("[::1]:8443".to_owned())
+        );
+        assert_eq!(
+            target(Method::CONNECT, "example.com"),
+            Err(InvalidConnectTarget)
+        );
+        assert_eq!(
+            target(Method::CONNECT, "https://example.com:443/"),
+            Err(InvalidConnectTarget)
+        );
+        assert_eq!(
+            target(Method::CONNECT, "user@example.com:443"),
+            Err(InvalidConnectTarget)
+        );
+        assert_eq!(
+            target(Method::GET, "example.com:443"),
+            Err(InvalidConnectTarget)
+        );
+    }
+
+    #[test]
+    fn read_fragmented_head() {
+        let data = b"CONNECT example.com:443 HTTP/1.1\r\n\
+            Host: example.com:443\r\n\
+            Proxy-Authorization: Basic Zm9vOmJhcg==\r\n\r\n\
+            \x16\x03\x01";
+        for max in 1..=5 {
+            let mut reader = ChunksReader::new(data.chunks(max));
+            let (request, rest) = block_on(read_head(&mut reader)).unwrap();
+            assert_eq!(request.method(), Method::CONNECT);
+            assert_eq!(request.version(), Version::HTTP_11);
+            assert_eq!(connect_target(&request).unwrap(), "example.com:443");
+            assert_eq!(
+                request.headers()["proxy-authorization"],
+                "Basic Zm9vOmJhcg=="
+            );
+            // Whatever wasn't part of the head is kept, either buffered or
+            // still in the reader.
+            assert_eq!(rest.len() + reader.remaining(), 3);
+        }
+    }
+
+    #[test]
+    fn read_invalid_head() {
+        let read = |data: &'static [u8]| {
+            block_on(read_head(&mut ChunksReader::new([data])))
+                .unwrap_err()
+                .kind()
+        };
+        assert_eq!(
+            read(b"CONNECT example.com:443\r\n\r\n"),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            read(b"CONNECT example.com:443 HTTP/2\r\n\r\n"),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            read(b"CONNECT example.com:443 HTTP/1.1\r\nno-colon\r\n\r\n"),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            read(b"CONNECT example.com:443 HTTP/1.1\r\n"),
+            ErrorKind::UnexpectedEof
+        );
+        let huge = vec![b'a'; MAX_HEAD_SIZE + 1];
+        let err = block_on(read_head(&mut ChunksReader::new([huge]))).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
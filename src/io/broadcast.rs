@@ -0,0 +1,318 @@
+use super::{AsyncWrite, Result};
+use crate::time::{Duration, Timer};
+use std::future::{poll_fn, Future};
+use std::io::ErrorKind;
+use std::pin::pin;
+use std::task::Poll;
+
+/// What a [`Broadcast`] does with subscribers which can't keep up.
+///
+/// A subscriber can't keep up when writing a message to it doesn't complete
+/// right away, for example because the client on the other end of a socket
+/// isn't reading fast enough. Subscribers whose writes fail are always
+/// dropped, whatever the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlowSubscriber {
+    /// Wait for every subscriber to accept each message.
+    ///
+    /// The slowest subscriber sets the pace for all of them, and one which
+    /// stops reading stalls the broadcast.
+    Block,
+    /// Wait up to the given duration for every subscriber to accept each
+    /// message, and drop the ones which haven't by then.
+    DropAfter(Duration),
+    /// Never wait: keep up to the given number of bytes per subscriber which
+    /// couldn't be written right away, and drop subscribers with more than
+    /// that.
+    ///
+    /// Buffered bytes are only written during later calls to
+    /// [`Broadcast::send`] or [`Broadcast::flush`], since there are no
+    /// background tasks to write them in the meantime.
+    Buffer(usize),
+}
+
+/// Identifies a subscriber of a [`Broadcast`].
+///
+/// This `struct` is returned by [`Broadcast::subscribe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubscriberId(u64);
+
+#[derive(Debug)]
+struct Subscriber<W> {
+    id: SubscriberId,
+    writer: W,
+    /// Bytes which haven't been written to `writer` yet.
+    backlog: Vec<u8>,
+}
+
+impl<W: AsyncWrite> Subscriber<W> {
+    /// Write the whole backlog, draining it as bytes are accepted, so it
+    /// stays accurate if this is cancelled.
+    async fn write_backlog(&mut self, flush: bool) -> Result<()> {
+        while !self.backlog.is_empty() {
+            match self.writer.write(&self.backlog).await? {
+                0 => return Err(ErrorKind::WriteZero.into()),
+                n => drop(self.backlog.drain(..n)),
+            }
+        }
+        if flush {
+            self.writer.flush().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes each message to a set of writers at once.
+///
+/// This is the building block for fanning messages out to many clients, such
+/// as in a pub/sub server. Messages are written to all subscribers
+/// concurrently, and what happens to subscribers which can't keep up is set
+/// by the [`SlowSubscriber`] policy. Subscribers whose writes fail are
+/// dropped, which closes them.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::io::{Broadcast, SlowSubscriber};
+/// use wstd::iter::AsyncIterator;
+/// use wstd::net::TcpListener;
+/// use wstd::time::Duration;
+///
+/// # async fn run(listener: TcpListener) -> std::io::Result<()> {
+/// let policy = SlowSubscriber::DropAfter(Duration::from_secs(1));
+/// let mut clients = Broadcast::with_policy(policy);
+/// let mut incoming = listener.incoming();
+/// while let Some(stream) = incoming.next().await {
+///     clients.subscribe(stream?);
+///     let dropped = clients.send(b"a client joined\n").await;
+///     eprintln!("dropped {} slow clients", dropped.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Broadcast<W> {
+    subscribers: Vec<Subscriber<W>>,
+    policy: SlowSubscriber,
+    next_id: u64,
+}
+
+impl<W> Default for Broadcast<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W> Broadcast<W> {
+    /// Create a broadcast without subscribers, which waits for slow
+    /// subscribers.
+    pub fn new() -> Self {
+        Self::with_policy(SlowSubscriber::Block)
+    }
+
+    /// Create a broadcast without subscribers, which handles slow
+    /// subscribers according to `policy`.
+    pub fn with_policy(policy: SlowSubscriber) -> Self {
+        Self {
+            subscribers: Vec::new(),
+            policy,
+            next_id: 0,
+        }
+    }
+
+    /// Add a subscriber, which receives every message sent from now on.
+    pub fn subscribe(&mut self, writer: W) -> SubscriberId {
+        let id = SubscriberId(self.next_id);
+        self.next_id += 1;
+        self.subscribers.push(Subscriber {
+            id,
+            writer,
+            backlog: Vec::new(),
+        });
+        id
+    }
+
+    /// Remove a subscriber, returning its writer, or `None` if it was
+    /// already removed or dropped.
+    ///
+    /// Any bytes still buffered for the subscriber are discarded.
+    pub fn unsubscribe(&mut self, id: SubscriberId) -> Option<W> {
+        let index = self.subscribers.iter().position(|sub| sub.id == id)?;
+        Some(self.subscribers.remove(index).writer)
+    }
+
+    /// The number of subscribers.
+    pub fn len(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Returns `true` if there are no subscribers.
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+}
+
+impl<W: AsyncWrite> Broadcast<W> {
+    /// Write `message` to every subscriber, returning the subscribers which
+    /// were dropped because they failed or couldn't keep up.
+    ///
+    /// How long this waits depends on the [`SlowSubscriber`] policy.
+    pub async fn send(&mut self, message: &[u8]) -> Vec<SubscriberId> {
+        for sub in &mut self.subscribers {
+            sub.backlog.extend_from_slice(message);
+        }
+        self.write_backlogs(self.policy, false).await
+    }
+
+    /// Write any buffered bytes, and flush every subscriber, returning the
+    /// subscribers which were dropped because they failed or couldn't keep
+    /// up.
+    ///
+    /// Unlike [`send`](Self::send), this waits for every subscriber under the
+    /// [`Buffer`](SlowSubscriber::Buffer) policy too.
+    pub async fn flush(&mut self) -> Vec<SubscriberId> {
+        let policy = match self.policy {
+            SlowSubscriber::Buffer(_) => SlowSubscriber::Block,
+            policy => policy,
+        };
+        self.write_backlogs(policy, true).await
+    }
+
+    async fn write_backlogs(&mut self, policy: SlowSubscriber, flush: bool) -> Vec<SubscriberId> {
+        let mut results: Vec<Option<Result<()>>> = Vec::new();
+        {
+            let mut writes: Vec<_> = self
+                .subscribers
+                .iter_mut()
+                .map(|sub| Some(Box::pin(sub.write_backlog(flush))))
+                .collect();
+            results.resize_with(writes.len(), || None);
+            let timer = match policy {
+                SlowSubscriber::DropAfter(timeout) => Timer::after(timeout),
+                _ => Timer::never(),
+            };
+            let mut deadline = pin!(timer.wait());
+            poll_fn(|cx| {
+                for (write, result) in writes.iter_mut().zip(&mut results) {
+                    if let Some(fut) = write {
+                        if let Poll::Ready(res) = fut.as_mut().poll(cx) {
+                            *result = Some(res);
+                            *write = None;
+                        }
+                    }
+                }
+                if writes.iter().all(Option::is_none) {
+                    return Poll::Ready(());
+                }
+                match policy {
+                    SlowSubscriber::Block => Poll::Pending,
+                    SlowSubscriber::DropAfter(_) => deadline.as_mut().poll(cx).map(|_| ()),
+                    SlowSubscriber::Buffer(_) => Poll::Ready(()),
+                }
+            })
+            .await;
+        }
+
+        // Writes which haven't finished have been cancelled by now, leaving
+        // what they didn't write in the backlog.
+        let mut results = results.into_iter();
+        let mut dropped = Vec::new();
+        self.subscribers.retain(|sub| {
+            let keep = match results.next().flatten() {
+                Some(res) => res.is_ok(),
+                None => match policy {
+                    SlowSubscriber::Buffer(limit) => sub.backlog.len() <= limit,
+                    _ => false,
+                },
+            };
+            if !keep {
+                dropped.push(sub.id);
+            }
+            keep
+        });
+        dropped
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::{pipe_with_capacity, AsyncRead};
+    use crate::runtime::block_on;
+
+    #[test]
+    fn block_waits_for_everyone() {
+        block_on(async {
+            let mut broadcast = Broadcast::new();
+            let (writer, mut reader) = pipe_with_capacity(4);
+            broadcast.subscribe(writer);
+            let send = async {
+                assert!(broadcast.send(b"hello world").await.is_empty());
+                assert!(broadcast.flush().await.is_empty());
+                drop(broadcast);
+            };
+            let receive = async {
+                let mut buf = Vec::new();
+                reader.read_to_end(&mut buf).await.unwrap();
+                buf
+            };
+            let ((), received) = futures_lite::future::zip(send, receive).await;
+            assert_eq!(received, b"hello world");
+        })
+    }
+
+    #[test]
+    fn drop_after_timeout() {
+        block_on(async {
+            let mut broadcast =
+                Broadcast::with_policy(SlowSubscriber::DropAfter(Duration::from_millis(10)));
+            let (writer, mut fast_reader) = pipe_with_capacity(64);
+            let fast = broadcast.subscribe(writer);
+            // Nobody reads from this pipe, so it fills up.
+            let (writer, _slow_reader) = pipe_with_capacity(4);
+            let slow = broadcast.subscribe(writer);
+
+            assert!(broadcast.send(b"meow").await.is_empty());
+            assert_eq!(broadcast.send(b"purr").await, [slow]);
+            assert_eq!(broadcast.len(), 1);
+            drop(broadcast.unsubscribe(fast).unwrap());
+            let mut buf = Vec::new();
+            fast_reader.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(buf, b"meowpurr");
+        })
+    }
+
+    #[test]
+    fn buffer_up_to_limit() {
+        block_on(async {
+            let mut broadcast = Broadcast::with_policy(SlowSubscriber::Buffer(4));
+            let (writer, mut reader) = pipe_with_capacity(4);
+            let id = broadcast.subscribe(writer);
+
+            // The pipe takes 4 bytes, and the rest are buffered.
+            assert!(broadcast.send(b"abcdef").await.is_empty());
+            let mut buf = [0; 16];
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 4);
+            // The 2 buffered bytes are written first, then 2 of these, so 4
+            // bytes are left over.
+            assert!(broadcast.send(b"ghijkl").await.is_empty());
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 4);
+            assert_eq!(&buf[..4], b"efgh");
+            // Now 1 byte too many is left over.
+            assert_eq!(broadcast.send(b"mnopq").await, [id]);
+            assert!(broadcast.is_empty());
+        })
+    }
+
+    #[test]
+    fn failed_subscribers_are_dropped() {
+        block_on(async {
+            let mut broadcast = Broadcast::new();
+            let (writer, reader) = pipe_with_capacity(4);
+            let id = broadcast.subscribe(writer);
+            drop(reader);
+            assert_eq!(broadcast.send(b"hi").await, [id]);
+            assert!(broadcast.unsubscribe(id).is_none());
+        })
+    }
+}
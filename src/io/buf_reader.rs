@@ -0,0 +1,209 @@
+use super::{AsyncRead, Result};
+
+/// The default capacity of a [`BufReader`]: 8 KiB.
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+/// Adds buffering to a reader, so it can be read up to a delimiter.
+///
+/// Reading from a stream in large chunks usually reads past the point a
+/// parser is interested in, such as the end of an HTTP request head. A
+/// `BufReader` keeps the bytes it has read ahead in a buffer, so they are
+/// returned by later reads instead of being lost. See
+/// [`read_until_slice`](Self::read_until_slice).
+///
+/// # Example
+///
+/// ```
+/// # wstd::runtime::block_on(async {
+/// use wstd::io::{AsyncRead, BufReader, ChunksReader};
+///
+/// let stream = ChunksReader::new([&b"GET / HTTP/1.1\r\n\r"[..], b"\nbody"]);
+/// let mut reader = BufReader::new(stream);
+/// let mut head = Vec::new();
+/// reader.read_until_slice(b"\r\n\r\n", &mut head).await?;
+/// assert_eq!(head, b"GET / HTTP/1.1\r\n\r\n");
+///
+/// let mut body = Vec::new();
+/// reader.read_to_end(&mut body).await?;
+/// assert_eq!(body, b"body");
+/// # std::io::Result::Ok(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct BufReader<R> {
+    inner: R,
+    buf: Box<[u8]>,
+    /// The unread bytes are `buf[pos..filled]`.
+    pos: usize,
+    filled: usize,
+}
+
+impl<R> BufReader<R> {
+    /// Wrap `inner` with a buffer of 8 KiB.
+    pub fn new(inner: R) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, inner)
+    }
+
+    /// Wrap `inner` with a buffer of `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `capacity` is zero.
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        assert!(capacity > 0, "BufReader requires a nonzero capacity");
+        Self {
+            inner,
+            buf: vec![0; capacity].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
+        }
+    }
+
+    /// Returns the bytes which have been read from the underlying reader but
+    /// not yet returned.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..self.filled]
+    }
+
+    /// Consumes this wrapper, returning the underlying reader.
+    ///
+    /// Any bytes still in the buffer are lost; use
+    /// [`buffer`](Self::buffer) to retrieve them first.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly skips over the bytes in the buffer.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Mark `amt` bytes returned by [`fill_buf`](Self::fill_buf) as read.
+    pub fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.filled);
+    }
+}
+
+impl<R: AsyncRead> BufReader<R> {
+    /// Returns the buffered bytes, reading more from the underlying reader
+    /// first if there are none.
+    ///
+    /// The bytes stay in the buffer until they are marked as read with
+    /// [`consume`](Self::consume). An empty slice means EOF.
+    pub async fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.pos == self.filled {
+            self.filled = self.inner.read(&mut self.buf).await?;
+            self.pos = 0;
+        }
+        Ok(self.buffer())
+    }
+
+    /// Read until the delimiter `delim` is found, appending the bytes,
+    /// including the delimiter, to `buf`.
+    ///
+    /// Returns the number of bytes appended. If EOF is reached first, the
+    /// bytes up to it are appended without a delimiter, and `0` means the
+    /// reader was already at EOF. The delimiter may be split across any
+    /// number of reads from the underlying reader.
+    ///
+    /// Bytes read past the delimiter stay in the buffer, and are returned by
+    /// the next read. A read which fails leaves the bytes read before it in
+    /// `buf`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `delim` is empty.
+    pub async fn read_until_slice(&mut self, delim: &[u8], buf: &mut Vec<u8>) -> Result<usize> {
+        assert!(!delim.is_empty(), "delimiter must not be empty");
+        let start = buf.len();
+        loop {
+            let available = self.fill_buf().await?;
+            if available.is_empty() {
+                return Ok(buf.len() - start);
+            }
+            // The delimiter may have started in what was appended before.
+            let prev = buf.len();
+            let searched = prev.saturating_sub(delim.len() - 1).max(start);
+            buf.extend_from_slice(available);
+            let found = buf[searched..]
+                .windows(delim.len())
+                .position(|window| window == delim);
+            match found {
+                Some(pos) => {
+                    let end = searched + pos + delim.len();
+                    buf.truncate(end);
+                    self.consume(end - prev);
+                    return Ok(end - start);
+                }
+                None => {
+                    let n = available.len();
+                    self.consume(n);
+                }
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead> AsyncRead for BufReader<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        // Skip the buffer for reads at least as large as it, if it is empty.
+        if self.pos == self.filled && buf.len() >= self.buf.len() {
+            return self.inner.read(buf).await;
+        }
+        let available = self.fill_buf().await?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::ChunksReader;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn delimiter_split_across_reads() {
+        let data = b"GET / HTTP/1.1\r\nHost: a\r\n\r\nbody";
+        // Split the data at every possible size, so that the delimiter
+        // arrives in pieces.
+        for max in 1..=data.len() {
+            block_on(async {
+                let mut reader = BufReader::with_capacity(3, ChunksReader::new(data.chunks(max)));
+                let mut head = b"prefix\r\n\r".to_vec();
+                let n = reader
+                    .read_until_slice(b"\r\n\r\n", &mut head)
+                    .await
+                    .unwrap();
+                assert_eq!(n, 27, "{max}");
+                assert_eq!(&head[9..], b"GET / HTTP/1.1\r\nHost: a\r\n\r\n");
+
+                let mut body = Vec::new();
+                reader.read_to_end(&mut body).await.unwrap();
+                assert_eq!(body, b"body", "{max}");
+            });
+        }
+    }
+
+    #[test]
+    fn eof_without_delimiter() {
+        block_on(async {
+            let mut reader = BufReader::new(ChunksReader::new([&b"a,b"[..], b"c"]));
+            let mut buf = Vec::new();
+            assert_eq!(reader.read_until_slice(b",", &mut buf).await.unwrap(), 2);
+            assert_eq!(reader.buffer(), b"b");
+            assert_eq!(reader.read_until_slice(b",", &mut buf).await.unwrap(), 2);
+            assert_eq!(buf, b"a,bc");
+            assert_eq!(reader.read_until_slice(b",", &mut buf).await.unwrap(), 0);
+        })
+    }
+}
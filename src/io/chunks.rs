@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+
+use super::AsyncRead;
+
+/// A reader that yields a fixed sequence of byte chunks.
+///
+/// Each call to [`read`](AsyncRead::read) returns bytes from at most one
+/// chunk, so a `ChunksReader` reproduces the partial reads of a real network
+/// stream. This makes it useful for replaying recorded bodies, and for
+/// testing parsers against data that arrives fragmented at awkward places.
+///
+/// Empty chunks are skipped rather than being reported as end-of-file. Once
+/// every chunk has been consumed, reads return `0`.
+///
+/// # Example
+///
+/// ```
+/// # wstd::runtime::block_on(async {
+/// use wstd::io::{AsyncRead, ChunksReader};
+///
+/// let mut reader = ChunksReader::new(["hel".as_bytes(), b"lo"]);
+/// let mut buf = [0; 16];
+/// assert_eq!(reader.read(&mut buf).await?, 3);
+/// assert_eq!(reader.read(&mut buf).await?, 2);
+/// assert_eq!(reader.read(&mut buf).await?, 0);
+/// # std::io::Result::Ok(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChunksReader {
+    chunks: VecDeque<Vec<u8>>,
+    /// How much of the front chunk has already been read.
+    pos: usize,
+}
+
+impl ChunksReader {
+    /// Creates a new `ChunksReader` which yields `chunks` in order.
+    pub fn new<I>(chunks: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Vec<u8>>,
+    {
+        let chunks = chunks
+            .into_iter()
+            .map(Into::into)
+            .filter(|chunk| !chunk.is_empty())
+            .collect();
+        Self { chunks, pos: 0 }
+    }
+
+    /// Returns the number of bytes which have not been read yet.
+    pub fn remaining(&self) -> usize {
+        self.chunks.iter().map(Vec::len).sum::<usize>() - self.pos
+    }
+}
+
+impl AsyncRead for ChunksReader {
+    async fn read(&mut self, buf: &mut [u8]) -> super::Result<usize> {
+        let Some(chunk) = self.chunks.front() else {
+            return Ok(0);
+        };
+        let rest = &chunk[self.pos..];
+        let n = rest.len().min(buf.len());
+        buf[..n].copy_from_slice(&rest[..n]);
+        self.pos += n;
+        if self.pos == chunk.len() {
+            self.chunks.pop_front();
+            self.pos = 0;
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn one_chunk_per_read() {
+        block_on(async {
+            let mut reader = ChunksReader::new(vec![b"ab".to_vec(), vec![], b"cde".to_vec()]);
+            assert_eq!(reader.remaining(), 5);
+            let mut buf = [0; 2];
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 2);
+            assert_eq!(&buf, b"ab");
+            // A chunk larger than the buffer is split over several reads.
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 2);
+            assert_eq!(&buf, b"cd");
+            assert_eq!(reader.remaining(), 1);
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 1);
+            assert_eq!(&buf[..1], b"e");
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 0);
+            assert_eq!(reader.remaining(), 0);
+        })
+    }
+}
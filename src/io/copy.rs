@@ -1,19 +1,349 @@
+use crate::future::FutureExt;
 use crate::io::{AsyncRead, AsyncWrite, Error};
+use crate::time::utils::timeout_err;
+use crate::time::{Duration, Instant, Timer};
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::task::Poll;
 use wasi::io::streams::StreamError;
 
 /// Copy bytes from a reader to a writer.
-pub async fn copy<R, W>(mut reader: R, mut writer: W) -> crate::io::Result<()>
+pub async fn copy<R, W>(reader: R, writer: W) -> crate::io::Result<()>
 where
     R: AsyncRead,
     W: AsyncWrite,
 {
+    copy_with_progress(reader, writer, |_| {}).await
+}
+
+/// Copy bytes from a reader to a writer, reporting progress along the way.
+///
+/// After each chunk has been written, `progress` is called with the total
+/// number of bytes copied so far.
+pub async fn copy_with_progress<R, W, F>(reader: R, writer: W, progress: F) -> crate::io::Result<()>
+where
+    R: AsyncRead,
+    W: AsyncWrite,
+    F: FnMut(u64),
+{
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    copy_inner(reader, writer, progress, || &mut buf[..]).await
+}
+
+const DEFAULT_BUF_SIZE: usize = 1024;
+
+/// Copy bytes in both directions between two endpoints at the same time.
+///
+/// Each endpoint is given as a `(reader, writer)` pair, such as the halves
+/// returned by [`TcpStream::split`](crate::net::TcpStream::split). Bytes read
+/// from `a` are written to `b`, and bytes read from `b` are written to `a`.
+/// Like [`copy`], each direction splices directly between WASI streams when
+/// both sides support it.
+///
+/// When one direction reaches end-of-file, its writer is flushed and dropped
+/// so that the other side observes the close, while the opposite direction
+/// keeps running. Once both directions have finished, this returns the
+/// number of bytes copied from `a` to `b` and from `b` to `a`, in that order.
+/// If either direction fails, the error is returned immediately.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::io;
+/// use wstd::net::TcpStream;
+///
+/// # async fn proxy(client: TcpStream, upstream: TcpStream) -> io::Result<()> {
+/// let (sent, received) = io::copy_bidirectional(client.split(), upstream.split()).await?;
+/// println!("proxied {sent} bytes up and {received} bytes down");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn copy_bidirectional<AR, AW, BR, BW>(
+    a: (AR, AW),
+    b: (BR, BW),
+) -> crate::io::Result<(u64, u64)>
+where
+    AR: AsyncRead,
+    AW: AsyncWrite,
+    BR: AsyncRead,
+    BW: AsyncWrite,
+{
+    let (a_reader, a_writer) = a;
+    let (b_reader, b_writer) = b;
+    let mut a_to_b = pin!(copy_half(a_reader, b_writer));
+    let mut b_to_a = pin!(copy_half(b_reader, a_writer));
+    let (mut sent, mut received) = (None, None);
+    poll_fn(|cx| {
+        if sent.is_none() {
+            if let Poll::Ready(n) = a_to_b.as_mut().poll(cx) {
+                sent = Some(n?);
+            }
+        }
+        if received.is_none() {
+            if let Poll::Ready(n) = b_to_a.as_mut().poll(cx) {
+                received = Some(n?);
+            }
+        }
+        match (sent, received) {
+            (Some(sent), Some(received)) => Poll::Ready(Ok((sent, received))),
+            _ => Poll::Pending,
+        }
+    })
+    .await
+}
+
+/// Copy bytes from a reader to a writer until the reader reaches
+/// end-of-file, or no bytes have been copied for `idle`.
+///
+/// This lets a server reclaim connections which have gone quiet, such as an
+/// echo server's `copy_until_idle(&stream, &stream, idle)`, and guards
+/// against clients which open connections and then stall. The idle period
+/// starts when the copy starts, and restarts every time a chunk has been
+/// written. A chunk which takes longer than `idle` to write, because the
+/// writer is blocked, counts as idle too.
+///
+/// Returns the number of bytes copied once the reader reaches end-of-file.
+/// Like [`copy`], this splices directly between WASI streams when both sides
+/// support it.
+///
+/// # Errors
+///
+/// Once no bytes have been copied for `idle`, the pending read or write is
+/// cancelled, and an error with the kind
+/// [`TimedOut`](std::io::ErrorKind::TimedOut) is returned.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::io::ErrorKind;
+/// use wstd::io;
+/// use wstd::net::TcpStream;
+/// use wstd::time::Duration;
+///
+/// # async fn echo(stream: TcpStream) -> io::Result<()> {
+/// match io::copy_until_idle(&stream, &stream, Duration::from_secs(30)).await {
+///     Ok(_) => println!("client closed the connection"),
+///     Err(err) if err.kind() == ErrorKind::TimedOut => println!("client went idle"),
+///     Err(err) => return Err(err),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn copy_until_idle<R, W>(
+    mut reader: R,
+    mut writer: W,
+    idle: Duration,
+) -> crate::io::Result<u64>
+where
+    R: AsyncRead,
+    W: AsyncWrite,
+{
+    let mut idle = IdleTimer::new(idle);
+    let mut copied = 0;
+
+    if let Some(reader) = reader.as_async_input_stream() {
+        if let Some(writer) = writer.as_async_output_stream() {
+            loop {
+                match idle.run(super::splice(reader, writer, u64::MAX)).await? {
+                    Ok(n) => copied += n,
+                    Err(StreamError::Closed) => return Ok(copied),
+                    Err(StreamError::LastOperationFailed(err)) => {
+                        return Err(Error::other(err.to_debug_string()));
+                    }
+                }
+                idle.reset();
+            }
+        }
+    }
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    loop {
+        let step = async {
+            let n = reader.read(&mut buf).await?;
+            writer.write_all(&buf[..n]).await?;
+            crate::io::Result::Ok(n)
+        };
+        match idle.run(step).await?? {
+            0 => return Ok(copied),
+            n => copied += n as u64,
+        }
+        idle.reset();
+    }
+}
+
+/// Tracks the idle deadline of [`copy_until_idle`].
+///
+/// WASI timers can't be moved once created, so resetting only moves the
+/// deadline; the timer is replaced when it fires early, rather than on every
+/// chunk. That way there's at most one new timer per idle period.
+struct IdleTimer {
+    idle: Duration,
+    deadline: Instant,
+    timer: Timer,
+}
+
+impl IdleTimer {
+    fn new(idle: Duration) -> Self {
+        let deadline = Instant::now() + idle;
+        Self {
+            idle,
+            deadline,
+            timer: Timer::at(deadline),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.deadline = Instant::now() + self.idle;
+    }
+
+    /// Run `fut`, failing once the deadline has passed.
+    async fn run<F: Future>(&mut self, fut: F) -> crate::io::Result<F::Output> {
+        let mut fut = pin!(fut);
+        loop {
+            if let Ok(out) = fut.as_mut().timeout(self.timer.wait()).await {
+                return Ok(out);
+            }
+            if Instant::now() >= self.deadline {
+                return Err(timeout_err("no bytes copied before the idle timeout"));
+            }
+            self.timer = Timer::at(self.deadline);
+        }
+    }
+}
+
+/// One direction of [`copy_bidirectional`]. Takes ownership of `writer` so
+/// that it is dropped, closing it, as soon as `reader` is exhausted.
+async fn copy_half<R, W>(reader: R, mut writer: W) -> crate::io::Result<u64>
+where
+    R: AsyncRead,
+    W: AsyncWrite,
+{
+    let mut copied = 0;
+    copy_with_progress(reader, &mut writer, |n| copied = n).await?;
+    writer.flush().await?;
+    Ok(copied)
+}
+
+/// Copies bytes from readers to writers, reusing one buffer between copies.
+///
+/// [`copy`] needs a fresh buffer for every call. When copying repeatedly, for
+/// example once per connection in a server, a `Copier` avoids that churn.
+///
+/// Like [`copy`], a `Copier` prefers splicing directly between WASI streams
+/// when both sides support it. Its buffer is only allocated, once, the first
+/// time a copy has to fall back to reading and writing.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::io::{self, Copier};
+/// use wstd::net::TcpListener;
+/// use wstd::iter::AsyncIterator;
+///
+/// # async fn serve() -> io::Result<()> {
+/// let listener = TcpListener::bind("127.0.0.1:8080").await?;
+/// let mut incoming = listener.incoming();
+/// let mut copier = Copier::new();
+/// while let Some(stream) = incoming.next().await {
+///     let stream = stream?;
+///     copier.copy(&stream, &stream).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Copier {
+    buf: Vec<u8>,
+    capacity: usize,
+}
+
+impl Copier {
+    /// Creates a new `Copier` with the default buffer size.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE)
+    }
+
+    /// Creates a new `Copier` whose buffer holds `capacity` bytes.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `capacity` is zero.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "Copier requires a buffer of at least one byte"
+        );
+        Self {
+            buf: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Copy bytes from a reader to a writer.
+    pub async fn copy<R, W>(&mut self, reader: R, writer: W) -> crate::io::Result<()>
+    where
+        R: AsyncRead,
+        W: AsyncWrite,
+    {
+        self.copy_with_progress(reader, writer, |_| {}).await
+    }
+
+    /// Copy bytes from a reader to a writer, reporting progress along the way.
+    ///
+    /// See [`copy_with_progress`] for details.
+    pub async fn copy_with_progress<R, W, F>(
+        &mut self,
+        reader: R,
+        writer: W,
+        progress: F,
+    ) -> crate::io::Result<()>
+    where
+        R: AsyncRead,
+        W: AsyncWrite,
+        F: FnMut(u64),
+    {
+        let Self { buf, capacity } = self;
+        copy_inner(reader, writer, progress, || {
+            if buf.is_empty() {
+                buf.resize(*capacity, 0);
+            }
+            &mut buf[..]
+        })
+        .await
+    }
+}
+
+impl Default for Copier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shared implementation of the copy functions. `buf` is only called if we
+/// can't splice and have to fall back to reading into a buffer.
+async fn copy_inner<'b, R, W, F>(
+    mut reader: R,
+    mut writer: W,
+    mut progress: F,
+    buf: impl FnOnce() -> &'b mut [u8],
+) -> crate::io::Result<()>
+where
+    R: AsyncRead,
+    W: AsyncWrite,
+    F: FnMut(u64),
+{
+    let mut copied = 0;
+
     // Optimized path when we have an `AsyncInputStream` and an
     // `AsyncOutputStream`.
     if let Some(reader) = reader.as_async_input_stream() {
         if let Some(writer) = writer.as_async_output_stream() {
             loop {
                 match super::splice(reader, writer, u64::MAX).await {
-                    Ok(_n) => (),
+                    Ok(n) => {
+                        copied += n;
+                        progress(copied);
+                    }
                     Err(StreamError::Closed) => return Ok(()),
                     Err(StreamError::LastOperationFailed(err)) => {
                         return Err(Error::other(err.to_debug_string()));
@@ -24,12 +354,118 @@ where
     }
 
     // Unoptimized case: read the input and then write it.
-    let mut buf = [0; 1024];
+    let buf = buf();
     'read: loop {
-        let bytes_read = reader.read(&mut buf).await?;
+        let bytes_read = reader.read(buf).await?;
         if bytes_read == 0 {
             break 'read Ok(());
         }
         writer.write_all(&buf[0..bytes_read]).await?;
+        copied += bytes_read as u64;
+        progress(copied);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::{AsyncRead, AsyncWrite, Cursor};
+    use crate::runtime::block_on;
+
+    #[test]
+    fn copy_with_progress_reports_totals() {
+        block_on(async {
+            let reader = Cursor::new(vec![7u8; 2500]);
+            let mut writer = Cursor::new(Vec::new());
+            let mut reports = Vec::new();
+            super::copy_with_progress(reader, &mut writer, |n| reports.push(n))
+                .await
+                .unwrap();
+            assert_eq!(writer.get_ref().len(), 2500);
+            assert_eq!(reports, [1024, 2048, 2500]);
+        })
+    }
+
+    #[test]
+    fn copier_reuses_buffer() {
+        block_on(async {
+            let mut copier = super::Copier::with_capacity(16);
+            let mut writer = Cursor::new(Vec::new());
+            copier
+                .copy(Cursor::new(b"hello ".to_vec()), &mut writer)
+                .await
+                .unwrap();
+            let buf = copier.buf.as_ptr();
+            copier
+                .copy(Cursor::new(b"world".to_vec()), &mut writer)
+                .await
+                .unwrap();
+            assert_eq!(copier.buf.as_ptr(), buf);
+            assert_eq!(copier.buf.len(), 16);
+            assert_eq!(writer.get_ref(), b"hello world");
+        })
+    }
+
+    #[test]
+    fn copy_until_idle_stops_when_quiet() {
+        use crate::task::sleep;
+        use crate::time::{Duration, Instant};
+        use std::io::ErrorKind;
+
+        block_on(async {
+            let (mut tx, rx) = crate::io::pipe();
+            let mut writer = Cursor::new(Vec::new());
+            let idle = Duration::from_millis(20);
+            let copy = super::copy_until_idle(rx, &mut writer, idle);
+            let start = Instant::now();
+            let send = async {
+                // Each gap is shorter than the idle timeout, but together
+                // they are longer: only the final silence ends the copy.
+                for _ in 0..4 {
+                    tx.write_all(b"ping").await.unwrap();
+                    sleep(Duration::from_millis(10)).await;
+                }
+                tx
+            };
+            let (res, _tx) = futures_lite::future::zip(copy, send).await;
+            assert_eq!(res.unwrap_err().kind(), ErrorKind::TimedOut);
+            assert!(start.elapsed() >= Duration::from_millis(50));
+            assert_eq!(writer.get_ref(), b"pingpingpingping");
+
+            let copied = super::copy_until_idle(Cursor::new(b"hello".to_vec()), &mut writer, idle);
+            assert_eq!(copied.await.unwrap(), 5);
+        })
+    }
+
+    #[test]
+    fn copy_bidirectional_pipes() {
+        block_on(async {
+            let (client_tx, proxy_a_rx) = crate::io::pipe();
+            let (proxy_a_tx, mut client_rx) = crate::io::pipe();
+            let (proxy_b_tx, mut server_rx) = crate::io::pipe();
+            let (server_tx, proxy_b_rx) = crate::io::pipe();
+
+            let proxy =
+                super::copy_bidirectional((proxy_a_rx, proxy_a_tx), (proxy_b_rx, proxy_b_tx));
+            let peers = async {
+                // The client half-closes first; the server only answers once
+                // it has seen end-of-file, so this exercises the half-close.
+                let mut client_tx = client_tx;
+                client_tx.write_all(b"ping").await.unwrap();
+                drop(client_tx);
+
+                let mut request = Vec::new();
+                server_rx.read_to_end(&mut request).await.unwrap();
+                assert_eq!(request, b"ping");
+                let mut server_tx = server_tx;
+                server_tx.write_all(b"pong!").await.unwrap();
+                drop(server_tx);
+
+                let mut response = Vec::new();
+                client_rx.read_to_end(&mut response).await.unwrap();
+                assert_eq!(response, b"pong!");
+            };
+            let (counts, ()) = futures_lite::future::zip(proxy, peers).await;
+            assert_eq!(counts.unwrap(), (4, 5));
+        })
     }
 }
@@ -0,0 +1,122 @@
+use super::{AsyncRead, AsyncWrite, Result};
+
+/// Wraps a reader or writer, counting the bytes which pass through it.
+///
+/// This is useful for metrics, such as accounting for the traffic of each
+/// connection in a server. Bytes are counted once a read or write returns,
+/// so the counts only include bytes which actually made it through, and
+/// not the bytes of operations which failed or were cancelled.
+///
+/// # Example
+///
+/// ```
+/// # wstd::runtime::block_on(async {
+/// use wstd::io::{self, Counted, Cursor};
+///
+/// let mut reader = Counted::new(Cursor::new("hello"));
+/// let mut writer = Counted::new(Cursor::new(Vec::new()));
+/// io::copy(&mut reader, &mut writer).await?;
+/// assert_eq!(reader.bytes_read(), 5);
+/// assert_eq!(writer.bytes_written(), 5);
+/// # std::io::Result::Ok(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Counted<S> {
+    inner: S,
+    read: u64,
+    written: u64,
+}
+
+impl<S> Counted<S> {
+    /// Wrap `inner`, starting both counts at zero.
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            read: 0,
+            written: 0,
+        }
+    }
+
+    /// The number of bytes read through this wrapper so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.read
+    }
+
+    /// The number of bytes written through this wrapper so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.written
+    }
+
+    /// Consumes this wrapper, returning the underlying value.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying value.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying value.
+    ///
+    /// Bytes read from or written to it directly aren't counted.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+}
+
+// Neither impl forwards `as_async_input_stream` or `as_async_output_stream`:
+// doing so would let `io::copy` splice the streams directly, bypassing the
+// counts.
+
+impl<S: AsyncRead> AsyncRead for Counted<S> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.inner.read(buf).await?;
+        self.read += n as u64;
+        Ok(n)
+    }
+}
+
+impl<S: AsyncWrite> AsyncWrite for Counted<S> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = self.inner.write(buf).await?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::pipe_with_capacity;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn counts_both_directions() {
+        block_on(async {
+            let (writer, reader) = pipe_with_capacity(4);
+            let mut writer = Counted::new(writer);
+            let mut reader = Counted::new(reader);
+            // Only the bytes the pipe accepted are counted.
+            assert_eq!(writer.write(b"hello").await.unwrap(), 4);
+            assert_eq!(writer.bytes_written(), 4);
+
+            let mut buf = [0; 3];
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 3);
+            assert_eq!(reader.bytes_read(), 3);
+            assert_eq!(reader.bytes_written(), 0);
+
+            writer.write_all(b"o!").await.unwrap();
+            drop(writer);
+            let mut rest = Vec::new();
+            reader.read_to_end(&mut rest).await.unwrap();
+            assert_eq!(rest, b"lo!");
+            assert_eq!(reader.bytes_read(), 6);
+        })
+    }
+}
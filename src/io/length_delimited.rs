@@ -0,0 +1,240 @@
+use super::{AsyncRead, AsyncWrite, Error, Result};
+use std::io::ErrorKind;
+
+/// The default value of [`LengthDelimited::max_frame_size`]: 8 MiB.
+const DEFAULT_MAX_FRAME_SIZE: usize = 8 * 1024 * 1024;
+
+/// Frames messages on a byte stream by prefixing each one with its length.
+///
+/// Every frame is a 4-byte unsigned length, followed by that many bytes of
+/// payload. The length is big-endian by default, which is the usual network
+/// byte order; use [`little_endian`](Self::little_endian) to change that.
+///
+/// Reading frames requires `S: AsyncRead`, and writing them requires
+/// `S: AsyncWrite`. A frame may arrive split across any number of reads.
+///
+/// # Example
+///
+/// ```
+/// # wstd::runtime::block_on(async {
+/// use wstd::io::{ChunksReader, LengthDelimited};
+///
+/// let data = [&[0, 0, 0, 5][..], b"hel", b"lo"];
+/// let mut frames = LengthDelimited::new(ChunksReader::new(data));
+/// assert_eq!(frames.read_frame().await?, b"hello");
+/// # std::io::Result::Ok(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct LengthDelimited<S> {
+    stream: S,
+    max_frame_size: usize,
+    little_endian: bool,
+}
+
+impl<S> LengthDelimited<S> {
+    /// Frame messages on `stream`, with big-endian lengths and a maximum
+    /// frame size of 8 MiB.
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            little_endian: false,
+        }
+    }
+
+    /// Set the largest payload, in bytes, that may be read or written.
+    ///
+    /// Reading or writing a larger frame fails with
+    /// [`ErrorKind::InvalidData`]. Frames are also limited to `u32::MAX`
+    /// bytes by the length prefix.
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Encode and decode the length prefix as little-endian.
+    pub fn little_endian(mut self) -> Self {
+        self.little_endian = true;
+        self
+    }
+
+    /// Returns a reference to the underlying stream.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Returns a mutable reference to the underlying stream.
+    ///
+    /// Reading from or writing to the stream directly may corrupt the
+    /// framing.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Consume the codec, returning the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    fn check_len(&self, len: usize) -> Result<()> {
+        if len > self.max_frame_size || u32::try_from(len).is_err() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "frame of {len} bytes exceeds the maximum of {} bytes",
+                    self.max_frame_size
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<S: AsyncRead> LengthDelimited<S> {
+    /// Read the next frame, returning its payload.
+    ///
+    /// If the stream ends, even cleanly between two frames, this fails with
+    /// [`ErrorKind::UnexpectedEof`]. A frame whose length exceeds the
+    /// maximum frame size fails with [`ErrorKind::InvalidData`], without its
+    /// payload being read.
+    pub async fn read_frame(&mut self) -> Result<Vec<u8>> {
+        let mut prefix = [0; 4];
+        read_exact(&mut self.stream, &mut prefix).await?;
+        let len = if self.little_endian {
+            u32::from_le_bytes(prefix)
+        } else {
+            u32::from_be_bytes(prefix)
+        } as usize;
+        self.check_len(len)?;
+
+        let mut frame = vec![0; len];
+        read_exact(&mut self.stream, &mut frame).await?;
+        Ok(frame)
+    }
+}
+
+impl<S: AsyncWrite> LengthDelimited<S> {
+    /// Write `payload` as a single frame.
+    ///
+    /// Fails with [`ErrorKind::InvalidData`] if the payload exceeds the
+    /// maximum frame size, without writing anything. The frame isn't
+    /// flushed; call [`flush`](Self::flush) to do so.
+    pub async fn write_frame(&mut self, payload: &[u8]) -> Result<()> {
+        self.check_len(payload.len())?;
+        let len = payload.len() as u32;
+        let prefix = if self.little_endian {
+            len.to_le_bytes()
+        } else {
+            len.to_be_bytes()
+        };
+        // Write the prefix and payload together, so a small frame doesn't
+        // take two writes to the underlying stream.
+        let mut frame = Vec::with_capacity(prefix.len() + payload.len());
+        frame.extend_from_slice(&prefix);
+        frame.extend_from_slice(payload);
+        self.stream.write_all(&frame).await
+    }
+
+    /// Flush the underlying stream.
+    pub async fn flush(&mut self) -> Result<()> {
+        self.stream.flush().await
+    }
+}
+
+/// Fill `buf` completely, failing with `UnexpectedEof` if the reader ends
+/// first.
+async fn read_exact<R: AsyncRead>(reader: &mut R, mut buf: &mut [u8]) -> Result<()> {
+    while !buf.is_empty() {
+        match reader.read(buf).await? {
+            0 => {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "stream ended in the middle of a frame",
+                ))
+            }
+            n => buf = &mut buf[n..],
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::{ChunksReader, Cursor};
+    use crate::runtime::block_on;
+
+    fn encode(frames: &[&[u8]], little_endian: bool) -> Vec<u8> {
+        block_on(async {
+            let mut codec = LengthDelimited::new(Cursor::new(Vec::new()));
+            if little_endian {
+                codec = codec.little_endian();
+            }
+            for frame in frames {
+                codec.write_frame(frame).await.unwrap();
+            }
+            codec.into_inner().into_inner()
+        })
+    }
+
+    #[test]
+    fn round_trip_fragmented() {
+        let frames: [&[u8]; 3] = [b"hello", b"", &[7; 300]];
+        for little_endian in [false, true] {
+            let data = encode(&frames, little_endian);
+            // Split the data at every possible size, so that both the length
+            // prefix and the payload arrive in pieces.
+            for max in 1..=8 {
+                let reader = ChunksReader::new(data.chunks(max));
+                let mut codec = LengthDelimited::new(reader);
+                if little_endian {
+                    codec = codec.little_endian();
+                }
+                block_on(async {
+                    for frame in frames {
+                        assert_eq!(codec.read_frame().await.unwrap(), frame);
+                    }
+                    let err = codec.read_frame().await.unwrap_err();
+                    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn endianness() {
+        assert_eq!(encode(&[b"ab"], false), [0, 0, 0, 2, b'a', b'b']);
+        assert_eq!(encode(&[b"ab"], true), [2, 0, 0, 0, b'a', b'b']);
+    }
+
+    #[test]
+    fn truncated_frame() {
+        block_on(async {
+            let reader = ChunksReader::new([&[0, 0, 0, 5][..], b"hel"]);
+            let err = LengthDelimited::new(reader).read_frame().await;
+            assert_eq!(err.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        })
+    }
+
+    #[test]
+    fn max_frame_size() {
+        let data = encode(&[b"0123456789"], false);
+        block_on(async {
+            let mut codec = LengthDelimited::new(ChunksReader::new([data])).max_frame_size(9);
+            let err = codec.read_frame().await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+            assert_eq!(
+                codec.get_ref().remaining(),
+                10,
+                "the payload is left unread"
+            );
+
+            let mut codec = LengthDelimited::new(Cursor::new(Vec::new())).max_frame_size(9);
+            let err = codec.write_frame(b"0123456789").await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+            codec.write_frame(b"012345678").await.unwrap();
+            assert_eq!(codec.into_inner().into_inner().len(), 13);
+        })
+    }
+}
@@ -1,22 +1,46 @@
 //! Async IO abstractions.
 
+mod broadcast;
+mod buf_reader;
+mod chunks;
 mod copy;
+mod counted;
 mod cursor;
 mod empty;
+mod length_delimited;
+mod pipe;
 mod read;
 mod seek;
+#[cfg(any(test, feature = "test-util"))]
+mod slow;
 mod stdio;
 mod streams;
+mod tee;
+mod throttle;
+mod timeout;
+mod transform;
 mod write;
 
 pub use crate::runtime::AsyncPollable;
+pub use broadcast::*;
+pub use buf_reader::*;
+pub use chunks::*;
 pub use copy::*;
+pub use counted::*;
 pub use cursor::*;
 pub use empty::*;
+pub use length_delimited::*;
+pub use pipe::*;
 pub use read::*;
 pub use seek::*;
+#[cfg(any(test, feature = "test-util"))]
+pub use slow::*;
 pub use stdio::*;
 pub use streams::*;
+pub use tee::*;
+pub use throttle::*;
+pub use timeout::*;
+pub use transform::*;
 pub use write::*;
 
 /// The error type for I/O operations.
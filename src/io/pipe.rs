@@ -0,0 +1,210 @@
+use super::{AsyncRead, AsyncWrite, Result};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::poll_fn;
+use std::rc::Rc;
+use std::task::{Poll, Waker};
+
+/// The default maximum number of bytes buffered by a [`pipe`].
+const DEFAULT_CAPACITY: usize = 64 * 1024;
+
+/// Create an in-memory pipe, buffering up to 64 KiB.
+///
+/// Bytes written to the [`PipeWriter`] can be read from the [`PipeReader`].
+/// Reads wait until data is available, and return `0` (end of stream) once
+/// the writer has been dropped and all buffered data has been read. Writes
+/// wait while the buffer is full, and fail with
+/// [`BrokenPipe`](std::io::ErrorKind::BrokenPipe) once the reader has been
+/// dropped.
+///
+/// # Single-threaded use
+///
+/// A pipe does not use any WASI pollables: the two halves wake each other up
+/// directly. Since WASI 0.2 is single-threaded, both halves must be driven by
+/// the same `wstd::runtime::block_on`, for example by joining the producer
+/// and the consumer into one future. Neither half is `Send`.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures_lite::future::zip;
+/// use wstd::io::{self, AsyncRead, AsyncWrite};
+///
+/// #[wstd::main]
+/// async fn main() -> io::Result<()> {
+///     let (mut writer, mut reader) = io::pipe();
+///     let produce = async move {
+///         writer.write_all(b"meow").await
+///         // `writer` is dropped here, closing the pipe.
+///     };
+///     let consume = async move {
+///         let mut buf = Vec::new();
+///         reader.read_to_end(&mut buf).await.map(|_| buf)
+///     };
+///     let (written, read) = zip(produce, consume).await;
+///     written?;
+///     assert_eq!(read?, b"meow");
+///     Ok(())
+/// }
+/// ```
+pub fn pipe() -> (PipeWriter, PipeReader) {
+    pipe_with_capacity(DEFAULT_CAPACITY)
+}
+
+/// Create an in-memory pipe, buffering up to `capacity` bytes.
+///
+/// See [`pipe`] for more.
+///
+/// # Panics
+///
+/// This function will panic if `capacity` is zero.
+pub fn pipe_with_capacity(capacity: usize) -> (PipeWriter, PipeReader) {
+    assert!(capacity > 0, "pipe capacity must be at least one byte");
+    let shared = Rc::new(RefCell::new(Shared {
+        buf: VecDeque::new(),
+        capacity,
+        reader_closed: false,
+        writer_closed: false,
+        reader_waker: None,
+        writer_waker: None,
+    }));
+    (
+        PipeWriter {
+            shared: shared.clone(),
+        },
+        PipeReader { shared },
+    )
+}
+
+/// State shared between both halves of a pipe.
+#[derive(Debug)]
+struct Shared {
+    buf: VecDeque<u8>,
+    capacity: usize,
+    reader_closed: bool,
+    writer_closed: bool,
+    reader_waker: Option<Waker>,
+    writer_waker: Option<Waker>,
+}
+
+/// The reading half of a [`pipe`].
+#[derive(Debug)]
+pub struct PipeReader {
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl AsyncRead for PipeReader {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        poll_fn(|cx| {
+            let mut shared = self.shared.borrow_mut();
+            if shared.buf.is_empty() {
+                if shared.writer_closed {
+                    return Poll::Ready(Ok(0));
+                }
+                shared.reader_waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            let len = buf.len().min(shared.buf.len());
+            for (dst, src) in buf.iter_mut().zip(shared.buf.drain(..len)) {
+                *dst = src;
+            }
+            if let Some(waker) = shared.writer_waker.take() {
+                waker.wake();
+            }
+            Poll::Ready(Ok(len))
+        })
+        .await
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.reader_closed = true;
+        if let Some(waker) = shared.writer_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The writing half of a [`pipe`].
+#[derive(Debug)]
+pub struct PipeWriter {
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl AsyncWrite for PipeWriter {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        poll_fn(|cx| {
+            let mut shared = self.shared.borrow_mut();
+            if shared.reader_closed {
+                return Poll::Ready(Err(std::io::ErrorKind::BrokenPipe.into()));
+            }
+            let available = shared.capacity - shared.buf.len();
+            if available == 0 {
+                shared.writer_waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            let len = buf.len().min(available);
+            shared.buf.extend(&buf[..len]);
+            if let Some(waker) = shared.reader_waker.take() {
+                waker.wake();
+            }
+            Poll::Ready(Ok(len))
+        })
+        .await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.writer_closed = true;
+        if let Some(waker) = shared.reader_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use futures_lite::future::zip;
+
+    #[test]
+    fn pipe_applies_backpressure() {
+        block_on(async {
+            let (mut writer, mut reader) = pipe_with_capacity(4);
+            let message = b"the quick brown fox jumps over the lazy dog";
+            let produce = async move { writer.write_all(message).await };
+            let consume = async move {
+                let mut buf = Vec::new();
+                reader.read_to_end(&mut buf).await.map(|_| buf)
+            };
+            let (written, read) = zip(produce, consume).await;
+            written.unwrap();
+            assert_eq!(read.unwrap(), message);
+        })
+    }
+
+    #[test]
+    fn pipe_reader_dropped() {
+        block_on(async {
+            let (mut writer, reader) = pipe();
+            drop(reader);
+            let err = writer.write(b"meow").await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+        })
+    }
+}
@@ -1,23 +1,70 @@
+use crate::future::FutureExt;
 use crate::io;
+use crate::iter::AsyncIterator;
+use crate::time::{Duration, Timer};
 
-const CHUNK_SIZE: usize = 2048;
+pub(super) const CHUNK_SIZE: usize = 2048;
 
 /// Read bytes from a source.
 pub trait AsyncRead {
     async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
     async fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
-        // total bytes written to buf
+        // total bytes appended to buf
         let mut n = 0;
 
         loop {
-            // grow buf if empty
-            if buf.len() == n {
-                buf.resize(n + CHUNK_SIZE, 0u8);
+            let len = self.read_buf(buf).await?;
+            if len == 0 {
+                return Ok(n);
             }
 
-            let len = self.read(&mut buf[n..]).await?;
+            n += len;
+        }
+    }
+
+    /// Like [`read_to_end`](AsyncRead::read_to_end), but fails with
+    /// [`TimedOut`](std::io::ErrorKind::TimedOut) if EOF isn't reached
+    /// within `duration`.
+    ///
+    /// The deadline bounds the whole operation, not each individual read, so
+    /// a peer which keeps sending a byte at a time can't stretch it out. A
+    /// single timer is shared by all the reads. Bytes read before the
+    /// deadline passed are left in `buf`, even on timeout.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wstd::io::{stdin, AsyncRead};
+    /// use wstd::time::Duration;
+    ///
+    /// # async fn run() -> std::io::Result<()> {
+    /// let mut input = Vec::new();
+    /// stdin()
+    ///     .read_to_end_timeout(&mut input, Duration::from_secs(5))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn read_to_end_timeout(
+        &mut self,
+        buf: &mut Vec<u8>,
+        duration: Duration,
+    ) -> io::Result<usize> {
+        let deadline = Timer::after(duration);
+        let mut n = 0;
+
+        loop {
+            let start = buf.len();
+            let len = match self.read_buf(buf).timeout(deadline.wait()).await {
+                Ok(res) => res?,
+                Err(err) => {
+                    // The cancelled read may have left the spare capacity it
+                    // was reading into in `buf`.
+                    buf.truncate(start);
+                    return Err(err);
+                }
+            };
             if len == 0 {
-                buf.truncate(n);
                 return Ok(n);
             }
 
@@ -25,8 +72,81 @@ pub trait AsyncRead {
         }
     }
 
+    /// Read some bytes, appending them to `buf`.
+    ///
+    /// This reads into the spare capacity of `buf`, growing it first if it
+    /// has none, and returns the number of bytes appended. A return value of
+    /// `0` means the reader has reached EOF.
+    ///
+    /// The default implementation zeroes at most one chunk of the spare
+    /// capacity, and then calls [`read`](AsyncRead::read) on that chunk.
+    /// Readers backed directly by a WASI stream, such as
+    /// [`AsyncInputStream`](io::AsyncInputStream) and
+    /// [`TcpStream`](crate::net::TcpStream), override it to append the bytes
+    /// WASI returns without initializing the spare capacity first.
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        if buf.len() == buf.capacity() {
+            buf.reserve(CHUNK_SIZE);
+        }
+
+        // Only zero one chunk, rather than all the spare capacity, so that
+        // repeated short reads into a large buffer don't redo the work.
+        let start = buf.len();
+        let end = (start + CHUNK_SIZE).min(buf.capacity());
+        buf.resize(end, 0u8);
+        let res = self.read(&mut buf[start..]).await;
+        let len = *res.as_ref().unwrap_or(&0);
+        buf.truncate(start + len);
+        res
+    }
+
+    /// Transforms this reader into an [`AsyncIterator`] over its bytes.
+    ///
+    /// The returned iterator yields `None` once the reader reaches EOF. Reads
+    /// from the underlying reader are buffered, so bytes may be read ahead
+    /// of what has been yielded so far; they are discarded if the iterator
+    /// is dropped.
+    fn bytes(self) -> Bytes<Self>
+    where
+        Self: Sized,
+    {
+        Bytes {
+            inner: self,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Wraps this reader so that each read fails with
+    /// [`TimedOut`](std::io::ErrorKind::TimedOut) if it doesn't complete within
+    /// `duration`.
+    ///
+    /// If the reader is also an [`AsyncWrite`](io::AsyncWrite), writes time out
+    /// too. See [`WithTimeout`](io::WithTimeout) for more.
+    fn with_timeout(self, duration: Duration) -> io::WithTimeout<Self>
+    where
+        Self: Sized,
+    {
+        io::WithTimeout::new(self, duration)
+    }
+
+    /// Wraps this reader so that it is closed once no read completes for
+    /// `idle`.
+    ///
+    /// If the reader is also an [`AsyncWrite`](io::AsyncWrite), writes count
+    /// as activity too. See [`IdleTimeout`](io::IdleTimeout) for more.
+    fn with_idle_timeout(self, idle: Duration) -> io::IdleTimeout<Self>
+    where
+        Self: Sized,
+    {
+        io::IdleTimeout::new(self, idle)
+    }
+
     // If the `AsyncRead` implementation is an unbuffered wrapper around an
-    // `AsyncInputStream`, some I/O operations can be more efficient.
+    // `AsyncInputStream`, some I/O operations can be more efficient: `io::copy`
+    // splices from the stream directly, without calling `read`. Wrappers
+    // which do more than forward `read`, such as counting bytes, mustn't
+    // return the stream.
     #[inline]
     fn as_async_input_stream(&self) -> Option<&io::AsyncInputStream> {
         None
@@ -44,8 +164,136 @@ impl<R: AsyncRead + ?Sized> AsyncRead for &mut R {
         (**self).read_to_end(buf).await
     }
 
+    #[inline]
+    async fn read_to_end_timeout(
+        &mut self,
+        buf: &mut Vec<u8>,
+        duration: Duration,
+    ) -> io::Result<usize> {
+        (**self).read_to_end_timeout(buf, duration).await
+    }
+
+    #[inline]
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        (**self).read_buf(buf).await
+    }
+
     #[inline]
     fn as_async_input_stream(&self) -> Option<&io::AsyncInputStream> {
         (**self).as_async_input_stream()
     }
 }
+
+/// An iterator over the bytes of a reader.
+///
+/// This `struct` is created by the [`bytes`] method on [`AsyncRead`]. See its
+/// documentation for more.
+///
+/// [`bytes`]: AsyncRead::bytes
+#[derive(Debug)]
+pub struct Bytes<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: AsyncRead> AsyncIterator for Bytes<R> {
+    type Item = io::Result<u8>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.buf.len() {
+            self.buf.resize(CHUNK_SIZE, 0u8);
+            let len = match self.inner.read(&mut self.buf).await {
+                Ok(len) => len,
+                Err(err) => {
+                    self.buf.clear();
+                    self.pos = 0;
+                    return Some(Err(err));
+                }
+            };
+            self.buf.truncate(len);
+            self.pos = 0;
+            if len == 0 {
+                return None;
+            }
+        }
+        let byte = self.buf[self.pos];
+        self.pos += 1;
+        Some(Ok(byte))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::{AsyncWrite, Cursor};
+    use crate::iter::AsyncIteratorExt;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn read_buf_zeroes_one_chunk() {
+        block_on(async {
+            let mut reader = Cursor::new(vec![7u8; 3 * CHUNK_SIZE]);
+            let mut buf = Vec::with_capacity(4 * CHUNK_SIZE);
+            assert_eq!(reader.read_buf(&mut buf).await.unwrap(), CHUNK_SIZE);
+            assert_eq!(buf.len(), CHUNK_SIZE);
+            let mut rest = Vec::new();
+            reader.read_to_end(&mut rest).await.unwrap();
+            assert_eq!(rest.len(), 2 * CHUNK_SIZE);
+        })
+    }
+
+    #[test]
+    fn bytes_until_eof() {
+        block_on(async {
+            let bytes = Cursor::new(b"meow").bytes();
+            let out: Vec<u8> = bytes.try_collect().await.unwrap();
+            assert_eq!(out, b"meow");
+        })
+    }
+
+    #[test]
+    fn read_to_end_timeout() {
+        block_on(async {
+            let mut reader = io::ChunksReader::new([b"me", b"ow"]);
+            let mut buf = Vec::new();
+            let n = reader
+                .read_to_end_timeout(&mut buf, Duration::from_secs(10))
+                .await
+                .unwrap();
+            assert_eq!((n, &buf[..]), (4, &b"meow"[..]));
+
+            // The writer is kept open but stalls, so EOF never arrives.
+            let (mut writer, mut reader) = io::pipe();
+            writer.write_all(b"meow").await.unwrap();
+            let mut buf = Vec::new();
+            let err = reader
+                .read_to_end_timeout(&mut buf, Duration::from_millis(10))
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+            assert_eq!(buf, b"meow", "bytes read before the deadline are kept");
+        })
+    }
+
+    #[test]
+    fn read_buf_appends() {
+        block_on(async {
+            let mut reader = Cursor::new(vec![7u8; 5000]);
+            let mut buf = Vec::with_capacity(16);
+            buf.extend_from_slice(b"head");
+            assert_eq!(reader.read_buf(&mut buf).await.unwrap(), 12);
+            assert_eq!(buf.len(), 16);
+            assert_eq!(&buf[..4], b"head");
+
+            // Out of spare capacity: grows rather than reading nothing.
+            assert!(reader.read_buf(&mut buf).await.unwrap() > 0);
+
+            let n = reader.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(buf.len(), 5004);
+            assert!(n > 0);
+            assert_eq!(reader.read_buf(&mut buf).await.unwrap(), 0);
+            assert_eq!(buf.len(), 5004);
+        })
+    }
+}
@@ -0,0 +1,114 @@
+use super::{AsyncRead, Result};
+use crate::task::sleep;
+use crate::time::Duration;
+
+/// Wraps a reader to deliver its bytes slowly, for testing timeouts.
+///
+/// Every read sleeps for a fixed delay before reading from the inner reader.
+/// With [`new`](SlowReader::new), each read then returns as many bytes as it
+/// otherwise would; with [`per_byte`](SlowReader::per_byte), it returns at
+/// most a single byte, so the delay applies to every byte. The timing doesn't
+/// depend on how fast anything else is, which makes it possible to trigger
+/// timeouts such as [`IdleTimeout`](super::IdleTimeout) deterministically.
+///
+/// The timeouts of [`Client`](crate::http::Client) are enforced by the host
+/// rather than by `wstd`, so wrapping a response body in a `SlowReader`
+/// doesn't trigger them: by then, the host has already received the bytes.
+///
+/// This is only available with the `test-util` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// # wstd::runtime::block_on(async {
+/// use wstd::io::{AsyncRead, Cursor, SlowReader};
+/// use wstd::time::Duration;
+///
+/// let reader = SlowReader::per_byte(Cursor::new("meow"), Duration::from_millis(50));
+/// let mut reader = reader.with_idle_timeout(Duration::from_millis(10));
+/// let err = reader.read(&mut [0; 4]).await.unwrap_err();
+/// assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct SlowReader<R> {
+    inner: R,
+    delay: Duration,
+    per_byte: bool,
+}
+
+impl<R> SlowReader<R> {
+    /// Wrap `inner`, sleeping for `delay` before every read.
+    pub fn new(inner: R, delay: Duration) -> Self {
+        Self {
+            inner,
+            delay,
+            per_byte: false,
+        }
+    }
+
+    /// Wrap `inner`, sleeping for `delay` before every byte.
+    pub fn per_byte(inner: R, delay: Duration) -> Self {
+        Self {
+            inner,
+            delay,
+            per_byte: true,
+        }
+    }
+
+    /// Consumes this wrapper, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+}
+
+// `as_async_input_stream` is deliberately not forwarded: doing so would let
+// `io::copy` splice from the stream directly, skipping the delay.
+
+impl<R: AsyncRead> AsyncRead for SlowReader<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        sleep(self.delay).await;
+        let len = if self.per_byte { 1 } else { buf.len() };
+        self.inner.read(&mut buf[..len]).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::Cursor;
+    use crate::runtime::block_on;
+    use crate::time::Instant;
+
+    #[test]
+    fn delays_every_read() {
+        block_on(async {
+            let mut reader = SlowReader::new(Cursor::new("meow"), Duration::from_millis(10));
+            let start = Instant::now();
+            let mut buf = [0; 8];
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 4);
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 0);
+            assert!(start.elapsed() >= Duration::from_millis(20));
+
+            let mut reader = SlowReader::per_byte(Cursor::new("meow"), Duration::from_millis(5));
+            let start = Instant::now();
+            let mut out = Vec::new();
+            reader.read_to_end(&mut out).await.unwrap();
+            assert_eq!(out, b"meow");
+            assert!(start.elapsed() >= Duration::from_millis(20));
+        })
+    }
+}
@@ -32,6 +32,11 @@ impl AsyncRead for Stdin {
         self.stream.read(buf).await
     }
 
+    #[inline]
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        self.stream.read_buf(buf).await
+    }
+
     #[inline]
     async fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
         self.stream.read_to_end(buf).await
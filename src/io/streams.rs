@@ -1,8 +1,13 @@
 use super::{AsyncPollable, AsyncRead, AsyncWrite};
+use crate::task::yield_now;
 use std::cell::RefCell;
 use std::io::Result;
 use wasi::io::streams::{InputStream, OutputStream, StreamError};
 
+/// How many empty reads in a row an [`AsyncInputStream`] tolerates from a
+/// stream which reports being ready, before yielding to the event loop.
+const MAX_EMPTY_READS: u32 = 16;
+
 #[derive(Debug)]
 pub struct AsyncInputStream {
     // Lazily initialized pollable, used for lifetime of stream to check readiness.
@@ -32,27 +37,76 @@ impl AsyncInputStream {
             .wait_for()
             .await;
     }
+    /// Check, without blocking, whether the stream is ready to be read from.
+    ///
+    /// A stream is ready when data is available, or when it has been closed
+    /// or failed. This is only a hint: by the time the stream is read, it
+    /// may no longer be ready, in which case the read will wait.
+    pub fn is_ready(&self) -> bool {
+        self.stream.subscribe().ready()
+    }
     /// Like [`AsyncRead::read`], but doesn't require a `&mut self`.
+    ///
+    /// Hosts may report the stream as ready and then have nothing to read,
+    /// in which case the read waits for readiness again. A host which keeps
+    /// doing so would make this spin, so after a few empty reads in a row the
+    /// read [yields](crate::task::yield_now) to the event loop, to let other
+    /// futures make progress before it tries again.
     pub async fn read(&self, buf: &mut [u8]) -> Result<usize> {
-        self.ready().await;
+        if buf.is_empty() {
+            return Ok(0);
+        }
         // Ideally, the ABI would be able to read directly into buf. However, with the default
         // generated bindings, it returns a newly allocated vec, which we need to copy into buf.
-        let read = match self.stream.read(buf.len() as u64) {
-            // We don't need to special-case 0 here: a value of 0 bytes from
-            // WASI's `read` doesn't mean end-of-stream as it does in Rust,
-            // however since we called `self.ready()`, we'll always get at
-            // least one byte.
-            Ok(r) => r,
-            // 0 bytes from Rust's `read` means end-of-stream.
-            Err(StreamError::Closed) => return Ok(0),
-            Err(StreamError::LastOperationFailed(err)) => {
-                return Err(std::io::Error::other(err.to_debug_string()))
-            }
-        };
+        let read = self.read_chunk(buf.len() as u64).await?;
         let len = read.len();
         buf[0..len].copy_from_slice(&read);
         Ok(len)
     }
+    /// Like [`AsyncRead::read_buf`], but doesn't require a `&mut self`.
+    ///
+    /// Reads at most as many bytes as `buf` has spare capacity, growing it
+    /// first if it has none, and appends them to `buf` without initializing
+    /// the spare capacity.
+    pub async fn read_buf(&self, buf: &mut Vec<u8>) -> Result<usize> {
+        if buf.len() == buf.capacity() {
+            buf.reserve(super::read::CHUNK_SIZE);
+        }
+        let spare = buf.capacity() - buf.len();
+        let read = self.read_chunk(spare as u64).await?;
+        buf.extend_from_slice(&read);
+        Ok(read.len())
+    }
+    /// Read at most `len` bytes, waiting until at least one is available.
+    /// Returns no bytes at end-of-stream.
+    async fn read_chunk(&self, len: u64) -> Result<Vec<u8>> {
+        let mut empty_reads = 0;
+        loop {
+            self.ready().await;
+            match self.stream.read(len) {
+                // A value of 0 bytes from WASI's `read` doesn't mean
+                // end-of-stream as it does in Rust. Hosts may report a
+                // stream as ready and then have nothing to read, e.g. a
+                // socket whose readiness was signalled by a previous read,
+                // so wait for readiness again. A host which keeps doing that
+                // would starve every other future, so let them run now and
+                // then.
+                Ok(r) if r.is_empty() => {
+                    empty_reads += 1;
+                    if empty_reads == MAX_EMPTY_READS {
+                        empty_reads = 0;
+                        yield_now().await;
+                    }
+                }
+                Ok(r) => return Ok(r),
+                // 0 bytes from Rust's `read` means end-of-stream.
+                Err(StreamError::Closed) => return Ok(Vec::new()),
+                Err(StreamError::LastOperationFailed(err)) => {
+                    return Err(std::io::Error::other(err.to_debug_string()))
+                }
+            }
+        }
+    }
 }
 
 impl AsyncRead for AsyncInputStream {
@@ -60,6 +114,10 @@ impl AsyncRead for AsyncInputStream {
         Self::read(self, buf).await
     }
 
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        Self::read_buf(self, buf).await
+    }
+
     #[inline]
     fn as_async_input_stream(&self) -> Option<&AsyncInputStream> {
         Some(self)
@@ -95,7 +153,23 @@ impl AsyncOutputStream {
             .wait_for()
             .await;
     }
+    /// Check, without blocking, whether the stream is ready to be written to.
+    ///
+    /// A stream is ready when it can accept more data, or when it has been
+    /// closed or failed. This is only a hint: by the time the stream is
+    /// written to, it may no longer be ready, in which case the write will
+    /// wait.
+    pub fn is_ready(&self) -> bool {
+        self.stream.subscribe().ready()
+    }
     /// Like [`AsyncWrite::write`], but doesn't require a `&mut self`.
+    ///
+    /// # Errors
+    ///
+    /// If the stream has been closed, for example because the peer of a
+    /// socket went away, this returns an error of kind
+    /// [`ConnectionReset`](std::io::ErrorKind::ConnectionReset). This is
+    /// usually not fatal: a server can drop the connection and carry on.
     pub async fn write(&self, buf: &[u8]) -> Result<usize> {
         // Loops at most twice.
         loop {
@@ -127,12 +201,20 @@ impl AsyncOutputStream {
         }
     }
     /// Like [`AsyncWrite::flush`], but doesn't require a `&mut self`.
+    ///
+    /// This waits until all previously written bytes have been handed off,
+    /// and returns an error if doing so failed.
     pub async fn flush(&self) -> Result<()> {
-        match self.stream.flush() {
+        let result = match self.stream.flush() {
             Ok(()) => {
                 self.ready().await;
-                Ok(())
+                // A failed flush is only reported by the next operation.
+                self.stream.check_write().map(|_| ())
             }
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(()) => Ok(()),
             Err(StreamError::Closed) => {
                 Err(std::io::Error::from(std::io::ErrorKind::ConnectionReset))
             }
@@ -0,0 +1,130 @@
+use super::{AsyncWrite, Result};
+
+/// Create a writer which writes everything to both `primary` and `secondary`.
+///
+/// This is useful to keep a copy of data while sending it, such as capturing
+/// an outgoing body into a buffer for debugging. See [`Tee`] for how partial
+/// writes and errors are handled.
+///
+/// # Example
+///
+/// ```
+/// # wstd::runtime::block_on(async {
+/// use wstd::io::{self, AsyncWrite, Cursor};
+///
+/// let mut out = io::tee(Cursor::new(Vec::new()), Cursor::new(Vec::new()));
+/// out.write_all(b"hello").await?;
+/// let (sent, copy) = out.into_inner();
+/// assert_eq!(sent.into_inner(), b"hello");
+/// assert_eq!(copy.into_inner(), b"hello");
+/// # std::io::Result::Ok(())
+/// # }).unwrap();
+/// ```
+pub fn tee<A: AsyncWrite, B: AsyncWrite>(primary: A, secondary: B) -> Tee<A, B> {
+    Tee { primary, secondary }
+}
+
+/// A writer which mirrors every write to two writers.
+///
+/// Each call to `write` first writes to the primary writer, which may accept
+/// fewer bytes than given. Exactly the bytes it accepted are then written in
+/// full to the secondary writer, and their count is returned. This way both
+/// writers always receive the same bytes, and the secondary writer never gets
+/// ahead of the primary one. `flush` flushes both writers.
+///
+/// # Errors
+///
+/// An error from the primary writer is returned as-is, and nothing is
+/// written to the secondary writer. An error from the secondary writer is
+/// returned too, but the primary writer has already accepted the bytes by
+/// then, so it is ahead of the secondary writer by up to a whole write. If
+/// flushing the primary writer fails, the secondary writer is still flushed,
+/// and the primary writer's error is returned.
+///
+/// This `struct` is created by the [`tee`] function. See its documentation
+/// for more.
+#[derive(Debug)]
+pub struct Tee<A, B> {
+    primary: A,
+    secondary: B,
+}
+
+impl<A, B> Tee<A, B> {
+    /// Consumes the tee, returning the primary and secondary writers.
+    pub fn into_inner(self) -> (A, B) {
+        (self.primary, self.secondary)
+    }
+
+    /// Gets references to the primary and secondary writers.
+    pub fn get_ref(&self) -> (&A, &B) {
+        (&self.primary, &self.secondary)
+    }
+
+    /// Gets mutable references to the primary and secondary writers.
+    ///
+    /// Writing to either of them directly means they no longer receive the
+    /// same bytes.
+    pub fn get_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.primary, &mut self.secondary)
+    }
+}
+
+impl<A: AsyncWrite, B: AsyncWrite> AsyncWrite for Tee<A, B> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = self.primary.write(buf).await?;
+        self.secondary.write_all(&buf[..n]).await?;
+        Ok(n)
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let primary = self.primary.flush().await;
+        let secondary = self.secondary.flush().await;
+        primary.and(secondary)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::{pipe_with_capacity, AsyncRead, Cursor};
+    use crate::runtime::block_on;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn partial_writes() {
+        block_on(async {
+            let (writer, mut reader) = pipe_with_capacity(4);
+            let mut out = tee(writer, Cursor::new(Vec::new()));
+            assert_eq!(out.write(b"hello").await.unwrap(), 4);
+            assert_eq!(out.get_ref().1.get_ref(), b"hell");
+
+            let mut buf = [0; 8];
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 4);
+            out.write_all(b"o").await.unwrap();
+            out.flush().await.unwrap();
+            let (_, copy) = out.into_inner();
+            assert_eq!(copy.into_inner(), b"hello");
+        })
+    }
+
+    #[test]
+    fn errors() {
+        block_on(async {
+            // The primary fails: nothing reaches the secondary.
+            let (writer, reader) = pipe_with_capacity(4);
+            drop(reader);
+            let mut out = tee(writer, Cursor::new(Vec::new()));
+            let err = out.write(b"hello").await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+            assert!(out.get_ref().1.get_ref().is_empty());
+
+            // The secondary fails: the primary has the bytes already.
+            let (writer, reader) = pipe_with_capacity(4);
+            drop(reader);
+            let mut out = tee(Cursor::new(Vec::new()), writer);
+            let err = out.write(b"hello").await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+            assert_eq!(out.get_ref().0.get_ref(), b"hello");
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use super::{AsyncWrite, Result};
+use crate::task::sleep;
+use crate::time::{Duration, Instant};
+
+/// Wraps a writer to limit how many bytes per second are written to it.
+///
+/// Throughput is limited with a token bucket: every byte written takes a
+/// token, and tokens are replenished at `bytes_per_sec`. The bucket holds at
+/// most `burst` tokens, so after a quiet period up to `burst` bytes can be
+/// written at once. When the bucket is empty, writes sleep until tokens are
+/// available again. A single call to `write` may write fewer bytes than
+/// given, so use [`write_all`](AsyncWrite::write_all) to write everything.
+///
+/// This is useful to simulate slow peers in tests, or to stay below an
+/// upstream's rate limit.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::io::{AsyncWrite, ThrottledWriter};
+///
+/// # async fn run(stream: wstd::net::TcpStream) -> wstd::io::Result<()> {
+/// // Write at most 1 KiB per second.
+/// let mut writer = ThrottledWriter::new(stream, 1024);
+/// writer.write_all(&[0; 4096]).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ThrottledWriter<W> {
+    inner: W,
+    bytes_per_sec: u64,
+    burst: u64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl<W> ThrottledWriter<W> {
+    /// Wrap `inner`, writing at most `bytes_per_sec` bytes per second.
+    ///
+    /// The burst size is one second's worth of bytes, and the bucket starts
+    /// out full.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `bytes_per_sec` is zero.
+    pub fn new(inner: W, bytes_per_sec: u64) -> Self {
+        Self::with_burst(inner, bytes_per_sec, bytes_per_sec)
+    }
+
+    /// Wrap `inner`, writing at most `bytes_per_sec` bytes per second, in
+    /// bursts of at most `burst` bytes.
+    ///
+    /// The bucket starts out full.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `bytes_per_sec` or `burst` is zero.
+    pub fn with_burst(inner: W, bytes_per_sec: u64, burst: u64) -> Self {
+        assert!(bytes_per_sec > 0, "ThrottledWriter requires a nonzero rate");
+        assert!(burst > 0, "ThrottledWriter requires a nonzero burst size");
+        Self {
+            inner,
+            bytes_per_sec,
+            burst,
+            tokens: burst as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Consumes this wrapper, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed: std::time::Duration = now.duration_since(self.last_refill).into();
+        self.last_refill = now;
+        let tokens = self.tokens + elapsed.as_secs_f64() * self.bytes_per_sec as f64;
+        self.tokens = tokens.min(self.burst as f64);
+    }
+}
+
+// `as_async_output_stream` is deliberately not forwarded: doing so would let
+// `io::copy` splice into the stream directly, bypassing the limit.
+
+impl<W: AsyncWrite> AsyncWrite for ThrottledWriter<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        // Wait until the whole buffer, or a full burst, can be written at
+        // once, rather than waking up for every byte.
+        let wanted = buf.len().min(self.burst.try_into().unwrap_or(usize::MAX));
+        self.refill();
+        if self.tokens < wanted as f64 {
+            let wait = (wanted as f64 - self.tokens) / self.bytes_per_sec as f64;
+            sleep(Duration::from_secs_f64(wait)).await;
+            self.refill();
+        }
+        // Guard against the sleep ending a hair early.
+        let allowed = (self.tokens as usize).max(1);
+        let n = self.inner.write(&buf[..wanted.min(allowed)]).await?;
+        self.tokens -= n as f64;
+        Ok(n)
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::Cursor;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn throttled_write_takes_time() {
+        block_on(async {
+            let mut writer = ThrottledWriter::with_burst(Cursor::new(Vec::new()), 2000, 200);
+            let start = Instant::now();
+            writer.write_all(&[7; 1200]).await.unwrap();
+            let elapsed: std::time::Duration = start.elapsed().into();
+            // 200 bytes go out in the initial burst, the other 1000 take half
+            // a second.
+            assert!(elapsed.as_millis() >= 450, "too fast: {elapsed:?}");
+            assert!(elapsed.as_millis() < 1500, "too slow: {elapsed:?}");
+            assert_eq!(writer.get_ref().get_ref().len(), 1200);
+        })
+    }
+
+    #[test]
+    fn burst_is_immediate() {
+        block_on(async {
+            let mut writer = ThrottledWriter::new(Cursor::new(Vec::new()), 1000);
+            let start = Instant::now();
+            writer.write_all(&[7; 1000]).await.unwrap();
+            let elapsed: std::time::Duration = start.elapsed().into();
+            assert!(elapsed.as_millis() < 100, "too slow: {elapsed:?}");
+        })
+    }
+}
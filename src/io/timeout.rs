@@ -0,0 +1,279 @@
+use super::{AsyncRead, AsyncWrite, Result};
+use crate::future::FutureExt;
+use crate::time::utils::timeout_err;
+use crate::time::{Duration, Instant};
+
+/// Wraps a reader or writer so that each individual operation times out.
+///
+/// Every call to `read`, `write`, or `flush` races against a timer of the
+/// given duration, and fails with [`TimedOut`](std::io::ErrorKind::TimedOut)
+/// if the timer fires first. The timed-out operation is dropped, which cancels
+/// any wait on the underlying stream.
+///
+/// This `struct` is created by [`WithTimeout::new`] or the [`with_timeout`]
+/// method on [`AsyncRead`].
+///
+/// [`with_timeout`]: AsyncRead::with_timeout
+#[derive(Debug)]
+pub struct WithTimeout<T> {
+    inner: T,
+    duration: Duration,
+}
+
+impl<T> WithTimeout<T> {
+    /// Wrap `inner` so that each read and write times out after `duration`.
+    pub fn new(inner: T, duration: Duration) -> Self {
+        Self { inner, duration }
+    }
+
+    /// Consumes this wrapper, returning the underlying value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying value.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+// Neither impl forwards `as_async_input_stream` or `as_async_output_stream`:
+// doing so would let `io::copy` splice the streams directly, bypassing the
+// timeout.
+
+impl<T: AsyncRead> AsyncRead for WithTimeout<T> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf).timeout(self.duration).await?
+    }
+}
+
+impl<T: AsyncWrite> AsyncWrite for WithTimeout<T> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf).timeout(self.duration).await?
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().timeout(self.duration).await?
+    }
+}
+
+/// Wraps a reader or writer, typically a connection, so that it is closed
+/// once it has been idle for too long.
+///
+/// The wrapper is idle from the time it is created, or the last read, write,
+/// or flush completed, until the next one completes. Once it has been idle
+/// for the given duration, the pending operation fails with
+/// [`TimedOut`](std::io::ErrorKind::TimedOut), and so does every operation
+/// after it: the connection is considered closed, and should be dropped.
+///
+/// Unlike [`WithTimeout`], which limits how long each operation may take,
+/// this also counts the time between operations, so it closes connections
+/// which neither side is using. It doesn't limit how long a peer which keeps
+/// sending a byte now and then can hold a connection open; use an overall
+/// deadline, such as [`read_to_end_timeout`](AsyncRead::read_to_end_timeout),
+/// for that.
+///
+/// This `struct` is created by [`IdleTimeout::new`] or the
+/// [`with_idle_timeout`] method on [`AsyncRead`].
+///
+/// [`with_idle_timeout`]: AsyncRead::with_idle_timeout
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::io::{self, AsyncRead, AsyncWrite};
+/// use wstd::iter::AsyncIterator;
+/// use wstd::net::TcpListener;
+/// use wstd::time::Duration;
+///
+/// # async fn run(listener: TcpListener) -> io::Result<()> {
+/// let mut incoming = listener.incoming();
+/// while let Some(stream) = incoming.next().await {
+///     // Close connections after 30 seconds without activity.
+///     let mut stream = stream?.with_idle_timeout(Duration::from_secs(30));
+///     let mut buf = [0; 1024];
+///     while let Ok(n @ 1..) = stream.read(&mut buf).await {
+///         stream.write_all(&buf[..n]).await?;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct IdleTimeout<T> {
+    inner: T,
+    idle: Duration,
+    deadline: Instant,
+    expired: bool,
+}
+
+impl<T> IdleTimeout<T> {
+    /// Wrap `inner` so that it is closed once it has been idle for `idle`.
+    pub fn new(inner: T, idle: Duration) -> Self {
+        Self {
+            inner,
+            idle,
+            deadline: Instant::now() + idle,
+            expired: false,
+        }
+    }
+
+    /// Returns `true` if the idle timeout has passed, so every operation
+    /// fails.
+    pub fn is_expired(&self) -> bool {
+        self.expired
+    }
+
+    /// Consumes this wrapper, returning the underlying value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying value.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying value.
+    ///
+    /// Operations on it directly don't count as activity.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.expired {
+            return Err(idle_err());
+        }
+        Ok(())
+    }
+
+    /// Records the outcome of an operation raced against the deadline.
+    fn update<R>(&mut self, res: Result<Result<R>>) -> Result<R> {
+        match res {
+            Ok(res) => {
+                self.deadline = Instant::now() + self.idle;
+                res
+            }
+            Err(_) => {
+                self.expired = true;
+                Err(idle_err())
+            }
+        }
+    }
+}
+
+fn idle_err() -> std::io::Error {
+    timeout_err("connection was idle for too long")
+}
+
+// As for `WithTimeout`, the stream accessors aren't forwarded, so that
+// `io::copy` can't bypass the timeout.
+
+impl<T: AsyncRead> AsyncRead for IdleTimeout<T> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.check()?;
+        let res = self.inner.read(buf).timeout(self.deadline).await;
+        self.update(res)
+    }
+}
+
+impl<T: AsyncWrite> AsyncWrite for IdleTimeout<T> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.check()?;
+        let res = self.inner.write(buf).timeout(self.deadline).await;
+        self.update(res)
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.check()?;
+        let res = self.inner.flush().timeout(self.deadline).await;
+        self.update(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn read_times_out() {
+        block_on(async {
+            // Nothing is ever written, so reads never complete.
+            let (_writer, reader) = io::pipe();
+            let mut reader = reader.with_timeout(Duration::from_millis(10));
+            let err = reader.read(&mut [0; 8]).await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        })
+    }
+
+    #[test]
+    fn write_times_out() {
+        block_on(async {
+            // Nothing is ever read, so the second write waits forever.
+            let (writer, _reader) = io::pipe_with_capacity(4);
+            let mut writer = WithTimeout::new(writer, Duration::from_millis(10));
+            writer.write_all(b"meow").await.unwrap();
+            let err = writer.write(b"!").await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        })
+    }
+
+    #[test]
+    fn activity_resets_idle_timeout() {
+        block_on(async {
+            let (mut writer, reader) = io::pipe();
+            let mut reader = IdleTimeout::new(reader, Duration::from_millis(40));
+            let mut buf = [0; 8];
+            // Longer than the idle timeout in total, but never idle for long.
+            for _ in 0..4 {
+                crate::task::sleep(Duration::from_millis(15)).await;
+                writer.write_all(b"meow").await.unwrap();
+                assert_eq!(reader.read(&mut buf).await.unwrap(), 4);
+            }
+            assert!(!reader.is_expired());
+        })
+    }
+
+    #[test]
+    fn between_bytes_timeout() {
+        block_on(async {
+            let body = || io::Cursor::new("meow");
+
+            // Slow, but never slower than the timeout between two bytes.
+            let reader = io::SlowReader::per_byte(body(), Duration::from_millis(5));
+            let mut reader = IdleTimeout::new(reader, Duration::from_millis(40));
+            let mut out = Vec::new();
+            reader.read_to_end(&mut out).await.unwrap();
+            assert_eq!(out, b"meow");
+
+            let reader = io::SlowReader::per_byte(body(), Duration::from_millis(40));
+            let mut reader = IdleTimeout::new(reader, Duration::from_millis(10));
+            let err = reader.read_to_end(&mut Vec::new()).await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        })
+    }
+
+    #[test]
+    fn idle_timeout_closes() {
+        block_on(async {
+            let (mut writer, reader) = io::pipe();
+            let mut reader = IdleTimeout::new(reader, Duration::from_millis(10));
+            let err = reader.read(&mut [0; 8]).await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+            assert!(reader.is_expired());
+
+            // Closed for good, even once there is something to read.
+            writer.write_all(b"meow").await.unwrap();
+            let err = reader.read(&mut [0; 8]).await.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        })
+    }
+}
@@ -0,0 +1,207 @@
+use super::read::CHUNK_SIZE;
+use super::{AsyncRead, Result};
+
+/// A streaming transformation of bytes, such as decryption, decompression, or
+/// decoding.
+///
+/// Input is passed to [`transform`](Transform::transform) in chunks of any
+/// size, as it is read. A transform doesn't have to produce output for every
+/// chunk: a block cipher can keep the bytes of an incomplete block until more
+/// input arrives, while a stream cipher can transform every byte right away.
+/// At the end of the input, [`finalize`](Transform::finalize) is called to
+/// produce whatever is left, such as a final padded block, and to check for
+/// truncated input or a bad authentication tag.
+///
+/// See [`TransformReader`] for how transforms are applied.
+pub trait Transform {
+    /// Transform `input`, appending the result to `output`.
+    ///
+    /// Errors are returned from the read which passed `input` in.
+    fn transform(&mut self, input: &[u8], output: &mut Vec<u8>) -> Result<()>;
+
+    /// Finish the transformation once the input has ended, appending any
+    /// remaining output to `output`.
+    ///
+    /// This is called exactly once, unless a call to `transform` failed. The
+    /// default implementation does nothing.
+    fn finalize(&mut self, output: &mut Vec<u8>) -> Result<()> {
+        let _ = output;
+        Ok(())
+    }
+}
+
+/// A reader which applies a [`Transform`] to the bytes of another reader.
+///
+/// Bytes are read from the inner reader in chunks, passed through the
+/// transform, and buffered until they are read. Reads only return `0` once
+/// the inner reader has reached EOF, the transform has been finalized, and
+/// all of its output has been read.
+///
+/// # Example
+///
+/// ```
+/// # wstd::runtime::block_on(async {
+/// use wstd::io::{AsyncRead, Cursor, Transform, TransformReader};
+///
+/// struct Uppercase;
+///
+/// impl Transform for Uppercase {
+///     fn transform(&mut self, input: &[u8], output: &mut Vec<u8>) -> std::io::Result<()> {
+///         output.extend(input.iter().map(u8::to_ascii_uppercase));
+///         Ok(())
+///     }
+/// }
+///
+/// let mut reader = TransformReader::new(Cursor::new("meow"), Uppercase);
+/// let mut out = Vec::new();
+/// reader.read_to_end(&mut out).await?;
+/// assert_eq!(out, b"MEOW");
+/// # std::io::Result::Ok(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TransformReader<R, T> {
+    inner: R,
+    transform: T,
+    input: Box<[u8]>,
+    /// Transformed bytes; the unread ones are `output[pos..]`.
+    output: Vec<u8>,
+    pos: usize,
+    finished: bool,
+}
+
+impl<R, T> TransformReader<R, T> {
+    /// Apply `transform` to the bytes read from `inner`.
+    pub fn new(inner: R, transform: T) -> Self {
+        Self {
+            inner,
+            transform,
+            input: vec![0; CHUNK_SIZE].into_boxed_slice(),
+            output: Vec::new(),
+            pos: 0,
+            finished: false,
+        }
+    }
+
+    /// Consumes this wrapper, returning the underlying reader and transform.
+    ///
+    /// Transformed bytes which haven't been read yet are lost.
+    pub fn into_inner(self) -> (R, T) {
+        (self.inner, self.transform)
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly skips the transform.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+}
+
+impl<R: AsyncRead, T: Transform> AsyncRead for TransformReader<R, T> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        // A transform may produce no output for some input, so keep reading
+        // until it does.
+        while self.pos == self.output.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            self.output.clear();
+            self.pos = 0;
+            let n = self.inner.read(&mut self.input).await?;
+            if n == 0 {
+                self.finished = true;
+                self.transform.finalize(&mut self.output)?;
+            } else {
+                self.transform
+                    .transform(&self.input[..n], &mut self.output)?;
+            }
+        }
+        let available = &self.output[self.pos..];
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::ChunksReader;
+    use crate::runtime::block_on;
+    use std::io::ErrorKind;
+
+    /// A stream cipher, which transforms every byte right away.
+    struct Xor(u8);
+
+    impl Transform for Xor {
+        fn transform(&mut self, input: &[u8], output: &mut Vec<u8>) -> Result<()> {
+            output.extend(input.iter().map(|byte| byte ^ self.0));
+            Ok(())
+        }
+    }
+
+    /// A block cipher which reverses blocks of 4 bytes, and requires whole
+    /// blocks of input.
+    #[derive(Default)]
+    struct ReverseBlocks(Vec<u8>);
+
+    impl Transform for ReverseBlocks {
+        fn transform(&mut self, input: &[u8], output: &mut Vec<u8>) -> Result<()> {
+            self.0.extend_from_slice(input);
+            let whole = self.0.len() / 4 * 4;
+            for block in self.0[..whole].chunks(4) {
+                output.extend(block.iter().rev());
+            }
+            self.0.drain(..whole);
+            Ok(())
+        }
+
+        fn finalize(&mut self, _output: &mut Vec<u8>) -> Result<()> {
+            if !self.0.is_empty() {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+            Ok(())
+        }
+    }
+
+    fn read_all<T: Transform>(chunks: &[&[u8]], transform: T) -> Result<Vec<u8>> {
+        block_on(async {
+            let mut reader = TransformReader::new(ChunksReader::new(chunks.to_vec()), transform);
+            let mut out = Vec::new();
+            // Read in small pieces, so output is left over between reads.
+            let mut buf = [0; 3];
+            loop {
+                match reader.read(&mut buf).await? {
+                    0 => return Ok(out),
+                    n => out.extend_from_slice(&buf[..n]),
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn xor() {
+        let encrypted: Vec<u8> = b"attack at dawn".iter().map(|b| b ^ 0x5a).collect();
+        let (a, b) = encrypted.split_at(5);
+        assert_eq!(read_all(&[a, b], Xor(0x5a)).unwrap(), b"attack at dawn");
+        assert_eq!(read_all(&[], Xor(0x5a)).unwrap(), b"");
+    }
+
+    #[test]
+    fn blocks_split_across_reads() {
+        let out = read_all(&[b"a", b"bcdef", b"gh"], ReverseBlocks::default());
+        assert_eq!(out.unwrap(), b"dcbahgfe");
+        let err = read_all(&[b"abcdef"], ReverseBlocks::default()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::io;
+use std::fmt;
 
 /// Write bytes to a sink.
 pub trait AsyncWrite {
@@ -17,6 +18,20 @@ pub trait AsyncWrite {
         }
     }
 
+    /// Writes formatted output into this writer.
+    ///
+    /// Formatting is synchronous, so unless `args` is a plain string literal,
+    /// the output is first formatted into a temporary `String`, which is then
+    /// written with [`write_all`](AsyncWrite::write_all). This is usually
+    /// called through the [`write!`](crate::write) and
+    /// [`writeln!`](crate::writeln) macros.
+    async fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> io::Result<()> {
+        match args.as_str() {
+            Some(s) => self.write_all(s.as_bytes()).await,
+            None => self.write_all(args.to_string().as_bytes()).await,
+        }
+    }
+
     // If the `AsyncWrite` implementation is an unbuffered wrapper around an
     // `AsyncOutputStream`, some I/O operations can be more efficient.
     #[inline]
@@ -25,6 +40,51 @@ pub trait AsyncWrite {
     }
 }
 
+/// Writes formatted data into an [`AsyncWrite`].
+///
+/// This is the async counterpart of [`std::write!`]: it evaluates to a future
+/// which must be `.await`ed, and which resolves to an `io::Result<()>`. See
+/// [`AsyncWrite::write_fmt`] for how the output is buffered.
+///
+/// # Example
+///
+/// ```
+/// # wstd::runtime::block_on(async {
+/// use wstd::io::Cursor;
+///
+/// let mut out = Cursor::new(Vec::new());
+/// wstd::write!(out, "{} + {} = {}", 1, 2, 1 + 2).await?;
+/// assert_eq!(out.into_inner(), b"1 + 2 = 3");
+/// # std::io::Result::Ok(())
+/// # }).unwrap();
+/// ```
+#[macro_export]
+macro_rules! write {
+    ($dst:expr, $($arg:tt)*) => {
+        async {
+            use $crate::io::AsyncWrite as _;
+            $dst.write_fmt(::std::format_args!($($arg)*)).await
+        }
+    };
+}
+
+/// Writes formatted data into an [`AsyncWrite`], followed by a newline.
+///
+/// See [`write!`](crate::write) for details.
+#[macro_export]
+macro_rules! writeln {
+    ($dst:expr $(,)?) => {
+        $crate::write!($dst, "\n")
+    };
+    ($dst:expr, $($arg:tt)*) => {
+        async {
+            use $crate::io::AsyncWrite as _;
+            $dst.write_fmt(::std::format_args!("{}\n", ::std::format_args!($($arg)*)))
+                .await
+        }
+    };
+}
+
 impl<W: AsyncWrite + ?Sized> AsyncWrite for &mut W {
     #[inline]
     async fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
@@ -41,8 +101,33 @@ impl<W: AsyncWrite + ?Sized> AsyncWrite for &mut W {
         (**self).write_all(buf).await
     }
 
+    #[inline]
+    async fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> io::Result<()> {
+        (**self).write_fmt(args).await
+    }
+
     #[inline]
     fn as_async_output_stream(&self) -> Option<&io::AsyncOutputStream> {
         (**self).as_async_output_stream()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::io::Cursor;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn write_macros() {
+        block_on(async {
+            let mut out = Cursor::new(Vec::new());
+            let name = "world";
+            crate::write!(out, "hello").await.unwrap();
+            crate::writeln!(out, ", {name}!").await.unwrap();
+            let writer = &mut out;
+            crate::writeln!(writer).await.unwrap();
+            crate::write!(writer, "{:03}", 7).await.unwrap();
+            assert_eq!(out.into_inner(), b"hello, world!\n\n007");
+        })
+    }
+}
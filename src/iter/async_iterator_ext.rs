@@ -0,0 +1,399 @@
+use super::{AsyncIterator, Chain, FilterMap, Retry, Scan, Take, Throttle, Zip};
+use crate::time::Duration;
+
+/// Extend `AsyncIterator` with combinators and consumers.
+pub trait AsyncIteratorExt: AsyncIterator {
+    /// Transforms an async iterator into a collection.
+    ///
+    /// This drains the iterator until it returns `None`, so it should only be
+    /// used on iterators which are known to end.
+    async fn collect<C>(mut self) -> C
+    where
+        Self: Sized,
+        C: FromIterator<Self::Item>,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = self.next().await {
+            items.push(item);
+        }
+        items.into_iter().collect()
+    }
+
+    /// Transforms an async iterator of `Result`s into a collection, returning
+    /// the first error encountered.
+    ///
+    /// Iteration stops as soon as an `Err` is yielded; no further items are
+    /// requested from the underlying iterator.
+    async fn try_collect<T, E, C>(mut self) -> Result<C, E>
+    where
+        Self: Sized + AsyncIterator<Item = Result<T, E>>,
+        C: FromIterator<T>,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = self.next().await {
+            items.push(item?);
+        }
+        Ok(items.into_iter().collect())
+    }
+
+    /// Yield an item, then ignore all further items until `interval` has
+    /// elapsed.
+    ///
+    /// Items which arrive within the interval are pulled from the underlying
+    /// iterator and dropped. This is useful to rate-limit a noisy source of
+    /// events.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wstd::iter::AsyncIterator;
+    /// use wstd::prelude::*;
+    /// use wstd::time::{self, Duration};
+    ///
+    /// #[wstd::main]
+    /// async fn main() {
+    ///     let mut ticks = time::interval(Duration::from_millis(10))
+    ///         .throttle(Duration::from_millis(100));
+    ///     // Only one in every ten ticks makes it through.
+    ///     ticks.next().await;
+    ///     ticks.next().await;
+    /// }
+    /// ```
+    fn throttle(self, interval: Duration) -> Throttle<Self>
+    where
+        Self: Sized,
+    {
+        Throttle::new(self, interval)
+    }
+
+    /// Combine two iterators into one which yields pairs of their items.
+    ///
+    /// Both iterators are advanced concurrently: each call to `next` polls
+    /// both underlying `next` futures, and completes once both have yielded
+    /// an item. Waiting for two timers of 20ms and 30ms therefore takes 30ms,
+    /// not 50ms.
+    ///
+    /// The zipped iterator ends as soon as either iterator ends. The other
+    /// iterator's pending `next` future is then dropped, and if it had
+    /// already yielded an item, that item is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wstd::iter::AsyncIterator;
+    /// use wstd::prelude::*;
+    /// use wstd::time::{self, Duration};
+    ///
+    /// #[wstd::main]
+    /// async fn main() {
+    ///     let fast = time::interval(Duration::from_millis(10));
+    ///     let slow = time::interval(Duration::from_millis(100));
+    ///     let mut both = fast.zip(slow);
+    ///     // Resolves after 100ms, when both ticks have happened.
+    ///     let (_fast, _slow) = both.next().await.unwrap();
+    /// }
+    /// ```
+    fn zip<B>(self, other: B) -> Zip<Self, B>
+    where
+        Self: Sized,
+        B: AsyncIterator,
+    {
+        Zip::new(self, other)
+    }
+
+    /// Yield all items of this iterator, followed by all items of `other`.
+    ///
+    /// `other` is only advanced once this iterator has returned `None`, and
+    /// this iterator isn't advanced again after that.
+    fn chain<B>(self, other: B) -> Chain<Self, B>
+    where
+        Self: Sized,
+        B: AsyncIterator<Item = Self::Item>,
+    {
+        Chain::new(self, other)
+    }
+
+    /// Yield the items for which `f` returns `Some`, mapped to the value
+    /// inside.
+    ///
+    /// Items for which `f` returns `None` are dropped, and the next item is
+    /// requested from the underlying iterator straight away.
+    fn filter_map<B, F>(self, f: F) -> FilterMap<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> Option<B>,
+    {
+        FilterMap::new(self, f)
+    }
+
+    /// Map each item with `f`, which is also passed mutable state that is
+    /// carried from one item to the next.
+    ///
+    /// The state starts out as `initial_state`. The iterator ends as soon as
+    /// `f` returns `None`, or the underlying iterator does.
+    fn scan<St, B, F>(self, initial_state: St, f: F) -> Scan<Self, St, F>
+    where
+        Self: Sized,
+        F: FnMut(&mut St, Self::Item) -> Option<B>,
+    {
+        Scan::new(self, initial_state, f)
+    }
+
+    /// Yield at most the first `n` items of this iterator.
+    ///
+    /// The underlying iterator isn't advanced again once `n` items have been
+    /// yielded.
+    fn take(self, n: usize) -> Take<Self>
+    where
+        Self: Sized,
+    {
+        Take::new(self, n)
+    }
+
+    /// On an iterator of `Result`s, retry errors for which `should_retry`
+    /// returns `true`, up to `retries` times in a row.
+    ///
+    /// Before each retry, the iterator sleeps for `backoff(attempt)`, where
+    /// `attempt` counts from 1 for the first retry after a success. When the
+    /// retries are used up, or `should_retry` returns `false`, the error is
+    /// yielded. The next call starts counting from zero again.
+    ///
+    /// Retrying calls `next` on the underlying iterator again, so this is
+    /// only useful for iterators which can continue after yielding an
+    /// error, such as [`Incoming`](crate::net::Incoming).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::io::ErrorKind;
+    /// use wstd::iter::AsyncIterator;
+    /// use wstd::net::TcpListener;
+    /// use wstd::prelude::*;
+    /// use wstd::time::Duration;
+    ///
+    /// #[wstd::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let listener = TcpListener::bind("127.0.0.1:8080").await?;
+    ///     let mut incoming = listener.incoming().retry(
+    ///         5,
+    ///         |err| err.kind() == ErrorKind::WouldBlock,
+    ///         // Exponential backoff: 10ms, 20ms, 40ms, ...
+    ///         |attempt| Duration::from_millis(10 << (attempt - 1)),
+    ///     );
+    ///     while let Some(stream) = incoming.next().await {
+    ///         let _stream = stream?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    fn retry<T, E, P, B>(self, retries: u32, should_retry: P, backoff: B) -> Retry<Self, P, B>
+    where
+        Self: Sized + AsyncIterator<Item = Result<T, E>>,
+        P: FnMut(&E) -> bool,
+        B: FnMut(u32) -> Duration,
+    {
+        Retry::new(self, retries, should_retry, backoff)
+    }
+}
+
+impl<I> AsyncIteratorExt for I where I: AsyncIterator {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+
+    /// Yields the contents of a `Vec` in order.
+    struct FromVec<T>(std::vec::IntoIter<T>);
+    impl<T> AsyncIterator for FromVec<T> {
+        type Item = T;
+        async fn next(&mut self) -> Option<Self::Item> {
+            self.0.next()
+        }
+    }
+
+    #[test]
+    fn collect_into_vec() {
+        block_on(async {
+            let iter = FromVec(vec![1, 2, 3].into_iter());
+            let out: Vec<u32> = iter.collect().await;
+            assert_eq!(out, [1, 2, 3]);
+        })
+    }
+
+    #[test]
+    fn try_collect_short_circuits() {
+        block_on(async {
+            let mut iter = FromVec(vec![Ok(1), Err("boom"), Ok(3)].into_iter());
+            let out: Result<Vec<u32>, _> = (&mut iter).try_collect().await;
+            assert_eq!(out, Err("boom"));
+            assert_eq!(
+                iter.next().await,
+                Some(Ok(3)),
+                "iteration stops at the error"
+            );
+        })
+    }
+
+    /// Fails `failures` times for every successful item.
+    struct Flaky {
+        calls: u32,
+        failures: u32,
+    }
+    impl AsyncIterator for Flaky {
+        type Item = Result<u32, &'static str>;
+        async fn next(&mut self) -> Option<Self::Item> {
+            self.calls += 1;
+            if self.calls > 9 {
+                return None;
+            }
+            match self.calls % (self.failures + 1) {
+                0 => Some(Ok(self.calls)),
+                _ => Some(Err("transient")),
+            }
+        }
+    }
+
+    #[test]
+    fn retry_transient_errors() {
+        block_on(async {
+            let flaky = Flaky {
+                calls: 0,
+                failures: 2,
+            };
+            let mut backoffs = Vec::new();
+            let mut iter = flaky.retry(
+                2,
+                |err| *err == "transient",
+                |attempt| {
+                    backoffs.push(attempt);
+                    Duration::from_millis(1)
+                },
+            );
+            assert_eq!(iter.next().await, Some(Ok(3)));
+            assert_eq!(iter.next().await, Some(Ok(6)));
+            assert_eq!(iter.next().await, Some(Ok(9)));
+            assert_eq!(iter.next().await, None);
+            drop(iter);
+            assert_eq!(backoffs, [1, 2, 1, 2, 1, 2]);
+        })
+    }
+
+    #[test]
+    fn retry_gives_up() {
+        block_on(async {
+            let flaky = Flaky {
+                calls: 0,
+                failures: 3,
+            };
+            let mut iter = flaky.retry(2, |err| *err == "transient", |_| Duration::from_millis(1));
+            assert_eq!(iter.next().await, Some(Err("transient")), "retries used up");
+            assert_eq!(iter.next().await, Some(Ok(4)));
+
+            let flaky = Flaky {
+                calls: 0,
+                failures: 3,
+            };
+            let mut iter = flaky.retry(2, |_| false, |_| Duration::from_millis(1));
+            assert_eq!(iter.next().await, Some(Err("transient")), "not retried");
+            assert_eq!(iter.into_inner().calls, 1);
+        })
+    }
+
+    #[test]
+    fn zip_polls_concurrently() {
+        block_on(async {
+            let fast = crate::time::interval(Duration::from_millis(20));
+            let slow = crate::time::interval(Duration::from_millis(30));
+            let mut zipped = fast.zip(slow);
+            let start = crate::time::Instant::now();
+            for _ in 0..3 {
+                let (a, b) = zipped.next().await.unwrap();
+                assert!(a <= b, "the fast tick comes first");
+            }
+            let elapsed: std::time::Duration = start.elapsed().into();
+            assert!(elapsed >= std::time::Duration::from_millis(90));
+            // Advancing one after the other would have taken 150ms.
+            assert!(
+                elapsed < std::time::Duration::from_millis(140),
+                "{elapsed:?}"
+            );
+        })
+    }
+
+    #[test]
+    fn zip_ends_with_shorter() {
+        block_on(async {
+            let ticks = crate::time::interval(Duration::from_millis(1));
+            let items: Vec<_> = FromVec(vec!['a', 'b'].into_iter())
+                .zip(ticks)
+                .collect()
+                .await;
+            assert_eq!(items.len(), 2);
+            assert_eq!(items[1].0, 'b');
+        })
+    }
+
+    #[test]
+    fn filter_map_take() {
+        block_on(async {
+            let frames = FromVec(vec!["1", "two", "3", "", "5", "6"].into_iter());
+            let mut numbers = frames.filter_map(|s| s.parse::<u32>().ok()).take(3);
+            assert_eq!(numbers.next().await, Some(1));
+            assert_eq!(numbers.next().await, Some(3));
+            assert_eq!(numbers.next().await, Some(5));
+            assert_eq!(numbers.next().await, None);
+        })
+    }
+
+    #[test]
+    fn scan_carries_state() {
+        block_on(async {
+            // Number the messages which parse, and stop at the first "bye".
+            let frames = FromVec(vec!["hi", "??", "ok", "bye", "late"].into_iter());
+            let messages: Vec<_> = frames
+                .filter_map(|s| (s != "??").then_some(s))
+                .scan(0, |seq, s| {
+                    *seq += 1;
+                    (s != "bye").then_some((*seq, s))
+                })
+                .collect()
+                .await;
+            assert_eq!(messages, [(1, "hi"), (2, "ok")]);
+
+            let running_sum: Vec<u32> = FromVec(vec![1, 2, 3, 4].into_iter())
+                .scan(0, |sum, n| {
+                    *sum += n;
+                    Some(*sum)
+                })
+                .take(3)
+                .collect()
+                .await;
+            assert_eq!(running_sum, [1, 3, 6]);
+        })
+    }
+
+    #[test]
+    fn take_stops_advancing() {
+        block_on(async {
+            let mut iter = FromVec(vec![1, 2, 3].into_iter());
+            let taken: Vec<_> = (&mut iter).take(2).collect().await;
+            assert_eq!(taken, [1, 2]);
+            assert_eq!(iter.next().await, Some(3));
+            assert_eq!((&mut iter).take(0).next().await, None);
+        })
+    }
+
+    #[test]
+    fn chain_interval() {
+        block_on(async {
+            let start = crate::time::Instant::now();
+            let ticks = crate::time::interval(Duration::from_millis(5));
+            let mut chained = FromVec(vec![start, start].into_iter()).chain(ticks);
+            assert_eq!(chained.next().await, Some(start));
+            assert_eq!(chained.next().await, Some(start));
+            let tick = chained.next().await.unwrap();
+            assert!(tick >= start + Duration::from_millis(5));
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use super::AsyncIterator;
+
+/// Yields the items of one iterator, then those of another.
+///
+/// This `struct` is created by the [`chain`] method on [`AsyncIteratorExt`].
+/// See its documentation for more.
+///
+/// [`chain`]: crate::iter::AsyncIteratorExt::chain
+/// [`AsyncIteratorExt`]: crate::iter::AsyncIteratorExt
+#[must_use = "iterators do nothing unless advanced"]
+#[derive(Debug)]
+pub struct Chain<A, B> {
+    a: A,
+    b: B,
+    a_done: bool,
+}
+
+impl<A, B> Chain<A, B> {
+    pub(super) fn new(a: A, b: B) -> Self {
+        Self {
+            a,
+            b,
+            a_done: false,
+        }
+    }
+}
+
+impl<A, B> AsyncIterator for Chain<A, B>
+where
+    A: AsyncIterator,
+    B: AsyncIterator<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        if !self.a_done {
+            match self.a.next().await {
+                Some(item) => return Some(item),
+                None => self.a_done = true,
+            }
+        }
+        self.b.next().await
+    }
+}
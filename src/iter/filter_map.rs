@@ -0,0 +1,37 @@
+use super::AsyncIterator;
+
+/// Yields the items of an iterator for which a closure returns `Some`.
+///
+/// This `struct` is created by the [`filter_map`] method on
+/// [`AsyncIteratorExt`]. See its documentation for more.
+///
+/// [`filter_map`]: crate::iter::AsyncIteratorExt::filter_map
+/// [`AsyncIteratorExt`]: crate::iter::AsyncIteratorExt
+#[must_use = "iterators do nothing unless advanced"]
+#[derive(Debug)]
+pub struct FilterMap<I, F> {
+    iter: I,
+    f: F,
+}
+
+impl<I, F> FilterMap<I, F> {
+    pub(super) fn new(iter: I, f: F) -> Self {
+        Self { iter, f }
+    }
+}
+
+impl<I, F, B> AsyncIterator for FilterMap<I, F>
+where
+    I: AsyncIterator,
+    F: FnMut(I::Item) -> Option<B>,
+{
+    type Item = B;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = (self.f)(self.iter.next().await?) {
+                return Some(item);
+            }
+        }
+    }
+}
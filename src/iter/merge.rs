@@ -0,0 +1,195 @@
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::AsyncIterator;
+
+/// The in-flight call to `next` on one source, which hands the source back
+/// along with its item.
+type Advance<I> = Pin<Box<dyn Future<Output = (I, Option<<I as AsyncIterator>::Item>)>>>;
+
+/// Merge several async iterators into one, which yields items from whichever
+/// source has one ready.
+///
+/// Every source is advanced concurrently, and sources which have ended are
+/// removed. Once all sources have ended, the merged iterator returns `None`.
+///
+/// # Fairness
+///
+/// When several sources are ready at once, they take turns: after a source
+/// yields an item, every other source that is ready gets to yield one before
+/// it yields again. A source which is always ready therefore can't starve the
+/// others.
+///
+/// # Cancellation
+///
+/// Each source's `next` future is kept between calls, rather than being
+/// restarted, so dropping a call to `next` on the merged iterator loses no
+/// items, and timers in the sources keep running. This is why the sources
+/// must be `'static`.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::iter::{self, AsyncIterator};
+/// use wstd::time::{self, Duration};
+///
+/// #[wstd::main]
+/// async fn main() {
+///     let fast = time::interval(Duration::from_millis(10));
+///     let slow = time::interval(Duration::from_millis(100));
+///     let mut ticks = iter::merge([fast, slow]);
+///     while let Some(tick) = ticks.next().await {
+///         println!("tick at {tick:?}");
+///     }
+/// }
+/// ```
+pub fn merge<I>(iters: impl IntoIterator<Item = I>) -> Merge<I>
+where
+    I: AsyncIterator + 'static,
+{
+    Merge {
+        sources: iters.into_iter().map(advance).collect(),
+    }
+}
+
+fn advance<I: AsyncIterator + 'static>(mut iter: I) -> Advance<I> {
+    Box::pin(async move {
+        let item = iter.next().await;
+        (iter, item)
+    })
+}
+
+/// Yields items from several async iterators as they become ready.
+///
+/// This `struct` is created by the [`merge`] function. See its documentation
+/// for more.
+#[must_use = "iterators do nothing unless advanced"]
+pub struct Merge<I: AsyncIterator> {
+    /// The sources still running, in the order they are polled.
+    sources: Vec<Advance<I>>,
+}
+
+impl<I: AsyncIterator + 'static> Merge<I> {
+    /// The number of sources which haven't ended yet.
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Returns `true` if all sources have ended.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Add another source.
+    pub fn push(&mut self, iter: I) {
+        self.sources.push(advance(iter));
+    }
+
+    fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+        let mut i = 0;
+        while i < self.sources.len() {
+            match self.sources[i].as_mut().poll(cx) {
+                Poll::Pending => i += 1,
+                Poll::Ready((_, None)) => {
+                    drop(self.sources.remove(i));
+                }
+                Poll::Ready((iter, Some(item))) => {
+                    self.sources[i] = advance(iter);
+                    // Move this source to the back, so the others are polled
+                    // before it next time.
+                    self.sources[i..].rotate_left(1);
+                    return Poll::Ready(Some(item));
+                }
+            }
+        }
+        if self.sources.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<I: AsyncIterator + 'static> AsyncIterator for Merge<I> {
+    type Item = I::Item;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        poll_fn(|cx| self.poll_next(cx)).await
+    }
+}
+
+impl<I: AsyncIterator> fmt::Debug for Merge<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Merge")
+            .field("sources", &self.sources.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::iter::AsyncIteratorExt;
+    use crate::runtime::block_on;
+    use crate::time::{interval, Duration, Interval};
+
+    struct FromVec<T>(std::vec::IntoIter<T>);
+    impl<T> AsyncIterator for FromVec<T> {
+        type Item = T;
+        async fn next(&mut self) -> Option<Self::Item> {
+            self.0.next()
+        }
+    }
+
+    #[test]
+    fn round_robin() {
+        block_on(async {
+            let sources = [vec![1, 2, 3], vec![10, 20], vec![100]];
+            let merged = merge(sources.map(|items| FromVec(items.into_iter())));
+            let items: Vec<_> = merged.collect().await;
+            assert_eq!(items, [1, 10, 100, 2, 20, 3]);
+        })
+    }
+
+    #[test]
+    fn empty() {
+        block_on(async {
+            let mut merged = merge(Vec::<FromVec<u8>>::new());
+            assert!(merged.is_empty());
+            assert_eq!(merged.next().await, None);
+
+            merged.push(FromVec(vec![1].into_iter()));
+            assert_eq!(merged.len(), 1);
+            assert_eq!(merged.next().await, Some(1));
+            assert_eq!(merged.next().await, None);
+            assert!(merged.is_empty());
+        })
+    }
+
+    /// Tags the ticks of an interval with a name.
+    struct Tagged(&'static str, Interval);
+    impl AsyncIterator for Tagged {
+        type Item = &'static str;
+        async fn next(&mut self) -> Option<Self::Item> {
+            self.1.next().await.map(|_| self.0)
+        }
+    }
+
+    #[test]
+    fn slow_source_not_starved() {
+        block_on(async {
+            let fast = Tagged("fast", interval(Duration::from_millis(5)));
+            let slow = Tagged("slow", interval(Duration::from_millis(30)));
+            let mut merged = merge([fast, slow]);
+            let mut fast_ticks = 0;
+            // If the slow interval was restarted on every fast tick, it
+            // would never fire.
+            while merged.next().await == Some("fast") {
+                fast_ticks += 1;
+            }
+            assert!(fast_ticks >= 3, "{fast_ticks}");
+        })
+    }
+}
@@ -1,5 +1,25 @@
 //! Composable async iteration.
 
+mod async_iterator_ext;
+mod chain;
+mod filter_map;
+mod merge;
+mod retry;
+mod scan;
+mod take;
+mod throttle;
+mod zip;
+
+pub use async_iterator_ext::AsyncIteratorExt;
+pub use chain::Chain;
+pub use filter_map::FilterMap;
+pub use merge::{merge, Merge};
+pub use retry::Retry;
+pub use scan::Scan;
+pub use take::Take;
+pub use throttle::Throttle;
+pub use zip::Zip;
+
 /// A trait for dealing with async iterators.
 pub trait AsyncIterator {
     /// The type of the elements being iterated over.
@@ -8,3 +28,12 @@ pub trait AsyncIterator {
     /// Advances the iterator and returns the next value.
     async fn next(&mut self) -> Option<Self::Item>;
 }
+
+impl<I: AsyncIterator + ?Sized> AsyncIterator for &mut I {
+    type Item = I::Item;
+
+    #[inline]
+    async fn next(&mut self) -> Option<Self::Item> {
+        (**self).next().await
+    }
+}
@@ -0,0 +1,57 @@
+use super::AsyncIterator;
+use crate::task;
+use crate::time::Duration;
+
+/// Retries an iterator of `Result`s when it yields a transient error.
+///
+/// This `struct` is created by the [`retry`] method on [`AsyncIteratorExt`].
+/// See its documentation for more.
+///
+/// [`retry`]: crate::iter::AsyncIteratorExt::retry
+/// [`AsyncIteratorExt`]: crate::iter::AsyncIteratorExt
+#[must_use = "iterators do nothing unless advanced"]
+#[derive(Debug)]
+pub struct Retry<I, P, B> {
+    iter: I,
+    retries: u32,
+    should_retry: P,
+    backoff: B,
+}
+
+impl<I, P, B> Retry<I, P, B> {
+    pub(super) fn new(iter: I, retries: u32, should_retry: P, backoff: B) -> Self {
+        Self {
+            iter,
+            retries,
+            should_retry,
+            backoff,
+        }
+    }
+
+    /// Consumes the `Retry`, returning the underlying iterator.
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<I, T, E, P, B> AsyncIterator for Retry<I, P, B>
+where
+    I: AsyncIterator<Item = Result<T, E>>,
+    P: FnMut(&E) -> bool,
+    B: FnMut(u32) -> Duration,
+{
+    type Item = Result<T, E>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        let mut attempt = 0;
+        loop {
+            match self.iter.next().await? {
+                Err(err) if attempt < self.retries && (self.should_retry)(&err) => {
+                    attempt += 1;
+                    task::sleep((self.backoff)(attempt)).await;
+                }
+                item => return Some(item),
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use super::AsyncIterator;
+
+/// Maps the items of an iterator with a closure which carries state between
+/// items.
+///
+/// This `struct` is created by the [`scan`] method on [`AsyncIteratorExt`].
+/// See its documentation for more.
+///
+/// [`scan`]: crate::iter::AsyncIteratorExt::scan
+/// [`AsyncIteratorExt`]: crate::iter::AsyncIteratorExt
+#[must_use = "iterators do nothing unless advanced"]
+#[derive(Debug)]
+pub struct Scan<I, St, F> {
+    iter: I,
+    state: St,
+    f: F,
+}
+
+impl<I, St, F> Scan<I, St, F> {
+    pub(super) fn new(iter: I, state: St, f: F) -> Self {
+        Self { iter, state, f }
+    }
+}
+
+impl<I, St, F, B> AsyncIterator for Scan<I, St, F>
+where
+    I: AsyncIterator,
+    F: FnMut(&mut St, I::Item) -> Option<B>,
+{
+    type Item = B;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next().await?;
+        (self.f)(&mut self.state, item)
+    }
+}
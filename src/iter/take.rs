@@ -0,0 +1,33 @@
+use super::AsyncIterator;
+
+/// Yields the first `n` items of an iterator.
+///
+/// This `struct` is created by the [`take`] method on [`AsyncIteratorExt`].
+/// See its documentation for more.
+///
+/// [`take`]: crate::iter::AsyncIteratorExt::take
+/// [`AsyncIteratorExt`]: crate::iter::AsyncIteratorExt
+#[must_use = "iterators do nothing unless advanced"]
+#[derive(Debug)]
+pub struct Take<I> {
+    iter: I,
+    remaining: usize,
+}
+
+impl<I> Take<I> {
+    pub(super) fn new(iter: I, n: usize) -> Self {
+        Self { iter, remaining: n }
+    }
+}
+
+impl<I: AsyncIterator> AsyncIterator for Take<I> {
+    type Item = I::Item;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.iter.next().await
+    }
+}
@@ -0,0 +1,76 @@
+use super::AsyncIterator;
+use crate::time::{Duration, Instant};
+
+/// Filters out items yielded too soon after the previous one.
+///
+/// This `struct` is created by the [`throttle`] method on [`AsyncIteratorExt`].
+/// See its documentation for more.
+///
+/// [`throttle`]: crate::iter::AsyncIteratorExt::throttle
+/// [`AsyncIteratorExt`]: crate::iter::AsyncIteratorExt
+#[must_use = "iterators do nothing unless advanced"]
+#[derive(Debug)]
+pub struct Throttle<I> {
+    iter: I,
+    interval: Duration,
+    deadline: Option<Instant>,
+}
+
+impl<I> Throttle<I> {
+    pub(super) fn new(iter: I, interval: Duration) -> Self {
+        Self {
+            iter,
+            interval,
+            deadline: None,
+        }
+    }
+}
+
+impl<I: AsyncIterator> AsyncIterator for Throttle<I> {
+    type Item = I::Item;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next().await?;
+            let now = Instant::now();
+            match self.deadline {
+                // Still inside the window of the previous item: discard.
+                Some(deadline) if now < deadline => continue,
+                _ => {
+                    self.deadline = Some(now + self.interval);
+                    return Some(item);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::iter::AsyncIteratorExt;
+    use crate::runtime::block_on;
+    use crate::task::sleep;
+
+    /// Yields each item after sleeping for its delay.
+    struct Delayed(std::vec::IntoIter<(u64, u32)>);
+    impl AsyncIterator for Delayed {
+        type Item = u32;
+        async fn next(&mut self) -> Option<Self::Item> {
+            let (delay, item) = self.0.next()?;
+            sleep(Duration::from_millis(delay)).await;
+            Some(item)
+        }
+    }
+
+    #[test]
+    fn drops_items_inside_interval() {
+        block_on(async {
+            // Items 1 and 2 arrive within 50ms of item 0, item 3 well after.
+            // Item 4 arrives within 50ms of item 3.
+            let source = Delayed(vec![(0, 0), (5, 1), (5, 2), (60, 3), (5, 4)].into_iter());
+            let items: Vec<_> = source.throttle(Duration::from_millis(50)).collect().await;
+            assert_eq!(items, [0, 3]);
+        })
+    }
+}
@@ -0,0 +1,60 @@
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::task::Poll;
+
+use super::AsyncIterator;
+
+/// Iterates two iterators in lockstep, advancing both concurrently.
+///
+/// This `struct` is created by the [`zip`] method on [`AsyncIteratorExt`].
+/// See its documentation for more.
+///
+/// [`zip`]: crate::iter::AsyncIteratorExt::zip
+/// [`AsyncIteratorExt`]: crate::iter::AsyncIteratorExt
+#[must_use = "iterators do nothing unless advanced"]
+#[derive(Debug)]
+pub struct Zip<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Zip<A, B> {
+    pub(super) fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A: AsyncIterator, B: AsyncIterator> AsyncIterator for Zip<A, B> {
+    type Item = (A::Item, B::Item);
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        let mut a_next = pin!(self.a.next());
+        let mut b_next = pin!(self.b.next());
+        let (mut a_item, mut b_item) = (None, None);
+        poll_fn(|cx| {
+            if a_item.is_none() {
+                match a_next.as_mut().poll(cx) {
+                    Poll::Ready(Some(item)) => a_item = Some(item),
+                    // One side has ended, so stop waiting for the other.
+                    Poll::Ready(None) => return Poll::Ready(None),
+                    Poll::Pending => {}
+                }
+            }
+            if b_item.is_none() {
+                match b_next.as_mut().poll(cx) {
+                    Poll::Ready(Some(item)) => b_item = Some(item),
+                    Poll::Ready(None) => return Poll::Ready(None),
+                    Poll::Pending => {}
+                }
+            }
+            match (a_item.take(), b_item.take()) {
+                (Some(a), Some(b)) => Poll::Ready(Some((a, b))),
+                (a, b) => {
+                    (a_item, b_item) = (a, b);
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
@@ -48,6 +48,7 @@
 //! These are unique capabilities provided by WASI 0.2, and because this library
 //! is specific to that are exposed from here.
 
+pub mod channel;
 pub mod future;
 pub mod http;
 pub mod io;
@@ -64,6 +65,11 @@ pub use wstd_macro::attr_macro_test as test;
 pub mod prelude {
     pub use crate::future::FutureExt as _;
     pub use crate::http::Body as _;
+    pub use crate::http::FormExt as _;
+    pub use crate::http::HeadersExt as _;
+    pub use crate::http::RequestExt as _;
+    pub use crate::http::ResponseExt as _;
     pub use crate::io::AsyncRead as _;
     pub use crate::io::AsyncWrite as _;
+    pub use crate::iter::AsyncIteratorExt as _;
 }
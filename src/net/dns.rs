@@ -0,0 +1,181 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use wasi::sockets::ip_name_lookup::resolve_addresses;
+use wasi::sockets::network::{ErrorCode, IpAddress};
+
+use super::tcp_listener::to_io_err;
+use super::TcpStream;
+use crate::io;
+use crate::runtime::AsyncPollable;
+use crate::time::{Duration, Instant};
+
+/// Opens a TCP connection to the first of `addrs` which accepts one.
+///
+/// If no connection can be made, the error of the last attempt is returned.
+pub(crate) async fn connect_any(
+    host: &str,
+    addrs: Vec<IpAddr>,
+    port: u16,
+) -> io::Result<TcpStream> {
+    let mut last_err = None;
+    for ip in addrs {
+        match TcpStream::connect(&SocketAddr::new(ip, port).to_string()).await {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("no addresses found for {host}"),
+        )
+    }))
+}
+
+/// Resolves a host name to its IP addresses, in the order in which they
+/// should be tried.
+///
+/// IP address literals are returned as-is, without a lookup. Other names
+/// are resolved by the host, which may require the component to be granted
+/// permission to look up names.
+pub async fn lookup_host(host: &str) -> io::Result<Vec<IpAddr>> {
+    if let Ok(ip) = host.parse() {
+        return Ok(vec![ip]);
+    }
+    let network = wasi::sockets::instance_network::instance_network();
+    let stream = resolve_addresses(&network, host).map_err(to_io_err)?;
+    let pollable = AsyncPollable::new(stream.subscribe());
+    let mut addrs = Vec::new();
+    loop {
+        match stream.resolve_next_address() {
+            Ok(Some(addr)) => addrs.push(from_wasi_ip(addr)),
+            Ok(None) => return Ok(addrs),
+            Err(ErrorCode::WouldBlock) => pollable.wait_for().await,
+            Err(err) => return Err(to_io_err(err)),
+        }
+    }
+}
+
+fn from_wasi_ip(addr: IpAddress) -> IpAddr {
+    match addr {
+        IpAddress::Ipv4((a, b, c, d)) => Ipv4Addr::new(a, b, c, d).into(),
+        IpAddress::Ipv6((a, b, c, d, e, f, g, h)) => Ipv6Addr::new(a, b, c, d, e, f, g, h).into(),
+    }
+}
+
+/// A name resolver which caches the results of [`lookup_host`].
+///
+/// WASI doesn't report the TTL of DNS records, so every successful lookup is
+/// cached for the same, configurable amount of time. Failed lookups aren't
+/// cached. Since all methods take `&self`, a resolver can be shared by
+/// wrapping it in an `Rc`.
+///
+/// `wasi:http` resolves host names itself, so this only speeds up
+/// connections made with [`Resolver::connect`], not [`Client`] requests.
+///
+/// [`Client`]: crate::http::Client
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::net::Resolver;
+/// use wstd::time::Duration;
+///
+/// # async fn connect() -> std::io::Result<()> {
+/// let resolver = Resolver::new(Duration::from_secs(60));
+/// let first = resolver.connect("example.com", 80).await?;
+/// // This reuses the addresses looked up above.
+/// let second = resolver.connect("example.com", 80).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Resolver {
+    ttl: Duration,
+    cache: RefCell<HashMap<String, (Vec<IpAddr>, Instant)>>,
+}
+
+impl Resolver {
+    /// Create a resolver which caches lookups for `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Returns how long lookups are cached for.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Resolves a host name like [`lookup_host`], returning cached addresses
+    /// if they haven't expired yet.
+    pub async fn lookup(&self, host: &str) -> io::Result<Vec<IpAddr>> {
+        let now = Instant::now();
+        if let Some((addrs, expires)) = self.cache.borrow().get(host) {
+            if now < *expires {
+                return Ok(addrs.clone());
+            }
+        }
+        let addrs = lookup_host(host).await?;
+        let expires = Instant::now() + self.ttl;
+        let mut cache = self.cache.borrow_mut();
+        cache.retain(|_, (_, expires)| now < *expires);
+        cache.insert(host.to_owned(), (addrs.clone(), expires));
+        Ok(addrs)
+    }
+
+    /// Opens a TCP connection to `host`, trying each of its addresses in
+    /// turn until one succeeds.
+    ///
+    /// If no connection can be made, the error of the last attempt is
+    /// returned.
+    pub async fn connect(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        connect_any(host, self.lookup(host).await?, port).await
+    }
+
+    /// Removes all cached lookups.
+    pub fn clear(&self) {
+        self.cache.borrow_mut().clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn ip_literals() {
+        block_on(async {
+            let v6: IpAddr = "::1".parse().unwrap();
+            assert_eq!(lookup_host("::1").await.unwrap(), [v6]);
+            let resolver = Resolver::new(Duration::from_secs(1));
+            let v4: IpAddr = [127, 0, 0, 1].into();
+            assert_eq!(resolver.lookup("127.0.0.1").await.unwrap(), [v4]);
+        })
+    }
+
+    #[test]
+    fn cache_expires() {
+        block_on(async {
+            let resolver = Resolver::new(Duration::from_millis(20));
+            // `.invalid` names never resolve (RFC 6761), so any successful
+            // lookup below must have come from the cache.
+            let host = "wstd.invalid";
+            let cached: Vec<IpAddr> = vec![[192, 0, 2, 1].into()];
+            let expires = Instant::now() + resolver.ttl();
+            resolver
+                .cache
+                .borrow_mut()
+                .insert(host.to_owned(), (cached.clone(), expires));
+            assert_eq!(resolver.lookup(host).await.unwrap(), cached);
+
+            crate::task::sleep_until(expires).await;
+            assert!(resolver.lookup(host).await.is_err(), "cache expired");
+        })
+    }
+}
@@ -1,7 +1,11 @@
 //! Async network abstractions.
 
+mod dns;
 mod tcp_listener;
+mod tcp_listener_set;
 mod tcp_stream;
 
+pub use dns::*;
 pub use tcp_listener::*;
+pub use tcp_listener_set::*;
 pub use tcp_stream::*;
@@ -1,10 +1,12 @@
-use wasi::sockets::network::Ipv4SocketAddress;
+use wasi::sockets::network::{Ipv4SocketAddress, Ipv6SocketAddress};
 use wasi::sockets::tcp::{ErrorCode, IpAddressFamily, IpSocketAddress, TcpSocket};
 
+use crate::future::FutureExt;
 use crate::io;
 use crate::iter::AsyncIterator;
+use crate::time::Duration;
 use std::io::ErrorKind;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 
 use super::TcpStream;
 use crate::runtime::AsyncPollable;
@@ -13,7 +15,7 @@ use crate::runtime::AsyncPollable;
 #[derive(Debug)]
 pub struct TcpListener {
     // Field order matters: must drop this child before parent below
-    pollable: AsyncPollable,
+    pub(super) pollable: AsyncPollable,
     socket: TcpSocket,
 }
 
@@ -21,6 +23,16 @@ impl TcpListener {
     /// Creates a new TcpListener which will be bound to the specified address.
     ///
     /// The returned listener is ready for accepting connections.
+    ///
+    /// # Address reuse
+    ///
+    /// WASI sockets do not expose `SO_REUSEADDR` or any other address-reuse
+    /// option. Instead, hosts are expected to behave as if `SO_REUSEADDR` were
+    /// set implicitly, so that a port held in `TIME_WAIT` by a recently closed
+    /// socket can be bound again right away. If the previous owner of the
+    /// address is still shutting down, `bind` fails with
+    /// [`AddrInUse`](std::io::ErrorKind::AddrInUse); use
+    /// [`bind_with_retry`](TcpListener::bind_with_retry) to wait it out.
     pub async fn bind(addr: &str) -> io::Result<Self> {
         let addr: SocketAddr = addr
             .parse()
@@ -55,11 +67,54 @@ impl TcpListener {
         Ok(Self { pollable, socket })
     }
 
+    /// Like [`bind`](TcpListener::bind), but fails with
+    /// [`TimedOut`](std::io::ErrorKind::TimedOut) if binding and starting to
+    /// listen take longer than `timeout`.
+    ///
+    /// On timeout the half-initialized socket is dropped, and with it its
+    /// pollable and the timer's, so nothing is left registered with the
+    /// reactor.
+    pub async fn bind_timeout(addr: &str, timeout: Duration) -> io::Result<Self> {
+        Self::bind(addr).timeout(timeout).await?
+    }
+
+    /// Like [`bind`](TcpListener::bind), but retries up to `retries` times
+    /// while the address is in use.
+    ///
+    /// The first retry waits for `backoff`, and each subsequent retry waits
+    /// twice as long as the one before. This is handy in development restart
+    /// loops, where the previous instance of a server may still be releasing
+    /// its port. Errors other than [`AddrInUse`](std::io::ErrorKind::AddrInUse)
+    /// are returned immediately, as is the last `AddrInUse` once the retries
+    /// are exhausted.
+    pub async fn bind_with_retry(addr: &str, retries: u32, backoff: Duration) -> io::Result<Self> {
+        let mut backoff = backoff;
+        let mut retries_left = retries;
+        loop {
+            match Self::bind(addr).await {
+                Err(err) if err.kind() == ErrorKind::AddrInUse && retries_left > 0 => {
+                    retries_left -= 1;
+                    backoff.await;
+                    backoff += backoff;
+                }
+                res => return res,
+            }
+        }
+    }
+
     /// Returns the local socket address of this listener.
-    // TODO: make this return an actual socket addr
-    pub fn local_addr(&self) -> io::Result<String> {
+    ///
+    /// When bound to port 0, this can be used to find out which port was
+    /// actually assigned.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
         let addr = self.socket.local_address().map_err(to_io_err)?;
-        Ok(format!("{addr:?}"))
+        Ok(from_wasi_addr(addr))
+    }
+
+    /// Accepts a pending connection. The listener's pollable must be ready.
+    pub(super) fn accept(&self) -> io::Result<TcpStream> {
+        let (socket, input, output) = self.socket.accept().map_err(to_io_err)?;
+        Ok(TcpStream::new(input, output, socket))
     }
 
     /// Returns an iterator over the connections being received on this listener.
@@ -79,11 +134,46 @@ impl<'a> AsyncIterator for Incoming<'a> {
 
     async fn next(&mut self) -> Option<Self::Item> {
         self.listener.pollable.wait_for().await;
-        let (socket, input, output) = match self.listener.socket.accept().map_err(to_io_err) {
-            Ok(accepted) => accepted,
-            Err(err) => return Some(Err(err)),
-        };
-        Some(Ok(TcpStream::new(input, output, socket)))
+        Some(self.listener.accept())
+    }
+}
+
+pub(super) fn from_wasi_addr(addr: IpSocketAddress) -> SocketAddr {
+    match addr {
+        IpSocketAddress::Ipv4(addr) => {
+            let (a, b, c, d) = addr.address;
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(a, b, c, d), addr.port))
+        }
+        IpSocketAddress::Ipv6(addr) => {
+            let (a, b, c, d, e, f, g, h) = addr.address;
+            SocketAddr::V6(SocketAddrV6::new(
+                Ipv6Addr::new(a, b, c, d, e, f, g, h),
+                addr.port,
+                addr.flow_info,
+                addr.scope_id,
+            ))
+        }
+    }
+}
+
+pub(super) fn to_wasi_addr(addr: SocketAddr) -> IpSocketAddress {
+    match addr {
+        SocketAddr::V4(addr) => {
+            let [a, b, c, d] = addr.ip().octets();
+            IpSocketAddress::Ipv4(Ipv4SocketAddress {
+                port: addr.port(),
+                address: (a, b, c, d),
+            })
+        }
+        SocketAddr::V6(addr) => {
+            let [a, b, c, d, e, f, g, h] = addr.ip().segments();
+            IpSocketAddress::Ipv6(Ipv6SocketAddress {
+                port: addr.port(),
+                flow_info: addr.flowinfo(),
+                address: (a, b, c, d, e, f, g, h),
+                scope_id: addr.scope_id(),
+            })
+        }
     }
 }
 
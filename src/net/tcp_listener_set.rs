@@ -0,0 +1,109 @@
+use crate::io;
+use crate::iter::AsyncIterator;
+use crate::runtime::{AsyncPollable, Reactor};
+
+use super::{TcpListener, TcpStream};
+
+/// A set of TCP listeners, accepting connections on all of them at once.
+///
+/// This is useful to serve on multiple addresses or ports from a single
+/// accept loop. Rather than racing one [`Incoming`](super::Incoming) iterator
+/// per listener, [`incoming`](TcpListenerSet::incoming) waits on all of the
+/// listeners with a single call to the reactor.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::iter::AsyncIterator;
+/// use wstd::net::{TcpListener, TcpListenerSet};
+///
+/// # async fn run() -> wstd::io::Result<()> {
+/// let mut listeners = TcpListenerSet::new();
+/// listeners.add(TcpListener::bind("127.0.0.1:8080").await?);
+/// listeners.add(TcpListener::bind("127.0.0.1:8081").await?);
+///
+/// let mut incoming = listeners.incoming();
+/// while let Some(stream) = incoming.next().await {
+///     println!("Accepted from: {}", stream?.peer_addr()?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct TcpListenerSet {
+    listeners: Vec<TcpListener>,
+}
+
+impl TcpListenerSet {
+    /// Creates an empty set of listeners.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a listener to the set.
+    pub fn add(&mut self, listener: TcpListener) {
+        self.listeners.push(listener);
+    }
+
+    /// Returns the listeners in the set, in the order they were added.
+    pub fn listeners(&self) -> &[TcpListener] {
+        &self.listeners
+    }
+
+    /// Returns the number of listeners in the set.
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    /// Returns `true` if the set contains no listeners.
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    /// Returns an iterator over the connections being received on any of the
+    /// listeners in the set.
+    ///
+    /// When several listeners have pending connections, they take turns: after
+    /// accepting on one listener, the next connection is taken from the
+    /// listener after it, if that one is ready. This way a busy listener can't
+    /// starve the others. The iterator yields `None` right away if the set is
+    /// empty.
+    pub fn incoming(&self) -> IncomingSet<'_> {
+        IncomingSet { set: self, next: 0 }
+    }
+}
+
+/// An iterator that infinitely accepts connections on a [`TcpListenerSet`].
+///
+/// This `struct` is created by the [`incoming`] method on [`TcpListenerSet`].
+/// See its documentation for more.
+///
+/// [`incoming`]: TcpListenerSet::incoming
+#[derive(Debug)]
+pub struct IncomingSet<'a> {
+    set: &'a TcpListenerSet,
+    /// The listener which gets priority when several are ready.
+    next: usize,
+}
+
+impl<'a> AsyncIterator for IncomingSet<'a> {
+    type Item = io::Result<TcpStream>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        let listeners = &self.set.listeners;
+        if listeners.is_empty() {
+            return None;
+        }
+        // `wait_for_any` prefers the lowest index, so rotate the listeners to
+        // start at the one whose turn it is.
+        let len = listeners.len();
+        let start = self.next % len;
+        let pollables: Vec<&AsyncPollable> = (0..len)
+            .map(|i| &listeners[(start + i) % len].pollable)
+            .collect();
+        let ready = Reactor::current().wait_for_any(&pollables).await;
+        let index = (start + ready) % len;
+        self.next = index + 1;
+        Some(listeners[index].accept())
+    }
+}
@@ -1,9 +1,19 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{ready, Context, Poll};
+
 use wasi::{
     io::streams::{InputStream, OutputStream},
-    sockets::tcp::TcpSocket,
+    sockets::tcp::{ErrorCode, IpAddressFamily, TcpSocket},
 };
 
+use super::tcp_listener::{to_io_err, to_wasi_addr};
+use crate::future::FutureExt;
 use crate::io::{self, AsyncInputStream, AsyncOutputStream};
+use crate::runtime::{AsyncPollable, WaitFor};
+use crate::time::Duration;
 
 /// A TCP stream between a local and a remote socket.
 pub struct TcpStream {
@@ -20,6 +30,50 @@ impl TcpStream {
             socket,
         }
     }
+
+    /// Opens a TCP connection to a remote host.
+    pub async fn connect(addr: &str) -> io::Result<Self> {
+        Self::start_connect(addr)?.await
+    }
+
+    /// Like [`connect`](TcpStream::connect), but fails with
+    /// [`TimedOut`](std::io::ErrorKind::TimedOut) if the connection isn't
+    /// established within `timeout`.
+    pub async fn connect_timeout(addr: &str, timeout: Duration) -> io::Result<Self> {
+        Self::start_connect(addr)?.timeout(timeout).await?
+    }
+
+    /// Starts opening a TCP connection to a remote host, without waiting for
+    /// it to be established.
+    ///
+    /// The returned [`PendingConnect`] is a future which resolves to the
+    /// connected stream. Because the connection attempt is already underway,
+    /// several attempts can be started and raced against each other or
+    /// against other events, for example to connect to both the IPv4 and the
+    /// IPv6 address of a host. Dropping a `PendingConnect` abandons its
+    /// connection attempt.
+    pub fn start_connect(addr: &str) -> io::Result<PendingConnect> {
+        let addr: SocketAddr = addr
+            .parse()
+            .map_err(|_| io::Error::other("failed to parse string to socket addr"))?;
+        let family = match addr {
+            SocketAddr::V4(_) => IpAddressFamily::Ipv4,
+            SocketAddr::V6(_) => IpAddressFamily::Ipv6,
+        };
+        let socket =
+            wasi::sockets::tcp_create_socket::create_tcp_socket(family).map_err(to_io_err)?;
+        let network = wasi::sockets::instance_network::instance_network();
+        socket
+            .start_connect(&network, to_wasi_addr(addr))
+            .map_err(to_io_err)?;
+        let pollable = AsyncPollable::new(socket.subscribe());
+        Ok(PendingConnect {
+            wait: Some(pollable.wait_for()),
+            pollable: Some(pollable),
+            socket: Some(socket),
+        })
+    }
+
     /// Returns the socket address of the remote peer of this TCP connection.
     pub fn peer_addr(&self) -> io::Result<String> {
         let addr = self
@@ -29,9 +83,100 @@ impl TcpStream {
         Ok(format!("{addr:?}"))
     }
 
+    /// Check, without blocking or consuming any data, whether the stream is
+    /// ready to be read from.
+    ///
+    /// This also returns `true` once the peer has closed the connection,
+    /// since a read would then complete immediately. Readiness is only a
+    /// hint: a subsequent read may still have to wait.
+    pub fn is_read_ready(&self) -> bool {
+        self.input.is_ready()
+    }
+
+    /// Check, without blocking, whether the stream is ready to be written to.
+    ///
+    /// Readiness is only a hint: a subsequent write may still have to wait.
+    pub fn is_write_ready(&self) -> bool {
+        self.output.is_ready()
+    }
+
     pub fn split(&self) -> (ReadHalf<'_>, WriteHalf<'_>) {
         (ReadHalf(self), WriteHalf(self))
     }
+
+    /// Splits the stream into a read half and a write half which own it
+    /// together, so each can be moved and used independently.
+    ///
+    /// Unlike [`split`](TcpStream::split), the halves don't borrow the
+    /// stream, which makes it possible to run the read and write loops of a
+    /// connection in separate tasks. Dropping a half shuts down its direction
+    /// of the connection, and the connection is closed once both halves have
+    /// been dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use wstd::io::{self, AsyncWrite};
+    /// use wstd::net::TcpStream;
+    ///
+    /// # async fn run(stream: TcpStream) -> io::Result<()> {
+    /// let (mut reader, mut writer) = stream.into_split();
+    /// let echo = async move { io::copy(&mut reader, io::stdout()).await };
+    /// let greet = async move { writer.write_all(b"hello\n").await };
+    /// let (echoed, greeted) = futures_lite::future::zip(echo, greet).await;
+    /// echoed?;
+    /// greeted?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
+        let stream = Rc::new(self);
+        (OwnedReadHalf(stream.clone()), OwnedWriteHalf(stream))
+    }
+}
+
+/// A TCP connection which is being established.
+///
+/// This `struct` is created by the [`start_connect`] method on [`TcpStream`].
+/// See its documentation for more.
+///
+/// [`start_connect`]: TcpStream::start_connect
+#[must_use = "futures do nothing unless polled or .awaited"]
+#[derive(Debug)]
+pub struct PendingConnect {
+    // Field order matters: the pollable is a child of the socket, so it has
+    // to be dropped first when a connection attempt is cancelled.
+    wait: Option<WaitFor>,
+    pollable: Option<AsyncPollable>,
+    socket: Option<TcpSocket>,
+}
+
+impl Future for PendingConnect {
+    type Output = io::Result<TcpStream>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        loop {
+            let wait = this.wait.as_mut().expect("future polled after completing");
+            ready!(Pin::new(wait).poll(cx));
+            let socket = this.socket.as_ref().unwrap();
+            let result = match socket.finish_connect() {
+                Err(ErrorCode::WouldBlock) => {
+                    this.wait = Some(this.pollable.as_ref().unwrap().wait_for());
+                    continue;
+                }
+                result => result,
+            };
+            // Release the pollable before the socket can be handed out.
+            this.wait = None;
+            this.pollable = None;
+            let socket = this.socket.take().unwrap();
+            return Poll::Ready(match result {
+                Ok((input, output)) => Ok(TcpStream::new(input, output, socket)),
+                Err(err) => Err(to_io_err(err)),
+            });
+        }
+    }
 }
 
 impl Drop for TcpStream {
@@ -45,6 +190,10 @@ impl io::AsyncRead for TcpStream {
         self.input.read(buf).await
     }
 
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.input.read_buf(buf).await
+    }
+
     fn as_async_input_stream(&self) -> Option<&AsyncInputStream> {
         Some(&self.input)
     }
@@ -55,6 +204,10 @@ impl io::AsyncRead for &TcpStream {
         self.input.read(buf).await
     }
 
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.input.read_buf(buf).await
+    }
+
     fn as_async_input_stream(&self) -> Option<&AsyncInputStream> {
         (**self).as_async_input_stream()
     }
@@ -94,6 +247,10 @@ impl<'a> io::AsyncRead for ReadHalf<'a> {
         self.0.read(buf).await
     }
 
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.0.input.read_buf(buf).await
+    }
+
     fn as_async_input_stream(&self) -> Option<&AsyncInputStream> {
         self.0.as_async_input_stream()
     }
@@ -131,3 +288,67 @@ impl<'a> Drop for WriteHalf<'a> {
             .shutdown(wasi::sockets::tcp::ShutdownType::Send);
     }
 }
+
+/// The read half of a [`TcpStream`], which owns the stream together with
+/// its write half.
+///
+/// This `struct` is created by the [`into_split`] method on [`TcpStream`].
+/// See its documentation for more.
+///
+/// [`into_split`]: TcpStream::into_split
+pub struct OwnedReadHalf(Rc<TcpStream>);
+
+impl io::AsyncRead for OwnedReadHalf {
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self.0).read(buf).await
+    }
+
+    async fn read_buf(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.0.input.read_buf(buf).await
+    }
+
+    fn as_async_input_stream(&self) -> Option<&AsyncInputStream> {
+        self.0.as_async_input_stream()
+    }
+}
+
+impl Drop for OwnedReadHalf {
+    fn drop(&mut self) {
+        let _ = self
+            .0
+            .socket
+            .shutdown(wasi::sockets::tcp::ShutdownType::Receive);
+    }
+}
+
+/// The write half of a [`TcpStream`], which owns the stream together with
+/// its read half.
+///
+/// This `struct` is created by the [`into_split`] method on [`TcpStream`].
+/// See its documentation for more.
+///
+/// [`into_split`]: TcpStream::into_split
+pub struct OwnedWriteHalf(Rc<TcpStream>);
+
+impl io::AsyncWrite for OwnedWriteHalf {
+    async fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self.0).write(buf).await
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        (&*self.0).flush().await
+    }
+
+    fn as_async_output_stream(&self) -> Option<&AsyncOutputStream> {
+        self.0.as_async_output_stream()
+    }
+}
+
+impl Drop for OwnedWriteHalf {
+    fn drop(&mut self) {
+        let _ = self
+            .0
+            .socket
+            .shutdown(wasi::sockets::tcp::ShutdownType::Send);
+    }
+}
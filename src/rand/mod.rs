@@ -23,3 +23,18 @@ pub fn get_insecure_random_bytes(buf: &mut [u8]) {
         }
     }
 }
+
+/// Return a cryptographically secure random `u64`.
+///
+/// This is cheaper than [`get_random_bytes`] with an 8-byte buffer, since
+/// the host returns the number directly rather than allocating a list.
+pub fn get_random_u64() -> u64 {
+    random::random::get_random_u64()
+}
+
+/// Return an insecure random `u64`.
+///
+/// Like [`get_random_u64`], this avoids allocating a list of bytes.
+pub fn get_insecure_random_u64() -> u64 {
+    random::insecure::get_insecure_random_u64()
+}
@@ -1,13 +1,135 @@
 use super::{Reactor, REACTOR};
 
+use core::fmt;
 use core::future::Future;
 use core::pin::pin;
-use core::ptr;
 use core::task::Waker;
-use core::task::{Context, Poll, RawWaker, RawWakerVTable};
+use core::task::{Context, Poll};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::Wake;
 
 /// Start the event loop
+///
+/// This runs `fut` to completion, and only returns once it has completed.
+/// There are no background tasks: all async work happens inside `fut`, so
+/// by the time this returns, everything started inside it has either
+/// finished or been dropped, releasing its WASI resources. An exported
+/// function such as a `wasi:http` handler which calls `block_on` therefore
+/// doesn't return to the host while a response is still being written, as
+/// long as `fut` awaits the write.
+///
+/// # Panics
+///
+/// This function will panic if `fut` deadlocks, see
+/// [`block_on_with_deadlock`] for what that means and how to handle it
+/// differently.
 pub fn block_on<Fut>(fut: Fut) -> Fut::Output
+where
+    Fut: Future,
+{
+    match run(fut, &Cell::new(false), OnDeadlock::Error) {
+        Ok(res) => res,
+        Err(Stopped::Deadlock) => panic!("{}", Deadlock),
+        Err(Stopped::Aborted) => unreachable!("block_on can't be aborted"),
+    }
+}
+
+/// Start the event loop, choosing what happens when no progress is possible.
+///
+/// A future deadlocks when it is pending, but isn't waiting on any WASI
+/// pollables and didn't wake itself, for example when it waits on a
+/// [channel](crate::channel) whose sender is never used. Nothing can wake it
+/// up again. [`block_on`] panics when that happens, rather than letting the
+/// host trap on an empty `wasi:io/poll.poll`. With [`OnDeadlock::Error`],
+/// this function returns `Err(Deadlock)` instead, dropping the future; with
+/// [`OnDeadlock::Park`], it waits forever, just like a thread which is stuck
+/// on a lock.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::iter::AsyncIterator;
+/// use wstd::runtime::{block_on_with_deadlock, Deadlock, OnDeadlock};
+///
+/// let (_sender, mut receiver) = wstd::channel::unbounded::<()>();
+/// let res = block_on_with_deadlock(OnDeadlock::Error, async move {
+///     // Nothing is ever sent.
+///     receiver.next().await
+/// });
+/// assert_eq!(res, Err(Deadlock));
+/// ```
+pub fn block_on_with_deadlock<Fut>(
+    on_deadlock: OnDeadlock,
+    fut: Fut,
+) -> Result<Fut::Output, Deadlock>
+where
+    Fut: Future,
+{
+    match run(fut, &Cell::new(false), on_deadlock) {
+        Ok(res) => Ok(res),
+        Err(Stopped::Deadlock) => Err(Deadlock),
+        Err(Stopped::Aborted) => unreachable!("block_on_with_deadlock can't be aborted"),
+    }
+}
+
+/// Start the event loop, with a handle to stop it early.
+///
+/// `f` is called with an [`AbortHandle`] and returns the future to run.
+/// The handle can be cloned and passed to any part of the program, such as a
+/// shutdown signal handler. Once [`AbortHandle::abort`] is called, the event
+/// loop stops polling the future as soon as the current poll returns, and
+/// this function returns `Err(Aborted)`.
+///
+/// # Cleanup
+///
+/// Aborting drops the future, which drops everything it owns in its current
+/// state, just like dropping a future anywhere else. WASI resources are
+/// released in the process: pollables are unregistered from the reactor,
+/// streams and sockets are closed, and outgoing HTTP bodies which were never
+/// finished are treated as aborted by the host.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::runtime::block_on_with_handle;
+/// use wstd::task::sleep;
+/// use wstd::time::Duration;
+///
+/// let res = block_on_with_handle(|handle| async move {
+///     sleep(Duration::from_millis(10)).await;
+///     handle.abort();
+///     // Never reached: the event loop stops at the next await point.
+///     sleep(Duration::from_secs(60)).await;
+/// });
+/// assert!(res.is_err());
+/// ```
+pub fn block_on_with_handle<F, Fut>(f: F) -> Result<Fut::Output, Aborted>
+where
+    F: FnOnce(AbortHandle) -> Fut,
+    Fut: Future,
+{
+    let handle = AbortHandle {
+        aborted: Rc::new(Cell::new(false)),
+    };
+    let aborted = handle.aborted.clone();
+    match run(f(handle), &aborted, OnDeadlock::Error) {
+        Ok(res) => Ok(res),
+        Err(Stopped::Aborted) => Err(Aborted),
+        Err(Stopped::Deadlock) => panic!("{}", Deadlock),
+    }
+}
+
+/// Why the event loop stopped before the future completed.
+enum Stopped {
+    Aborted,
+    Deadlock,
+}
+
+/// Runs `fut` to completion, until `aborted` is set, or until it deadlocks.
+fn run<Fut>(fut: Fut, aborted: &Cell<bool>, on_deadlock: OnDeadlock) -> Result<Fut::Output, Stopped>
 where
     Fut: Future,
 {
@@ -19,19 +141,42 @@ where
         panic!("cannot wstd::runtime::block_on inside an existing block_on!")
     }
 
-    // Pin the future so it can be polled
-    let mut fut = pin!(fut);
-
     // Create a new context to be passed to the future.
-    let waker = noop_waker();
+    let root = Arc::new(RootWaker::default());
+    let waker = Waker::from(root.clone());
     let mut cx = Context::from_waker(&waker);
 
-    // Either the future completes and we return, or some IO is happening
-    // and we wait.
-    let res = loop {
-        match fut.as_mut().poll(&mut cx) {
-            Poll::Ready(res) => break res,
-            Poll::Pending => reactor.block_until(),
+    let res = {
+        // Pin the future so it can be polled. It's dropped at the end of this
+        // block, while the reactor is still current, so that whatever it owns
+        // can deregister from the reactor.
+        let mut fut = pin!(fut);
+
+        // Either the future completes and we return, or some IO is happening
+        // and we wait. If the future was woken while it was being polled, some
+        // in-process event (rather than IO) made progress possible, so we poll
+        // again straight away instead of waiting on the reactor, unless it
+        // yielded to let I/O which is ready make progress first. If it
+        // wasn't woken and isn't waiting on any pollables either, nothing can
+        // ever wake it again.
+        loop {
+            // Wakeups from the reactor happen right before we poll, so they
+            // carry no information.
+            root.take_woken();
+            match fut.as_mut().poll(&mut cx) {
+                Poll::Ready(res) => break Ok(res),
+                Poll::Pending if aborted.get() => break Err(Stopped::Aborted),
+                Poll::Pending if root.take_woken() => {
+                    if reactor.take_yield_request() {
+                        reactor.poll_ready();
+                    }
+                }
+                Poll::Pending if reactor.poll_once() => {}
+                Poll::Pending => match on_deadlock {
+                    OnDeadlock::Error => break Err(Stopped::Deadlock),
+                    OnDeadlock::Park => reactor.park(),
+                },
+            }
         }
     };
     // Clear the singleton
@@ -39,21 +184,165 @@ where
     res
 }
 
-/// Construct a new no-op waker
-// NOTE: we can remove this once <https://github.com/rust-lang/rust/issues/98286> lands
-fn noop_waker() -> Waker {
-    const VTABLE: RawWakerVTable = RawWakerVTable::new(
-        // Cloning just returns a new no-op raw waker
-        |_| RAW,
-        // `wake` does nothing
-        |_| {},
-        // `wake_by_ref` does nothing
-        |_| {},
-        // Dropping does nothing as we don't allocate anything
-        |_| {},
-    );
-    const RAW: RawWaker = RawWaker::new(ptr::null(), &VTABLE);
-
-    // SAFETY: all fields are no-ops, so this is safe
-    unsafe { Waker::from_raw(RAW) }
+/// A handle to stop the event loop started by [`block_on_with_handle`].
+#[derive(Debug, Clone)]
+pub struct AbortHandle {
+    aborted: Rc<Cell<bool>>,
+}
+
+impl AbortHandle {
+    /// Stop the event loop.
+    ///
+    /// The future being run is dropped once it next returns control to the
+    /// event loop. Calling this more than once has no further effect.
+    pub fn abort(&self) {
+        self.aborted.set(true);
+    }
+
+    /// Returns `true` if [`abort`](AbortHandle::abort) has been called.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.get()
+    }
+}
+
+/// The error returned by [`block_on_with_handle`] when the event loop was
+/// stopped through an [`AbortHandle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aborted;
+
+impl fmt::Display for Aborted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "event loop was aborted".fmt(f)
+    }
+}
+
+impl std::error::Error for Aborted {}
+
+/// What [`block_on_with_deadlock`] does when no progress is possible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OnDeadlock {
+    /// Stop the event loop and return a [`Deadlock`] error.
+    Error,
+    /// Wait forever.
+    Park,
+}
+
+/// The error returned by [`block_on_with_deadlock`] when no progress is
+/// possible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadlock;
+
+impl fmt::Display for Deadlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "deadlock: no progress possible, the future is pending but isn't waiting on any pollables"
+            .fmt(f)
+    }
+}
+
+impl std::error::Error for Deadlock {}
+
+/// The waker handed to the root future.
+///
+/// Wakers registered with the reactor are called from `Reactor::block_until`,
+/// right before the root future is polled again anyway. But in-process
+/// primitives such as pipes call their wakers while the root future is being
+/// polled; we record that so the event loop knows not to block.
+#[derive(Debug, Default)]
+struct RootWaker {
+    woken: AtomicBool,
+}
+
+impl RootWaker {
+    /// Returns whether the waker was called since the last check.
+    fn take_woken(&self) -> bool {
+        self.woken.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl Wake for RootWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::task::sleep;
+    use crate::time::Duration;
+
+    #[test]
+    fn abort_drops_future() {
+        struct SetOnDrop<'a>(&'a Cell<bool>);
+        impl Drop for SetOnDrop<'_> {
+            fn drop(&mut self) {
+                self.0.set(true);
+            }
+        }
+
+        let dropped = Cell::new(false);
+        let res = block_on_with_handle(|handle| async {
+            let _guard = SetOnDrop(&dropped);
+            let abort = async move {
+                sleep(Duration::from_millis(10)).await;
+                handle.abort();
+            };
+            // The long sleep is still registered with the reactor when the
+            // event loop is aborted, and must be cleaned up.
+            futures_lite::future::zip(abort, sleep(Duration::from_secs(60))).await;
+        });
+        assert_eq!(res, Err(Aborted));
+        assert!(dropped.get());
+    }
+
+    #[test]
+    fn runs_to_completion() {
+        use crate::io::{AsyncWrite, Cursor};
+        use crate::time::Instant;
+
+        let start = Instant::now();
+        let mut out = Cursor::new(Vec::new());
+        block_on(async {
+            // A slow handler: nothing is written until it wakes up.
+            sleep(Duration::from_millis(20)).await;
+            out.write_all(b"response").await.unwrap();
+            out.flush().await.unwrap();
+        });
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(out.into_inner(), b"response");
+    }
+
+    #[test]
+    fn deadlock_is_reported() {
+        use crate::iter::AsyncIterator;
+
+        let (sender, mut receiver) = crate::channel::unbounded::<()>();
+        let res = block_on_with_deadlock(OnDeadlock::Error, async move {
+            // The timer keeps the event loop busy for a while, but once it
+            // has fired, nothing is left that could wake the receiver.
+            sleep(Duration::from_millis(1)).await;
+            receiver.next().await
+        });
+        assert_eq!(res, Err(Deadlock));
+        drop(sender);
+
+        let res = block_on_with_deadlock(OnDeadlock::Error, async {
+            sleep(Duration::from_millis(1)).await;
+            42
+        });
+        assert_eq!(res, Ok(42));
+    }
+
+    #[test]
+    fn completes_without_abort() {
+        let res = block_on_with_handle(|handle| async move {
+            sleep(Duration::from_millis(1)).await;
+            handle.is_aborted()
+        });
+        assert_eq!(res, Ok(false));
+    }
 }
@@ -6,15 +6,46 @@
 //! [`wasi::Pollable`](https://docs.rs/wasi/latest/wasi/io/poll/struct.Pollable.html).
 //! This will automatically wait for the futures to resolve, and call the
 //! necessary wakers to work.
+//!
+//! # Tracing
+//!
+//! With the `tracing` feature enabled, the reactor emits [`tracing`] events
+//! at the `TRACE` level, with the target `wstd::runtime`. They are useful to
+//! find out why a future stays pending, or why the event loop traps. When
+//! the feature is disabled, no code is generated for them.
+//!
+//! | Event name   | Fields    | Emitted when                                          |
+//! |--------------|-----------|-------------------------------------------------------|
+//! | `register`   | `key`     | a pollable is scheduled with the reactor              |
+//! | `deregister` | `key`     | the last [`AsyncPollable`] for a pollable is dropped  |
+//! | `poll`       | `targets` | the reactor blocks in `wasi:io/poll.poll`             |
+//! | `wake`       | `key`     | a pollable is ready, right before its waker is called |
+//!
+//! The `key` identifies a pollable for as long as it is registered; keys of
+//! deregistered pollables are reused.
+//!
+//! [`tracing`]: https://docs.rs/tracing
 
 #![deny(missing_debug_implementations, nonstandard_style)]
 #![warn(missing_docs, unreachable_pub)]
 
+/// Emits a `tracing` event from the reactor, if the `tracing` feature is
+/// enabled.
+macro_rules! trace_event {
+    ($name:literal, $($field:tt)+) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::trace!(name: $name, target: "wstd::runtime", $($field)+);
+    };
+}
+
 mod block_on;
 mod reactor;
 
-pub use block_on::block_on;
-pub use reactor::{AsyncPollable, Reactor, WaitFor};
+pub use block_on::{
+    block_on, block_on_with_deadlock, block_on_with_handle, AbortHandle, Aborted, Deadlock,
+    OnDeadlock,
+};
+pub use reactor::{AsyncPollable, Reactor, WaitFor, WaitForAny};
 use std::cell::RefCell;
 
 // There are no threads in WASI 0.2, so this is just a safe way to thread a single reactor to all
@@ -40,15 +40,20 @@ impl AsyncPollable {
     }
     /// Create a Future that waits for the Pollable's readiness.
     pub fn wait_for(&self) -> WaitFor {
+        WaitFor {
+            waitee: self.waitee(),
+            needs_deregistration: false,
+        }
+    }
+
+    /// Create a uniquely identified `Waitee` for this pollable.
+    fn waitee(&self) -> Waitee {
         use std::sync::atomic::{AtomicUsize, Ordering};
         static COUNTER: AtomicUsize = AtomicUsize::new(0);
         let unique = COUNTER.fetch_add(1, Ordering::Relaxed);
-        WaitFor {
-            waitee: Waitee {
-                pollable: self.clone(),
-                unique,
-            },
-            needs_deregistration: false,
+        Waitee {
+            pollable: self.clone(),
+            unique,
         }
     }
 }
@@ -88,6 +93,56 @@ impl Drop for WaitFor {
     }
 }
 
+/// A Future that waits for the first of several Pollables to become ready.
+///
+/// This `struct` is created by the [`Reactor::wait_for_any`] method. See its
+/// documentation for more.
+#[must_use = "futures do nothing unless polled or .awaited"]
+#[derive(Debug)]
+pub struct WaitForAny {
+    waitees: Vec<Waitee>,
+    needs_deregistration: bool,
+}
+impl future::Future for WaitForAny {
+    type Output = usize;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let reactor = Reactor::current();
+        // Every pollable checked and found not ready registers our waker, so
+        // that the next call to `block_until` waits on all of them at once.
+        let ready = self
+            .waitees
+            .iter()
+            .position(|waitee| reactor.ready(waitee, cx.waker()));
+        match ready {
+            Some(index) => {
+                // The pollables checked before the ready one registered our
+                // waker, even if this is the first poll.
+                self.as_mut().deregister(&reactor);
+                Poll::Ready(index)
+            }
+            None => {
+                self.as_mut().needs_deregistration = true;
+                Poll::Pending
+            }
+        }
+    }
+}
+impl WaitForAny {
+    fn deregister(&mut self, reactor: &Reactor) {
+        for waitee in self.waitees.iter() {
+            reactor.deregister_waitee(waitee)
+        }
+        self.needs_deregistration = false;
+    }
+}
+impl Drop for WaitForAny {
+    fn drop(&mut self) {
+        if self.needs_deregistration {
+            self.deregister(&Reactor::current())
+        }
+    }
+}
+
 /// Manage async system resources for WASI 0.2
 #[derive(Debug, Clone)]
 pub struct Reactor {
@@ -100,6 +155,7 @@ pub struct Reactor {
 struct InnerReactor {
     pollables: Slab<Pollable>,
     wakers: HashMap<Waitee, Waker>,
+    yield_requested: bool,
 }
 
 impl Reactor {
@@ -122,10 +178,39 @@ impl Reactor {
             inner: Rc::new(RefCell::new(InnerReactor {
                 pollables: Slab::new(),
                 wakers: HashMap::new(),
+                yield_requested: false,
             })),
         }
     }
 
+    /// Drive a single iteration of the event loop.
+    ///
+    /// If any futures are waiting on pollables, this blocks until at least
+    /// one of those pollables is ready, calls the respective wakers, and
+    /// returns `true`. If nothing is waiting, no progress can be made: this
+    /// returns `false` immediately rather than blocking forever.
+    ///
+    /// This is the same step [`block_on`](super::block_on) takes every time
+    /// its future returns `Poll::Pending`. It allows building a custom
+    /// executor loop on top of the reactor: poll the futures, call
+    /// `poll_once`, and repeat.
+    pub fn poll_once(&self) -> bool {
+        if self.inner.borrow().wakers.is_empty() {
+            return false;
+        }
+        self.block_until();
+        true
+    }
+
+    /// Block forever, because nothing is waiting on a pollable which could
+    /// make progress.
+    pub(crate) fn park(&self) -> ! {
+        let never = wasi::clocks::monotonic_clock::subscribe_instant(u64::MAX);
+        loop {
+            never.block();
+        }
+    }
+
     /// Block until new events are ready. Calls the respective wakers once done.
     ///
     /// # On Wakers and single-threaded runtimes
@@ -139,6 +224,33 @@ impl Reactor {
     /// reason that we have to call all the wakers - even if by default they
     /// will do nothing.
     pub(crate) fn block_until(&self) {
+        self.poll(true)
+    }
+
+    /// Call the wakers of the pollables which are ready right now, without
+    /// blocking.
+    ///
+    /// This is how [`yield_now`](crate::task::yield_now) gives I/O a chance
+    /// to make progress: the event loop calls it instead of polling the root
+    /// future again straight away.
+    pub(crate) fn poll_ready(&self) {
+        self.poll(false)
+    }
+
+    /// Request a call to [`poll_ready`](Self::poll_ready) before the root
+    /// future is polled again.
+    pub(crate) fn request_yield(&self) {
+        self.inner.borrow_mut().yield_requested = true;
+    }
+
+    /// Returns whether a yield was requested since the last check.
+    pub(crate) fn take_yield_request(&self) -> bool {
+        std::mem::take(&mut self.inner.borrow_mut().yield_requested)
+    }
+
+    fn poll(&self, block: bool) {
+        // A pollable which is ready right away, so that polling doesn't block.
+        let ready_now = (!block).then(|| wasi::clocks::monotonic_clock::subscribe_duration(0));
         let reactor = self.inner.borrow();
 
         // We're about to wait for a number of pollables. When they wake we get
@@ -148,13 +260,21 @@ impl Reactor {
         // We start by iterating over the pollables, and keeping note of which
         // pollable belongs to which waker
         let mut indexed_wakers = Vec::with_capacity(reactor.wakers.len());
+        #[cfg(feature = "tracing")]
+        let mut indexed_keys = Vec::with_capacity(reactor.wakers.len());
         let mut targets = Vec::with_capacity(reactor.wakers.len());
         for (waitee, waker) in reactor.wakers.iter() {
             let pollable_index = waitee.pollable.0.key;
             indexed_wakers.push(waker);
+            #[cfg(feature = "tracing")]
+            indexed_keys.push(pollable_index.0);
             targets.push(&reactor.pollables[pollable_index.0]);
         }
 
+        if let Some(ready_now) = &ready_now {
+            targets.push(ready_now);
+        }
+
         debug_assert_ne!(
             targets.len(),
             0,
@@ -162,32 +282,58 @@ impl Reactor {
         );
 
         // Now that we have that association, we're ready to poll our targets.
-        // This will block until an event has completed.
+        // This will block until an event has completed, unless `ready_now`
+        // is among them.
+        trace_event!("poll", targets = targets.len());
         let ready_indexes = wasi::io::poll::poll(&targets);
 
         // Once we have the indexes for which pollables are available, we need
         // to convert it back to the right keys for the wakers. Earlier we
         // established a positional index -> waker key relationship, so we can
         // go right ahead and perform a lookup there.
-        let ready_wakers = ready_indexes
-            .into_iter()
-            .map(|index| indexed_wakers[index as usize]);
-
-        for waker in ready_wakers {
+        for index in ready_indexes {
+            let Some(waker) = indexed_wakers.get(index as usize) else {
+                continue;
+            };
+            trace_event!("wake", key = indexed_keys[index as usize]);
             waker.wake_by_ref()
         }
     }
 
+    /// Create a Future that waits for the first of several Pollables to become
+    /// ready, resolving to its index in `pollables`.
+    ///
+    /// If multiple pollables are ready at the same time, the lowest index is
+    /// returned. All pollables are waited on with a single call to
+    /// `wasi::io::poll::poll`, rather than racing a `WaitFor` for each one.
+    ///
+    /// # Panic
+    /// This will panic if `pollables` is empty, since no progress could ever
+    /// be made.
+    pub fn wait_for_any(&self, pollables: &[&AsyncPollable]) -> WaitForAny {
+        assert!(
+            !pollables.is_empty(),
+            "Reactor::wait_for_any requires at least one pollable"
+        );
+        let waitees = pollables.iter().map(|pollable| pollable.waitee()).collect();
+        WaitForAny {
+            waitees,
+            needs_deregistration: false,
+        }
+    }
+
     /// Turn a Wasi [`Pollable`] into an [`AsyncPollable`]
     pub fn schedule(&self, pollable: Pollable) -> AsyncPollable {
         let mut reactor = self.inner.borrow_mut();
         let key = EventKey(reactor.pollables.insert(pollable));
+        trace_event!("register", key = key.0);
         AsyncPollable(Rc::new(Registration { key }))
     }
 
     fn deregister_event(&self, key: EventKey) {
         let mut reactor = self.inner.borrow_mut();
         reactor.pollables.remove(key.0);
+        trace_event!("deregister", key = key.0);
     }
 
     fn deregister_waitee(&self, waitee: &Waitee) {
@@ -281,4 +427,51 @@ mod test {
             .await;
         })
     }
+
+    // Using WASMTIME_LOG, observe that this test results in a single call to poll() with both
+    // pollables, which resolves once the earlier one is ready.
+    #[test]
+    fn wait_for_any_durations() {
+        crate::runtime::block_on(async {
+            let reactor = Reactor::current();
+            let later = wasi::clocks::monotonic_clock::subscribe_duration(40_000_000);
+            let soon = wasi::clocks::monotonic_clock::subscribe_duration(10_000_000);
+            let later = reactor.schedule(later);
+            let soon = reactor.schedule(soon);
+            let ready = reactor.wait_for_any(&[&later, &soon]).await;
+            assert_eq!(ready, 1, "the sooner subscription should be ready first");
+        })
+    }
+
+    #[test]
+    fn wait_for_any_deregisters_on_first_poll() {
+        crate::runtime::block_on(async {
+            let reactor = Reactor::current();
+            let later = wasi::clocks::monotonic_clock::subscribe_duration(1_000_000_000);
+            let now = wasi::clocks::monotonic_clock::subscribe_duration(0);
+            let later = reactor.schedule(later);
+            let now = reactor.schedule(now);
+            let ready = reactor.wait_for_any(&[&later, &now]).await;
+            assert_eq!(ready, 1);
+            assert!(
+                reactor.inner.borrow().wakers.is_empty(),
+                "the pending pollable's waker was left registered"
+            );
+        })
+    }
+
+    #[test]
+    fn poll_once_progress() {
+        crate::runtime::block_on(async {
+            let reactor = Reactor::current();
+            assert!(!reactor.poll_once(), "nothing is waiting");
+
+            let pollable = wasi::clocks::monotonic_clock::subscribe_duration(10_000_000);
+            let sched = reactor.schedule(pollable);
+            let mut wait = sched.wait_for();
+            assert!(futures_lite::future::poll_once(&mut wait).await.is_none());
+            assert!(reactor.poll_once(), "the timer becomes ready");
+            assert!(futures_lite::future::poll_once(&mut wait).await.is_some());
+        })
+    }
 }
@@ -0,0 +1,197 @@
+use slab::Slab;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+/// An async barrier, which releases a group of tasks once they have all
+/// reached it.
+///
+/// Each task calls [`wait`](Barrier::wait), which resolves once `n` tasks
+/// are waiting. This is useful to make sure a set of tasks have all finished
+/// setting up, such as opening their streams, before any of them starts
+/// processing. Once released, the barrier can be used again by the next `n`
+/// waiters. Cloned barriers share their state.
+///
+/// Like [`Semaphore`](super::Semaphore), a barrier doesn't need any WASI
+/// pollables: waiters are woken directly when the last one arrives.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::task::{Barrier, JoinSet};
+///
+/// #[wstd::main]
+/// async fn main() {
+///     let barrier = Barrier::new(3);
+///     let mut tasks = JoinSet::new();
+///     for n in 0..3 {
+///         let barrier = barrier.clone();
+///         tasks.spawn(async move {
+///             // ... set up the worker ...
+///             barrier.wait().await;
+///             println!("worker {n} started");
+///         });
+///     }
+///     while tasks.join_next().await.is_some() {}
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Barrier {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    n: usize,
+    arrived: usize,
+    /// Incremented every time the waiters are released, so waiters can tell
+    /// whether they were.
+    generation: u64,
+    wakers: Slab<Waker>,
+}
+
+impl Barrier {
+    /// Create a barrier which releases its waiters once `n` tasks are
+    /// waiting.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `n` is zero.
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "barrier size must be at least one");
+        Self {
+            inner: Rc::new(RefCell::new(Inner {
+                n,
+                arrived: 0,
+                generation: 0,
+                wakers: Slab::new(),
+            })),
+        }
+    }
+
+    /// Wait until `n` tasks, including this one, are waiting on the barrier.
+    ///
+    /// A task only counts as waiting while the returned future is being
+    /// polled: dropping it before it resolves takes the task's arrival back.
+    pub fn wait(&self) -> BarrierWait {
+        BarrierWait {
+            inner: self.inner.clone(),
+            waiting: None,
+        }
+    }
+}
+
+/// A future which resolves once enough tasks are waiting on a [`Barrier`].
+///
+/// This `struct` is created by the [`wait`] method on [`Barrier`]. See its
+/// documentation for more.
+///
+/// [`wait`]: Barrier::wait
+#[must_use = "futures do nothing unless polled or .awaited"]
+#[derive(Debug)]
+pub struct BarrierWait {
+    inner: Rc<RefCell<Inner>>,
+    /// The generation this waiter arrived in, and the key of its waker.
+    waiting: Option<(u64, usize)>,
+}
+
+impl Future for BarrierWait {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut inner = this.inner.borrow_mut();
+        match this.waiting {
+            Some((generation, _)) if generation != inner.generation => {
+                // Our waker was drained when the waiters were released.
+                this.waiting = None;
+                Poll::Ready(())
+            }
+            Some((_, key)) => {
+                inner.wakers[key].clone_from(cx.waker());
+                Poll::Pending
+            }
+            None => {
+                inner.arrived += 1;
+                if inner.arrived == inner.n {
+                    inner.arrived = 0;
+                    inner.generation += 1;
+                    for waker in inner.wakers.drain() {
+                        waker.wake();
+                    }
+                    return Poll::Ready(());
+                }
+                let key = inner.wakers.insert(cx.waker().clone());
+                this.waiting = Some((inner.generation, key));
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for BarrierWait {
+    fn drop(&mut self) {
+        if let Some((generation, key)) = self.waiting {
+            let mut inner = self.inner.borrow_mut();
+            if generation == inner.generation {
+                inner.wakers.remove(key);
+                inner.arrived -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use crate::task::{sleep, JoinSet};
+    use crate::time::Duration;
+    use std::cell::Cell;
+
+    #[test]
+    fn releases_all_waiters_together() {
+        block_on(async {
+            let barrier = Barrier::new(3);
+            let started = Rc::new(Cell::new(0));
+            let mut set = JoinSet::new();
+            for n in 0..3 {
+                let barrier = barrier.clone();
+                let started = started.clone();
+                set.spawn(async move {
+                    sleep(Duration::from_millis(n * 5)).await;
+                    barrier.wait().await;
+                    started.set(started.get() + 1);
+                });
+            }
+            // The barrier is reusable once released.
+            set.spawn({
+                let barrier = barrier.clone();
+                async move {
+                    sleep(Duration::from_millis(20)).await;
+                    let mut wait = Box::pin(barrier.wait());
+                    assert!(futures_lite::future::poll_once(&mut wait).await.is_none());
+                }
+            });
+            while set.join_next().await.is_some() {}
+            assert_eq!(started.get(), 3);
+        })
+    }
+
+    #[test]
+    fn dropped_waiter_does_not_count() {
+        block_on(async {
+            let barrier = Barrier::new(2);
+            let mut first = Box::pin(barrier.wait());
+            assert!(futures_lite::future::poll_once(&mut first).await.is_none());
+            drop(first);
+
+            let mut second = Box::pin(barrier.wait());
+            assert!(futures_lite::future::poll_once(&mut second).await.is_none());
+            barrier.wait().await;
+            second.await;
+        })
+    }
+}
@@ -0,0 +1,202 @@
+use slab::Slab;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::task::{Context, Poll, Waker};
+
+/// A token which can be used to signal cancellation.
+///
+/// Cloned tokens share their state: cancelling any clone cancels all of them.
+/// Tokens created with [`child_token`](CancellationToken::child_token) are
+/// cancelled whenever their parent is, but cancelling a child does not
+/// affect its parent.
+///
+/// Cancellation does not need any WASI pollables: waiting futures are woken
+/// directly when [`cancel`](CancellationToken::cancel) is called.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures_lite::future::zip;
+/// use wstd::prelude::*;
+/// use wstd::task::{sleep, CancellationToken};
+/// use wstd::time::Duration;
+///
+/// #[wstd::main]
+/// async fn main() {
+///     let token = CancellationToken::new();
+///     let child = token.child_token();
+///
+///     let (slept, ()) = zip(
+///         // This would sleep for a minute, unless cancelled first.
+///         sleep(Duration::from_secs(60)).timeout(child.cancelled()),
+///         async {
+///             sleep(Duration::from_millis(10)).await;
+///             token.cancel();
+///         },
+///     )
+///     .await;
+///     assert!(slept.is_err(), "the sleep was cancelled");
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: bool,
+    wakers: Slab<Waker>,
+    children: Vec<Weak<RefCell<Inner>>>,
+}
+
+impl Inner {
+    fn cancel(&mut self) {
+        if self.cancelled {
+            return;
+        }
+        self.cancelled = true;
+        for waker in self.wakers.drain() {
+            waker.wake();
+        }
+        for child in self.children.drain(..) {
+            if let Some(child) = child.upgrade() {
+                child.borrow_mut().cancel();
+            }
+        }
+    }
+}
+
+impl CancellationToken {
+    /// Create a new, uncancelled token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a token which is cancelled when this token is cancelled.
+    ///
+    /// If this token is already cancelled, so is the returned token.
+    pub fn child_token(&self) -> CancellationToken {
+        let child = CancellationToken::new();
+        let mut inner = self.inner.borrow_mut();
+        if inner.cancelled {
+            child.inner.borrow_mut().cancelled = true;
+        } else {
+            inner.children.retain(|child| child.strong_count() > 0);
+            inner.children.push(Rc::downgrade(&child.inner));
+        }
+        child
+    }
+
+    /// Cancel this token, along with all of its children.
+    ///
+    /// All futures waiting on [`cancelled`](CancellationToken::cancelled) are
+    /// woken. Cancelling a token more than once has no further effect.
+    pub fn cancel(&self) {
+        self.inner.borrow_mut().cancel();
+    }
+
+    /// Returns `true` if this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.borrow().cancelled
+    }
+
+    /// Returns a future which resolves once this token is cancelled.
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            inner: self.inner.clone(),
+            key: None,
+        }
+    }
+}
+
+/// A future which resolves once a [`CancellationToken`] is cancelled.
+///
+/// This `struct` is created by the [`cancelled`] method on
+/// [`CancellationToken`]. See its documentation for more.
+///
+/// [`cancelled`]: CancellationToken::cancelled
+#[must_use = "futures do nothing unless polled or .awaited"]
+#[derive(Debug)]
+pub struct Cancelled {
+    inner: Rc<RefCell<Inner>>,
+    key: Option<usize>,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut inner = this.inner.borrow_mut();
+        if inner.cancelled {
+            // Our waker, if any, was drained on cancellation.
+            this.key = None;
+            return Poll::Ready(());
+        }
+        match this.key {
+            Some(key) => inner.wakers[key].clone_from(cx.waker()),
+            None => this.key = Some(inner.wakers.insert(cx.waker().clone())),
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Cancelled {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            let mut inner = self.inner.borrow_mut();
+            if !inner.cancelled {
+                inner.wakers.remove(key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::future::FutureExt;
+    use crate::runtime::block_on;
+    use crate::task::sleep;
+    use crate::time::Duration;
+    use futures_lite::future::zip;
+
+    #[test]
+    fn cancel_sleep() {
+        block_on(async {
+            let token = CancellationToken::new();
+            let (slept, ()) = zip(
+                sleep(Duration::from_secs(60)).timeout(token.cancelled()),
+                async {
+                    sleep(Duration::from_millis(10)).await;
+                    token.cancel();
+                },
+            )
+            .await;
+            assert!(slept.is_err());
+            assert!(token.is_cancelled());
+        })
+    }
+
+    #[test]
+    fn child_tokens() {
+        block_on(async {
+            let parent = CancellationToken::new();
+            let child = parent.child_token();
+            let grandchild = child.child_token();
+
+            child.cancel();
+            assert!(!parent.is_cancelled(), "cancellation doesn't propagate up");
+            assert!(grandchild.is_cancelled());
+
+            let other = parent.child_token();
+            parent.cancel();
+            assert!(other.is_cancelled());
+            assert!(parent.child_token().is_cancelled());
+            other.cancelled().await;
+        })
+    }
+}
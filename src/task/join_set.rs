@@ -0,0 +1,186 @@
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::Poll;
+
+/// A collection of tasks which can be awaited in the order they complete.
+///
+/// This is modeled after tokio's `JoinSet`. One important difference is that
+/// `wstd` has no background executor: tasks in a `JoinSet` only make progress
+/// while [`join_next`](JoinSet::join_next) is being awaited. Dropping the
+/// `JoinSet` drops all of its tasks.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::task::{sleep, JoinSet};
+/// use wstd::time::Duration;
+///
+/// #[wstd::main]
+/// async fn main() {
+///     let mut set = JoinSet::new();
+///     for millis in [30, 10, 20] {
+///         set.spawn(async move {
+///             sleep(Duration::from_millis(millis)).await;
+///             millis
+///         });
+///     }
+///     while let Some(res) = set.join_next().await {
+///         println!("task slept for {}ms", res.unwrap());
+///     }
+/// }
+/// ```
+pub struct JoinSet<T> {
+    tasks: Vec<Pin<Box<dyn Future<Output = T>>>>,
+    /// The number of aborted tasks whose `JoinError` hasn't been returned by
+    /// `join_next` yet.
+    aborted: usize,
+}
+
+impl<T> JoinSet<T> {
+    /// Create a new, empty `JoinSet`.
+    pub fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            aborted: 0,
+        }
+    }
+
+    /// Add a task to the set.
+    pub fn spawn<F>(&mut self, task: F)
+    where
+        F: Future<Output = T> + 'static,
+    {
+        self.tasks.push(Box::pin(task));
+    }
+
+    /// Returns the number of tasks in the set, including aborted tasks which
+    /// haven't been reaped by [`join_next`](JoinSet::join_next) yet.
+    pub fn len(&self) -> usize {
+        self.tasks.len() + self.aborted
+    }
+
+    /// Returns `true` if there are no tasks in the set.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Wait for one of the tasks in the set to complete, and return its
+    /// output.
+    ///
+    /// Tasks are returned in the order they complete. Aborted tasks are
+    /// returned first, as a [`JoinError`]. Returns `None` if the set is
+    /// empty.
+    pub async fn join_next(&mut self) -> Option<Result<T, JoinError>> {
+        if self.aborted > 0 {
+            self.aborted -= 1;
+            return Some(Err(JoinError::Cancelled));
+        }
+        if self.tasks.is_empty() {
+            return None;
+        }
+        poll_fn(|cx| {
+            for (index, task) in self.tasks.iter_mut().enumerate() {
+                if let Poll::Ready(output) = task.as_mut().poll(cx) {
+                    drop(self.tasks.swap_remove(index));
+                    return Poll::Ready(Some(Ok(output)));
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Abort all tasks in the set.
+    ///
+    /// The tasks are dropped immediately. Each of them is subsequently
+    /// returned by [`join_next`](JoinSet::join_next) as a cancelled
+    /// [`JoinError`].
+    pub fn abort_all(&mut self) {
+        self.aborted += self.tasks.len();
+        self.tasks.clear();
+    }
+
+    /// Abort all tasks in the set and remove them.
+    pub fn shutdown(&mut self) {
+        self.tasks.clear();
+        self.aborted = 0;
+    }
+}
+
+impl<T> Default for JoinSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for JoinSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinSet").field("len", &self.len()).finish()
+    }
+}
+
+/// An error returned when a task in a [`JoinSet`] failed to complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JoinError {
+    /// The task was aborted before it completed.
+    Cancelled,
+}
+
+impl JoinError {
+    /// Returns `true` if the task was aborted.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, JoinError::Cancelled)
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Cancelled => "task was cancelled".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use crate::task::sleep;
+    use crate::time::Duration;
+
+    #[test]
+    fn join_in_completion_order() {
+        block_on(async {
+            let mut set = JoinSet::new();
+            for millis in [30, 10, 20] {
+                set.spawn(async move {
+                    sleep(Duration::from_millis(millis)).await;
+                    millis
+                });
+            }
+            let mut out = Vec::new();
+            while let Some(res) = set.join_next().await {
+                out.push(res.unwrap());
+            }
+            assert_eq!(out, [10, 20, 30]);
+        })
+    }
+
+    #[test]
+    fn abort_all() {
+        block_on(async {
+            let mut set = JoinSet::new();
+            set.spawn(sleep(Duration::from_secs(60)));
+            set.spawn(sleep(Duration::from_secs(60)));
+            set.abort_all();
+            assert_eq!(set.len(), 2);
+            assert!(set.join_next().await.unwrap().unwrap_err().is_cancelled());
+            assert!(set.join_next().await.unwrap().unwrap_err().is_cancelled());
+            assert!(set.join_next().await.is_none());
+        })
+    }
+}
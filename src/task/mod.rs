@@ -0,0 +1,29 @@
+//! Types and Traits for working with asynchronous tasks.
+
+mod barrier;
+mod cancellation;
+mod join_set;
+mod mutex;
+mod semaphore;
+mod wait_group;
+mod yield_now;
+
+pub use barrier::{Barrier, BarrierWait};
+pub use cancellation::{CancellationToken, Cancelled};
+pub use join_set::{JoinError, JoinSet};
+pub use mutex::{Mutex, MutexGuard};
+pub use semaphore::{Acquire, Permit, Semaphore};
+pub use wait_group::{WaitGroup, WaitGroupWait};
+pub use yield_now::{run_chunked, yield_now, YieldNow};
+
+use crate::time::{Duration, Instant, Timer, Wait};
+
+/// Sleeps for the specified amount of time.
+pub fn sleep(dur: Duration) -> Wait {
+    Timer::after(dur).wait()
+}
+
+/// Sleeps until the specified instant.
+pub fn sleep_until(deadline: Instant) -> Wait {
+    Timer::at(deadline).wait()
+}
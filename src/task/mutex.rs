@@ -0,0 +1,195 @@
+use std::cell::{RefCell, RefMut};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use super::{Permit, Semaphore};
+
+/// An async mutex, which guards a value across await points.
+///
+/// WASI 0.2 has no threads, so this is not about thread safety: `Mutex` is
+/// neither `Send` nor `Sync`. It coordinates tasks on the same thread, such
+/// as those in a [`JoinSet`](super::JoinSet). Holding a
+/// `RefCell::borrow_mut` across an `.await` panics as soon as another task
+/// borrows the same cell; [`lock`](Mutex::lock) waits instead, and hands the
+/// lock to waiting tasks in the order they asked for it.
+///
+/// To share a `Mutex` between `'static` tasks, wrap it in an `Rc`.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::rc::Rc;
+/// use wstd::task::{sleep, JoinSet, Mutex};
+/// use wstd::time::Duration;
+///
+/// #[wstd::main]
+/// async fn main() {
+///     let log = Rc::new(Mutex::new(Vec::new()));
+///     let mut set = JoinSet::new();
+///     for id in 0..3 {
+///         let log = log.clone();
+///         set.spawn(async move {
+///             let mut log = log.lock().await;
+///             log.push(format!("task {id} started"));
+///             sleep(Duration::from_millis(10)).await;
+///             log.push(format!("task {id} finished"));
+///         });
+///     }
+///     while set.join_next().await.is_some() {}
+///     println!("{:?}", log.lock().await);
+/// }
+/// ```
+pub struct Mutex<T: ?Sized> {
+    semaphore: Semaphore,
+    value: RefCell<T>,
+}
+
+impl<T> Mutex<T> {
+    /// Create a new mutex guarding `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            semaphore: Semaphore::new(1),
+            value: RefCell::new(value),
+        }
+    }
+
+    /// Consumes the mutex, returning the guarded value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Wait until the lock is free, and acquire it.
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        let permit = self.semaphore.acquire().await;
+        self.guard(permit)
+    }
+
+    /// Acquire the lock if it is free right away, and no other task is
+    /// waiting for it.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        let permit = self.semaphore.try_acquire()?;
+        Some(self.guard(permit))
+    }
+
+    /// Returns a mutable reference to the guarded value.
+    ///
+    /// Since this takes `&mut self`, no locking is needed.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    fn guard(&self, permit: Permit) -> MutexGuard<'_, T> {
+        MutexGuard {
+            // Holding the only permit means nobody else holds a borrow.
+            value: self.value.borrow_mut(),
+            _permit: permit,
+        }
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Mutex");
+        match self.try_lock() {
+            Some(guard) => d.field("value", &&*guard),
+            None => d.field("value", &format_args!("<locked>")),
+        };
+        d.finish_non_exhaustive()
+    }
+}
+
+/// Grants access to the value in a [`Mutex`], and releases the lock when
+/// dropped.
+///
+/// This `struct` is created by the [`lock`] and [`try_lock`] methods on
+/// [`Mutex`]. See their documentation for more.
+///
+/// [`lock`]: Mutex::lock
+/// [`try_lock`]: Mutex::try_lock
+#[must_use = "if unused the Mutex will immediately unlock"]
+pub struct MutexGuard<'a, T: ?Sized> {
+    // Field order matters: the borrow must end before the lock is released.
+    value: RefMut<'a, T>,
+    _permit: Permit,
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use crate::task::{sleep, JoinSet};
+    use crate::time::Duration;
+    use std::rc::Rc;
+
+    #[test]
+    fn lock_across_await() {
+        block_on(async {
+            let log = Rc::new(Mutex::new(Vec::new()));
+            let mut set = JoinSet::new();
+            for id in 0..3 {
+                let log = log.clone();
+                set.spawn(async move {
+                    let mut log = log.lock().await;
+                    log.push((id, "start"));
+                    sleep(Duration::from_millis(5)).await;
+                    log.push((id, "end"));
+                });
+            }
+            while set.join_next().await.is_some() {}
+            let log = Rc::into_inner(log).unwrap().into_inner();
+            assert_eq!(
+                log,
+                [
+                    (0, "start"),
+                    (0, "end"),
+                    (1, "start"),
+                    (1, "end"),
+                    (2, "start"),
+                    (2, "end")
+                ],
+                "critical sections don't interleave, and run in order"
+            );
+        })
+    }
+
+    #[test]
+    fn try_lock() {
+        let mut mutex = Mutex::new(1);
+        let mut guard = mutex.try_lock().unwrap();
+        *guard += 1;
+        assert!(mutex.try_lock().is_none());
+        assert_eq!(format!("{mutex:?}"), "Mutex { value: <locked>, .. }");
+        drop(guard);
+        assert_eq!(*mutex.try_lock().unwrap(), 2);
+        *mutex.get_mut() += 1;
+        assert_eq!(format!("{mutex:?}"), "Mutex { value: 3, .. }");
+    }
+}
@@ -0,0 +1,248 @@
+use slab::Slab;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+/// An async semaphore, which hands out a limited number of permits.
+///
+/// This is useful to bound concurrency, for example the number of
+/// connections a server handles at once. Permits are released when the
+/// [`Permit`] is dropped. Waiters are served in the order they started
+/// waiting. Cloned semaphores share their permits.
+///
+/// Like [`CancellationToken`](super::CancellationToken), a semaphore doesn't
+/// need any WASI pollables: waiters are woken directly when a permit is
+/// released.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::iter::AsyncIterator;
+/// use wstd::net::TcpListener;
+/// use wstd::task::{JoinSet, Semaphore};
+///
+/// #[wstd::main]
+/// async fn main() -> std::io::Result<()> {
+///     let listener = TcpListener::bind("127.0.0.1:8080").await?;
+///     let limit = Semaphore::new(100);
+///     let mut tasks = JoinSet::new();
+///     let mut incoming = listener.incoming();
+///     loop {
+///         // Wait for a free slot before accepting the next connection.
+///         let permit = limit.acquire().await;
+///         let stream = incoming.next().await.unwrap()?;
+///         tasks.spawn(async move {
+///             let _permit = permit;
+///             let _stream = stream;
+///             // ... handle the connection ...
+///         });
+///         # break;
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Semaphore {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    permits: usize,
+    waiters: Slab<Waiter>,
+    /// Keys into `waiters`, in the order they started waiting.
+    queue: VecDeque<usize>,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    waker: Waker,
+    /// Set once a released permit has been handed to this waiter.
+    granted: bool,
+}
+
+impl Inner {
+    /// Hands a permit to the longest waiting task, or returns it to the pool.
+    fn release(&mut self) {
+        match self.queue.pop_front() {
+            Some(key) => {
+                let waiter = &mut self.waiters[key];
+                waiter.granted = true;
+                waiter.waker.wake_by_ref();
+            }
+            None => self.permits += 1,
+        }
+    }
+}
+
+impl Semaphore {
+    /// Create a semaphore with `permits` available permits.
+    pub fn new(permits: usize) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(Inner {
+                permits,
+                waiters: Slab::new(),
+                queue: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Returns the number of permits which can be acquired without waiting.
+    pub fn available_permits(&self) -> usize {
+        self.inner.borrow().permits
+    }
+
+    /// Wait for a permit to become available, and acquire it.
+    pub fn acquire(&self) -> Acquire {
+        Acquire {
+            inner: self.inner.clone(),
+            key: None,
+        }
+    }
+
+    /// Acquire a permit if one is available right away.
+    ///
+    /// This never takes a permit ahead of tasks which are already waiting.
+    pub fn try_acquire(&self) -> Option<Permit> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.permits > 0 && inner.queue.is_empty() {
+            inner.permits -= 1;
+            Some(Permit {
+                inner: self.inner.clone(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// A permit from a [`Semaphore`], which is released when dropped.
+#[must_use = "the permit is released immediately if it is not held"]
+#[derive(Debug)]
+pub struct Permit {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.inner.borrow_mut().release();
+    }
+}
+
+/// A future which resolves to a [`Permit`] once one is available.
+///
+/// This `struct` is created by the [`acquire`] method on [`Semaphore`]. See
+/// its documentation for more.
+///
+/// [`acquire`]: Semaphore::acquire
+#[must_use = "futures do nothing unless polled or .awaited"]
+#[derive(Debug)]
+pub struct Acquire {
+    inner: Rc<RefCell<Inner>>,
+    key: Option<usize>,
+}
+
+impl Future for Acquire {
+    type Output = Permit;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut inner = this.inner.borrow_mut();
+        match this.key {
+            None if inner.permits > 0 && inner.queue.is_empty() => {
+                inner.permits -= 1;
+            }
+            None => {
+                let key = inner.waiters.insert(Waiter {
+                    waker: cx.waker().clone(),
+                    granted: false,
+                });
+                inner.queue.push_back(key);
+                this.key = Some(key);
+                return Poll::Pending;
+            }
+            Some(key) if inner.waiters[key].granted => {
+                inner.waiters.remove(key);
+                this.key = None;
+            }
+            Some(key) => {
+                inner.waiters[key].waker.clone_from(cx.waker());
+                return Poll::Pending;
+            }
+        }
+        Poll::Ready(Permit {
+            inner: this.inner.clone(),
+        })
+    }
+}
+
+impl Drop for Acquire {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            let mut inner = self.inner.borrow_mut();
+            if inner.waiters.remove(key).granted {
+                // We were handed a permit but never took it: pass it on.
+                inner.release();
+            } else {
+                inner.queue.retain(|k| *k != key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use crate::task::{sleep, JoinSet};
+    use crate::time::Duration;
+    use std::cell::Cell;
+
+    #[test]
+    fn at_most_n_permits() {
+        block_on(async {
+            let semaphore = Semaphore::new(2);
+            let held = Rc::new(Cell::new(0));
+            let max_held = Rc::new(Cell::new(0));
+            let mut set = JoinSet::new();
+            for _ in 0..6 {
+                let semaphore = semaphore.clone();
+                let (held, max_held) = (held.clone(), max_held.clone());
+                set.spawn(async move {
+                    let _permit = semaphore.acquire().await;
+                    held.set(held.get() + 1);
+                    max_held.set(max_held.get().max(held.get()));
+                    sleep(Duration::from_millis(5)).await;
+                    held.set(held.get() - 1);
+                });
+            }
+            while set.join_next().await.is_some() {}
+            assert_eq!(max_held.get(), 2);
+            assert_eq!(semaphore.available_permits(), 2);
+        })
+    }
+
+    #[test]
+    fn cancelled_acquire_passes_permit_on() {
+        block_on(async {
+            let semaphore = Semaphore::new(1);
+            let permit = semaphore.try_acquire().unwrap();
+            assert!(semaphore.try_acquire().is_none());
+
+            let mut first = Box::pin(semaphore.acquire());
+            let mut second = Box::pin(semaphore.acquire());
+            assert!(futures_lite::future::poll_once(&mut first).await.is_none());
+            assert!(futures_lite::future::poll_once(&mut second).await.is_none());
+
+            // The permit is granted to `first`, which is dropped unused.
+            drop(permit);
+            assert!(semaphore.try_acquire().is_none(), "waiters go first");
+            drop(first);
+            let _permit = second.await;
+            assert_eq!(semaphore.available_permits(), 0);
+        })
+    }
+}
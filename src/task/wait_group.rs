@@ -0,0 +1,201 @@
+use slab::Slab;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+/// A counter of outstanding work, which can be waited on until it drops to
+/// zero.
+///
+/// Call [`add`](WaitGroup::add) before starting a piece of background work,
+/// and [`done`](WaitGroup::done) once it has finished. [`wait`](WaitGroup::wait)
+/// then resolves once all the work which was added is done. Unlike
+/// [`JoinSet`](super::JoinSet), a wait group doesn't own the work it tracks,
+/// nor its results. Cloned wait groups share their counter.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures_lite::future::zip;
+/// use wstd::task::{sleep, WaitGroup};
+/// use wstd::time::Duration;
+///
+/// #[wstd::main]
+/// async fn main() {
+///     let group = WaitGroup::new();
+///     group.add(1);
+///     let background = {
+///         let group = group.clone();
+///         async move {
+///             sleep(Duration::from_millis(10)).await;
+///             group.done();
+///         }
+///     };
+///     // Resolves once `done` has been called.
+///     zip(background, group.wait()).await;
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WaitGroup {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    count: usize,
+    /// Incremented every time the count drops to zero, so waiters can tell
+    /// whether it did after they started waiting.
+    generation: u64,
+    wakers: Slab<Waker>,
+}
+
+impl WaitGroup {
+    /// Create a wait group with no outstanding work.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `n` pieces of outstanding work.
+    pub fn add(&self, n: usize) {
+        self.inner.borrow_mut().count += n;
+    }
+
+    /// Mark one piece of work as done, waking all waiters if it was the last.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if there is no outstanding work.
+    pub fn done(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.count = inner
+            .count
+            .checked_sub(1)
+            .expect("`WaitGroup::done` called more often than `add`");
+        if inner.count == 0 {
+            inner.generation += 1;
+            for waker in inner.wakers.drain() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Returns the amount of outstanding work.
+    pub fn count(&self) -> usize {
+        self.inner.borrow().count
+    }
+
+    /// Wait until there is no outstanding work.
+    ///
+    /// This resolves right away if there is none.
+    pub fn wait(&self) -> WaitGroupWait {
+        WaitGroupWait {
+            inner: self.inner.clone(),
+            waiting: None,
+        }
+    }
+}
+
+/// A future which resolves once a [`WaitGroup`] has no outstanding work.
+///
+/// This `struct` is created by the [`wait`] method on [`WaitGroup`]. See
+/// its documentation for more.
+///
+/// [`wait`]: WaitGroup::wait
+#[must_use = "futures do nothing unless polled or .awaited"]
+#[derive(Debug)]
+pub struct WaitGroupWait {
+    inner: Rc<RefCell<Inner>>,
+    /// The generation this waiter started waiting in, and the key of its
+    /// waker.
+    waiting: Option<(u64, usize)>,
+}
+
+impl Future for WaitGroupWait {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut inner = this.inner.borrow_mut();
+        match this.waiting {
+            // Work may have been added again since the count dropped to zero,
+            // which doesn't hold up waiters that were already woken.
+            Some((generation, _)) if generation != inner.generation => {
+                this.waiting = None;
+                Poll::Ready(())
+            }
+            Some((_, key)) => {
+                inner.wakers[key].clone_from(cx.waker());
+                Poll::Pending
+            }
+            None if inner.count == 0 => Poll::Ready(()),
+            None => {
+                let key = inner.wakers.insert(cx.waker().clone());
+                this.waiting = Some((inner.generation, key));
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for WaitGroupWait {
+    fn drop(&mut self) {
+        if let Some((generation, key)) = self.waiting {
+            let mut inner = self.inner.borrow_mut();
+            if generation == inner.generation {
+                inner.wakers.remove(key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use crate::task::{sleep, JoinSet};
+    use crate::time::Duration;
+    use std::cell::Cell;
+
+    #[test]
+    fn waits_for_all_work() {
+        block_on(async {
+            let group = WaitGroup::new();
+            group.wait().await;
+
+            let finished = Rc::new(Cell::new(0));
+            let mut set = JoinSet::new();
+            for n in 1..=3 {
+                group.add(1);
+                let group = group.clone();
+                let finished = finished.clone();
+                set.spawn(async move {
+                    sleep(Duration::from_millis(n * 5)).await;
+                    finished.set(finished.get() + 1);
+                    group.done();
+                });
+            }
+            assert_eq!(group.count(), 3);
+            let ((), ()) = futures_lite::future::zip(group.wait(), async {
+                while set.join_next().await.is_some() {}
+            })
+            .await;
+            assert_eq!(finished.get(), 3);
+            assert_eq!(group.count(), 0);
+        })
+    }
+
+    #[test]
+    fn woken_waiter_resolves_after_more_work_is_added() {
+        block_on(async {
+            let group = WaitGroup::new();
+            group.add(1);
+            let mut wait = Box::pin(group.wait());
+            assert!(futures_lite::future::poll_once(&mut wait).await.is_none());
+            group.done();
+            group.add(1);
+            wait.await;
+            assert_eq!(group.count(), 1);
+        })
+    }
+}
@@ -0,0 +1,115 @@
+use crate::runtime::Reactor;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Yields to the event loop once, so I/O which is ready can make progress.
+///
+/// There is only one thread in WASI 0.2, so futures only run while nothing
+/// else does. A future which computes for a long time without awaiting
+/// anything holds up every other future in the program, such as those
+/// accepting connections or writing responses. Awaiting `yield_now` in
+/// between parts of the computation lets the event loop check for ready
+/// pollables without blocking, and wake their futures, before the current
+/// future continues.
+///
+/// See [`run_chunked`] for a convenient way to do this.
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+/// A future which yields to the event loop once.
+///
+/// This `struct` is created by the [`yield_now`] function. See its
+/// documentation for more.
+#[must_use = "futures do nothing unless polled or .awaited"]
+#[derive(Debug)]
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        Reactor::current().request_yield();
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Calls `f` on every item of `iter`, yielding to the event loop after every
+/// `chunk_size` items.
+///
+/// This keeps a program responsive while it does CPU-bound work, such as
+/// parsing a large document, by letting I/O make progress in between chunks.
+/// It doesn't make the work itself any faster: WASI 0.2 has no threads, so
+/// true parallelism isn't possible, and the work can only be interleaved with
+/// other futures. A smaller `chunk_size` lets other futures run more often,
+/// at the cost of more time spent yielding.
+///
+/// # Panics
+///
+/// This function will panic if `chunk_size` is zero.
+///
+/// # Example
+///
+/// ```no_run
+/// # wstd::runtime::block_on(async {
+/// use wstd::task::run_chunked;
+///
+/// let mut sum = 0;
+/// run_chunked(1..=1000, 100, |n| sum += n).await;
+/// assert_eq!(sum, 500500);
+/// # });
+/// ```
+pub async fn run_chunked<I, F>(iter: I, chunk_size: usize, mut f: F)
+where
+    I: IntoIterator,
+    F: FnMut(I::Item),
+{
+    assert!(chunk_size > 0, "chunk size must be nonzero");
+    let mut iter = iter.into_iter().peekable();
+    loop {
+        for item in iter.by_ref().take(chunk_size) {
+            f(item);
+        }
+        if iter.peek().is_none() {
+            return;
+        }
+        yield_now().await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use crate::task::sleep;
+    use crate::time::{Duration, Instant};
+    use std::cell::Cell;
+
+    #[test]
+    fn io_progresses_between_chunks() {
+        block_on(async {
+            let processed = Cell::new(0);
+            let processed_when_woken = async {
+                sleep(Duration::from_millis(1)).await;
+                processed.get()
+            };
+            let work = run_chunked(0..100, 10, |_| {
+                // Busy-wait, standing in for CPU-bound work.
+                let start = Instant::now();
+                while start.elapsed() < Duration::from_micros(100) {}
+                processed.set(processed.get() + 1);
+            });
+            let (woken, ()) = futures_lite::future::zip(processed_when_woken, work).await;
+            assert!(woken < 100, "the timer fired before the work was done");
+            assert_eq!(processed.get(), 100);
+        })
+    }
+}
@@ -0,0 +1,135 @@
+use super::{timeout_at, Duration, Elapsed, Instant, Timer, Wait};
+use std::future::IntoFuture;
+
+/// A point in time by which a sequence of operations must complete.
+///
+/// Timing out each step of a sequence separately, such as connecting,
+/// sending a request, and reading the response, doesn't bound how long the
+/// whole sequence takes. A `Deadline` is an overall time budget instead:
+/// each step is run with [`run`](Deadline::run), and the budget left over
+/// shrinks as the steps complete.
+///
+/// A deadline can be created from an [`Instant`] or from a [`Duration`]
+/// starting now. It can also be passed anywhere a timeout is accepted as a
+/// future, such as [`FutureExt::timeout`](crate::future::FutureExt::timeout).
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::http::{Client, Request};
+/// use wstd::io::{empty, AsyncRead};
+/// use wstd::time::{Deadline, Duration};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let deadline = Deadline::after(Duration::from_secs(5));
+/// let request = Request::get("https://example.com").body(empty())?;
+/// let response = Client::new().send_with_deadline(request, deadline).await?;
+/// let mut body = Vec::new();
+/// let mut response_body = response.into_body();
+/// deadline.run(response_body.read_to_end(&mut body)).await??;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+    /// A deadline at `instant`.
+    pub fn at(instant: Instant) -> Self {
+        Self(instant)
+    }
+
+    /// A deadline `duration` from now.
+    pub fn after(duration: Duration) -> Self {
+        Self(Instant::now() + duration)
+    }
+
+    /// The instant this deadline is at.
+    pub fn instant(&self) -> Instant {
+        self.0
+    }
+
+    /// Returns the time left until the deadline, or `None` if it has passed.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.0
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| remaining.0 > 0)
+    }
+
+    /// Returns `true` if the deadline has passed.
+    pub fn is_elapsed(&self) -> bool {
+        self.remaining().is_none()
+    }
+
+    /// Sleeps until the deadline.
+    pub fn sleep_until(&self) -> Wait {
+        Timer::at(self.0).wait()
+    }
+
+    /// Run `future`, failing with [`Elapsed`] if it doesn't complete by the
+    /// deadline.
+    ///
+    /// This is the same as [`timeout_at`](super::timeout_at).
+    pub async fn run<F: IntoFuture>(&self, future: F) -> Result<F::Output, Elapsed> {
+        timeout_at(self.0, future).await
+    }
+}
+
+impl From<Instant> for Deadline {
+    fn from(instant: Instant) -> Self {
+        Self::at(instant)
+    }
+}
+
+impl From<Duration> for Deadline {
+    /// A deadline `duration` from now.
+    fn from(duration: Duration) -> Self {
+        Self::after(duration)
+    }
+}
+
+impl IntoFuture for Deadline {
+    type Output = Instant;
+
+    type IntoFuture = Wait;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.sleep_until()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use crate::task::sleep;
+
+    #[test]
+    fn budget_shrinks_across_steps() {
+        block_on(async {
+            let deadline = Deadline::from(Duration::from_millis(50));
+            let before = deadline.remaining().unwrap();
+            assert!(before <= Duration::from_millis(50));
+
+            deadline
+                .run(sleep(Duration::from_millis(10)))
+                .await
+                .unwrap();
+            let after = deadline.remaining().unwrap();
+            assert!(after <= before - Duration::from_millis(10));
+
+            let res = deadline.run(sleep(Duration::from_secs(10))).await;
+            assert!(res.is_err());
+            assert!(deadline.is_elapsed());
+            assert_eq!(deadline.remaining(), None);
+        })
+    }
+
+    #[test]
+    fn conversions() {
+        let instant = Instant::from_nanos(1_000);
+        assert_eq!(Deadline::from(instant).instant(), instant);
+        assert_eq!(Deadline::at(instant), instant.into());
+        assert!(Deadline::at(instant).is_elapsed());
+    }
+}
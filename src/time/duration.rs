@@ -41,6 +41,13 @@ impl Duration {
         std::time::Duration::from_micros(micros).into()
     }
 
+    /// Creates a new `Duration` from the specified number of nanoseconds.
+    #[must_use]
+    #[inline]
+    pub fn from_nanos(nanos: u64) -> Self {
+        std::time::Duration::from_nanos(nanos).into()
+    }
+
     /// Creates a new `Duration` from the specified number of seconds represented
     /// as `f64`.
     ///
@@ -6,6 +6,13 @@ use wasi::clocks::monotonic_clock;
 /// A measurement of a monotonically nondecreasing clock. Opaque and useful only
 /// with Duration.
 ///
+/// Instants are read from WASI's `monotonic-clock`, which is guaranteed never
+/// to go backwards: an instant taken later in a program never compares less
+/// than one taken earlier. Instants may still be compared out of order, for
+/// example when one is computed by adding a `Duration`; use
+/// [`checked_duration_since`](Instant::checked_duration_since) to detect that,
+/// or rely on [`duration_since`](Instant::duration_since) saturating to zero.
+///
 /// This type wraps `std::time::Duration` so we can implement traits on it
 /// without coherence issues, just like if we were implementing this in the
 /// stdlib.
@@ -30,13 +37,67 @@ impl Instant {
     /// Returns the amount of time elapsed from another instant to this one, or zero duration if
     /// that instant is later than this one.
     pub fn duration_since(&self, earlier: Instant) -> Duration {
-        Duration::from_micros(self.0.checked_sub(earlier.0).unwrap_or_default())
+        self.saturating_duration_since(earlier)
+    }
+
+    /// Returns the amount of time elapsed from another instant to this one, or `None` if that
+    /// instant is later than this one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wstd::time::{Duration, Instant};
+    ///
+    /// let now = Instant::now();
+    /// let later = now + Duration::from_millis(10);
+    /// assert_eq!(later.checked_duration_since(now), Some(Duration::from_millis(10)));
+    /// assert_eq!(now.checked_duration_since(later), None);
+    /// ```
+    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+        self.0.checked_sub(earlier.0).map(Duration)
+    }
+
+    /// Returns the amount of time elapsed from another instant to this one, or zero duration if
+    /// that instant is later than this one.
+    pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or(Duration(0))
     }
 
     /// Returns the amount of time elapsed since this instant.
     pub fn elapsed(&self) -> Duration {
         Instant::now().duration_since(*self)
     }
+
+    /// Returns the raw value of the monotonic clock at this instant, in
+    /// nanoseconds.
+    ///
+    /// The clock's starting point is unspecified, so this value is only
+    /// meaningful when compared with other instants from the same run of a
+    /// component. It must not be persisted or sent to another component and
+    /// read back as an `Instant` there.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wstd::time::{Duration, Instant};
+    ///
+    /// let now = Instant::now();
+    /// let later = now + Duration::from_nanos(5);
+    /// assert_eq!(later.as_nanos() - now.as_nanos(), 5);
+    /// assert_eq!(Instant::from_nanos(now.as_nanos()), now);
+    /// ```
+    pub fn as_nanos(&self) -> u64 {
+        self.0
+    }
+
+    /// Creates an instant from a raw monotonic clock value in nanoseconds, as
+    /// returned by [`as_nanos`](Instant::as_nanos).
+    ///
+    /// Like `as_nanos`, this is only meaningful within a single run of a
+    /// component.
+    pub fn from_nanos(nanos: u64) -> Self {
+        Instant(nanos)
+    }
 }
 
 impl Add<Duration> for Instant {
@@ -90,3 +151,33 @@ impl IntoFuture for Instant {
         crate::task::sleep_until(self)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn duration_since_out_of_order() {
+        let earlier = Instant(1_000);
+        let later = Instant(1_000_000_000);
+        let diff = Duration::from_nanos(999_999_000);
+        assert_eq!(later.checked_duration_since(earlier), Some(diff));
+        assert_eq!(earlier.checked_duration_since(later), None);
+        assert_eq!(later.saturating_duration_since(earlier), diff);
+        assert_eq!(
+            earlier.saturating_duration_since(later),
+            Duration::from_nanos(0)
+        );
+        assert_eq!(later.duration_since(earlier), diff);
+        assert_eq!(earlier.duration_since(later), Duration::from_nanos(0));
+    }
+
+    #[test]
+    fn nanos_round_trip() {
+        let now = Instant::now();
+        let later = now + Duration::from_millis(1);
+        assert_eq!(Instant::from_nanos(now.as_nanos()), now);
+        assert_eq!(later.as_nanos() - now.as_nanos(), 1_000_000);
+        assert!(Instant::from_nanos(later.as_nanos()) > now);
+    }
+}
@@ -1,11 +1,18 @@
 //! Async time interfaces.
 
 pub(crate) mod utils;
+use utils::CivilDateTime;
 
+mod deadline;
 mod duration;
 mod instant;
+mod timeout;
+mod timer_wheel;
+pub use deadline::Deadline;
 pub use duration::Duration;
 pub use instant::Instant;
+pub use timeout::{timeout, timeout_at, Elapsed};
+pub use timer_wheel::{TimerKey, TimerWheel};
 
 use pin_project_lite::pin_project;
 use std::future::Future;
@@ -24,13 +31,64 @@ use crate::{
 /// A measurement of the system clock, useful for talking to external entities
 /// like the file system or other processes.
 #[derive(Debug, Clone, Copy)]
-#[allow(dead_code)]
 pub struct SystemTime(wall_clock::Datetime);
 
 impl SystemTime {
     pub fn now() -> Self {
         Self(wall_clock::now())
     }
+
+    pub(crate) fn from_unix_seconds(seconds: u64) -> Self {
+        Self(wall_clock::Datetime {
+            seconds,
+            nanoseconds: 0,
+        })
+    }
+
+    /// Whole seconds since the Unix epoch.
+    pub(crate) fn unix_seconds(&self) -> u64 {
+        self.0.seconds
+    }
+
+    /// Format this time as an RFC 3339 timestamp in UTC, e.g.
+    /// `1994-11-06T08:49:37Z`.
+    ///
+    /// Fractional seconds are included only when they are non-zero.
+    pub fn to_rfc3339(&self) -> String {
+        let dt = CivilDateTime::from_unix_seconds(self.0.seconds);
+        let mut out = format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            dt.year, dt.month, dt.day, dt.hour, dt.minute, dt.second
+        );
+        if self.0.nanoseconds != 0 {
+            out.push_str(&format!(".{:09}", self.0.nanoseconds));
+        }
+        out.push('Z');
+        out
+    }
+
+    /// Format this time as an HTTP date (the IMF-fixdate format from RFC 9110),
+    /// e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+    ///
+    /// This is the format expected by headers such as `Date` and
+    /// `Last-Modified`.
+    pub fn to_http_date(&self) -> String {
+        const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        let dt = CivilDateTime::from_unix_seconds(self.0.seconds);
+        format!(
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+            WEEKDAYS[dt.weekday as usize],
+            dt.day,
+            MONTHS[dt.month as usize - 1],
+            dt.year,
+            dt.hour,
+            dt.minute,
+            dt.second
+        )
+    }
 }
 
 /// An async iterator representing notifications at fixed interval.
@@ -113,6 +171,42 @@ mod test {
         println!("{what} awaited for {} s", d.as_secs_f32());
     }
 
+    fn system_time(seconds: u64, nanoseconds: u32) -> SystemTime {
+        SystemTime(wall_clock::Datetime {
+            seconds,
+            nanoseconds,
+        })
+    }
+
+    #[test]
+    fn system_time_to_rfc3339() {
+        assert_eq!(system_time(0, 0).to_rfc3339(), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            system_time(784_111_777, 0).to_rfc3339(),
+            "1994-11-06T08:49:37Z"
+        );
+        assert_eq!(
+            system_time(951_782_400, 500_000_000).to_rfc3339(),
+            "2000-02-29T00:00:00.500000000Z"
+        );
+    }
+
+    #[test]
+    fn system_time_to_http_date() {
+        assert_eq!(
+            system_time(0, 0).to_http_date(),
+            "Thu, 01 Jan 1970 00:00:00 GMT"
+        );
+        assert_eq!(
+            system_time(784_111_777, 0).to_http_date(),
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+        assert_eq!(
+            system_time(1_709_164_799, 0).to_http_date(),
+            "Wed, 28 Feb 2024 23:59:59 GMT"
+        );
+    }
+
     #[test]
     fn timer_now() {
         crate::runtime::block_on(debug_duration("timer_now", async {
@@ -0,0 +1,95 @@
+use super::{Duration, Instant};
+use crate::future::FutureExt;
+use std::fmt;
+use std::future::IntoFuture;
+use std::io;
+
+/// Run `future`, failing with [`Elapsed`] if it doesn't complete within
+/// `duration`.
+///
+/// This is the same as [`FutureExt::timeout`], as a free function which
+/// returns a typed error. On timeout, `future` is dropped, which cancels it
+/// and releases the WASI resources it holds, such as pollables it was
+/// waiting on.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::task::sleep;
+/// use wstd::time::{self, Duration};
+///
+/// #[wstd::main]
+/// async fn main() {
+///     let slow = sleep(Duration::from_secs(10));
+///     let res = time::timeout(Duration::from_millis(50), slow).await;
+///     assert!(res.is_err());
+/// }
+/// ```
+pub async fn timeout<F: IntoFuture>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    future
+        .into_future()
+        .timeout(duration)
+        .await
+        .map_err(|_| Elapsed(()))
+}
+
+/// Run `future`, failing with [`Elapsed`] if it doesn't complete by
+/// `deadline`.
+///
+/// See [`timeout`] for more.
+pub async fn timeout_at<F: IntoFuture>(deadline: Instant, future: F) -> Result<F::Output, Elapsed> {
+    future
+        .into_future()
+        .timeout(deadline)
+        .await
+        .map_err(|_| Elapsed(()))
+}
+
+/// The error returned by [`timeout`] and [`timeout_at`] when the future
+/// didn't complete in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed(());
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "deadline has elapsed".fmt(f)
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+impl From<Elapsed> for io::Error {
+    fn from(e: Elapsed) -> Self {
+        io::Error::new(io::ErrorKind::TimedOut, e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+    use crate::task::sleep;
+
+    #[test]
+    fn completes_in_time() {
+        block_on(async {
+            let res = timeout(Duration::from_secs(10), async { "meow" }).await;
+            assert_eq!(res, Ok("meow"));
+            let deadline = Instant::now() + Duration::from_secs(10);
+            assert_eq!(timeout_at(deadline, async { 7 }).await, Ok(7));
+        })
+    }
+
+    #[test]
+    fn times_out() {
+        block_on(async {
+            let res = timeout(Duration::from_millis(10), sleep(Duration::from_secs(10))).await;
+            let err = io::Error::from(res.unwrap_err());
+            assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+            let deadline = Instant::now() + Duration::from_millis(10);
+            let res = timeout_at(deadline, sleep(Duration::from_secs(10))).await;
+            assert_eq!(res, Err(Elapsed(())));
+        })
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::{Duration, Instant, Timer};
+
+/// Identifies a timer registered with a [`TimerWheel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerKey(u64);
+
+/// Multiplexes many deadlines onto a single clock subscription.
+///
+/// Giving each of hundreds of connections its own [`Timer`] means a pollable
+/// and a reactor registration per connection. A `TimerWheel` instead keeps
+/// its deadlines in order and only ever subscribes to the clock for the
+/// nearest one, handing out expired timers from
+/// [`next_expired`](TimerWheel::next_expired).
+///
+/// # Resolution
+///
+/// Deadlines are rounded up to a multiple of the wheel's resolution, so
+/// every timer in the same slot expires at once, with a single wakeup. A
+/// coarse resolution wakes the component less often when deadlines are close
+/// together, at the cost of firing timers up to one resolution late. Timers
+/// never fire early.
+///
+/// # Example
+///
+/// ```no_run
+/// use wstd::time::{Duration, Instant, TimerWheel};
+///
+/// # wstd::runtime::block_on(async {
+/// let mut wheel = TimerWheel::new(Duration::from_millis(10));
+/// let slow = wheel.register(Instant::now() + Duration::from_millis(200));
+/// let fast = wheel.register(Instant::now() + Duration::from_millis(100));
+/// assert_eq!(wheel.next_expired().await, Some(fast));
+/// assert_eq!(wheel.next_expired().await, Some(slow));
+/// assert_eq!(wheel.next_expired().await, None);
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct TimerWheel {
+    resolution: u64,
+    /// Pending timers, ordered by slot and then by registration.
+    timers: BTreeSet<(u64, TimerKey)>,
+    slots: HashMap<TimerKey, u64>,
+    next_key: u64,
+}
+
+impl TimerWheel {
+    /// Creates an empty `TimerWheel` which rounds deadlines up to a multiple
+    /// of `resolution`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `resolution` is zero.
+    pub fn new(resolution: Duration) -> Self {
+        let resolution = resolution.0;
+        assert!(resolution > 0, "TimerWheel resolution must be non-zero");
+        Self {
+            resolution,
+            timers: BTreeSet::new(),
+            slots: HashMap::new(),
+            next_key: 0,
+        }
+    }
+
+    /// Registers a timer which expires at `deadline`.
+    pub fn register(&mut self, deadline: Instant) -> TimerKey {
+        let key = TimerKey(self.next_key);
+        self.next_key += 1;
+        let slot = deadline.0.div_ceil(self.resolution);
+        self.timers.insert((slot, key));
+        self.slots.insert(key, slot);
+        key
+    }
+
+    /// Cancels a pending timer. Returns `false` if it had already expired or
+    /// been cancelled.
+    pub fn cancel(&mut self, key: TimerKey) -> bool {
+        match self.slots.remove(&key) {
+            Some(slot) => self.timers.remove(&(slot, key)),
+            None => false,
+        }
+    }
+
+    /// Returns the number of pending timers.
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// Returns `true` if there are no pending timers.
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// Returns the instant at which the next timer expires, rounded up to the
+    /// wheel's resolution.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let (slot, _) = self.timers.first()?;
+        Some(self.slot_deadline(*slot))
+    }
+
+    /// Waits for the next timer to expire, and removes it from the wheel.
+    ///
+    /// Timers expire in order of their deadline; timers in the same slot
+    /// expire in the order they were registered. Returns `None` immediately
+    /// if no timers are pending.
+    ///
+    /// This is cancel-safe: if the returned future is dropped before it
+    /// completes, no timer is removed.
+    pub async fn next_expired(&mut self) -> Option<TimerKey> {
+        loop {
+            let deadline = self.next_deadline()?;
+            if deadline <= Instant::now() {
+                let (_, key) = self.timers.pop_first().unwrap();
+                self.slots.remove(&key);
+                return Some(key);
+            }
+            Timer::at(deadline).wait().await;
+        }
+    }
+
+    fn slot_deadline(&self, slot: u64) -> Instant {
+        Instant(slot.saturating_mul(self.resolution))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::runtime::block_on;
+
+    #[test]
+    fn expire_in_deadline_order() {
+        block_on(async {
+            let mut wheel = TimerWheel::new(Duration::from_millis(5));
+            let start = Instant::now();
+            let c = wheel.register(start + Duration::from_millis(30));
+            let a = wheel.register(start + Duration::from_millis(10));
+            let b = wheel.register(start + Duration::from_millis(20));
+            let cancelled = wheel.register(start + Duration::from_millis(15));
+            assert_eq!(wheel.len(), 4);
+            assert!(wheel.cancel(cancelled));
+            assert!(!wheel.cancel(cancelled));
+
+            for (key, after) in [(a, 10), (b, 20), (c, 30)] {
+                assert_eq!(wheel.next_expired().await, Some(key));
+                let elapsed: std::time::Duration = start.elapsed().into();
+                assert!(elapsed >= std::time::Duration::from_millis(after));
+            }
+            assert!(wheel.is_empty());
+            assert_eq!(wheel.next_expired().await, None);
+        })
+    }
+
+    #[test]
+    fn same_slot_in_registration_order() {
+        block_on(async {
+            let mut wheel = TimerWheel::new(Duration::from_millis(50));
+            let now = Instant::now();
+            let first = wheel.register(now + Duration::from_millis(2));
+            let second = wheel.register(now + Duration::from_millis(1));
+            let deadline = wheel.next_deadline().unwrap();
+            assert!(deadline >= now + Duration::from_millis(2));
+            assert_eq!(deadline.as_nanos() % 50_000_000, 0);
+            assert_eq!(wheel.next_expired().await, Some(first));
+            assert_eq!(wheel.next_expired().await, Some(second));
+        })
+    }
+}
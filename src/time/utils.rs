@@ -3,3 +3,169 @@ use std::io;
 pub(crate) fn timeout_err(msg: &'static str) -> io::Error {
     io::Error::new(io::ErrorKind::TimedOut, msg)
 }
+
+/// A calendar date and time of day in UTC, broken down from a count of
+/// seconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CivilDateTime {
+    pub(crate) year: i64,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    /// Day of the week, where 0 is Sunday.
+    pub(crate) weekday: u32,
+}
+
+impl CivilDateTime {
+    pub(crate) fn from_unix_seconds(secs: u64) -> Self {
+        let days = (secs / 86_400) as i64;
+        let rem = (secs % 86_400) as u32;
+
+        // Convert days since the epoch to a proleptic Gregorian date. See
+        // <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: rem / 3_600,
+            minute: rem % 3_600 / 60,
+            second: rem % 60,
+            // 1970-01-01 was a Thursday.
+            weekday: ((days + 4) % 7) as u32,
+        }
+    }
+
+    /// The number of seconds since the Unix epoch, or `None` if the date is
+    /// before it.
+    pub(crate) fn to_unix_seconds(self) -> Option<u64> {
+        // The inverse of `from_unix_seconds`. See
+        // <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+        let year = self.year - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let mp = i64::from((self.month + 9) % 12);
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146_097 + doe - 719_468;
+        let secs = days * 86_400
+            + i64::from(self.hour) * 3_600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second);
+        u64::try_from(secs).ok()
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Parses an HTTP date into seconds since the Unix epoch.
+///
+/// All three formats from RFC 9110 §5.6.7 are accepted:
+///
+/// - IMF-fixdate: `Sun, 06 Nov 1994 08:49:37 GMT`
+/// - RFC 850: `Sunday, 06-Nov-94 08:49:37 GMT`
+/// - asctime: `Sun Nov  6 08:49:37 1994`
+///
+/// A two-digit RFC 850 year is taken to be in the century that puts it no
+/// more than 50 years after `current_year`. The weekday isn't checked
+/// against the date.
+pub(crate) fn parse_http_date(s: &str, current_year: i64) -> Option<u64> {
+    if let Some((_weekday, rest)) = s.split_once(", ") {
+        let mut fields = rest.split(' ');
+        let date = fields.next()?;
+        let time = fields.next()?;
+        if date.contains('-') {
+            // RFC 850: `06-Nov-94 08:49:37 GMT`.
+            if fields.next() != Some("GMT") || fields.next().is_some() {
+                return None;
+            }
+            let mut dmy = date.split('-');
+            let (day, month, year) = (dmy.next()?, dmy.next()?, dmy.next()?);
+            if dmy.next().is_some() {
+                return None;
+            }
+            let year = i64::from(parse_digits(year, 2)?);
+            let mut year = current_year - current_year.rem_euclid(100) + year;
+            if year > current_year + 50 {
+                year -= 100;
+            }
+            return civil_to_unix_seconds(year, month, parse_digits(day, 2)?, time);
+        }
+        // IMF-fixdate: `06 Nov 1994 08:49:37 GMT`.
+        let (month, year, time) = (time, fields.next()?, fields.next()?);
+        if fields.next() != Some("GMT") || fields.next().is_some() {
+            return None;
+        }
+        civil_to_unix_seconds(
+            parse_digits(year, 4)?.into(),
+            month,
+            parse_digits(date, 2)?,
+            time,
+        )
+    } else {
+        // asctime: `Sun Nov  6 08:49:37 1994`, with the day padded by a space.
+        let (_weekday, rest) = s.split_once(' ')?;
+        let (month, rest) = rest.split_once(' ')?;
+        let (day, rest) = rest.split_at_checked(2)?;
+        let day = match day.strip_prefix(' ') {
+            Some(digit) => parse_digits(digit, 1)?,
+            None => parse_digits(day, 2)?,
+        };
+        let (time, year) = rest.strip_prefix(' ')?.split_once(' ')?;
+        civil_to_unix_seconds(parse_digits(year, 4)?.into(), month, day, time)
+    }
+}
+
+/// Validates the fields of a date and converts it to seconds since the Unix
+/// epoch. `time` is `hh:mm:ss`.
+fn civil_to_unix_seconds(year: i64, month: &str, day: u32, time: &str) -> Option<u64> {
+    let mut hms = time.split(':');
+    let dt = CivilDateTime {
+        year,
+        month: MONTHS.iter().position(|m| *m == month)? as u32 + 1,
+        day,
+        hour: parse_digits(hms.next()?, 2)?,
+        minute: parse_digits(hms.next()?, 2)?,
+        second: parse_digits(hms.next()?, 2)?,
+        weekday: 0,
+    };
+    if hms.next().is_some()
+        || !(1..=days_in_month(dt.year, dt.month)).contains(&dt.day)
+        || dt.hour > 23
+        || dt.minute > 59
+        // Allow for a leap second.
+        || dt.second > 60
+    {
+        return None;
+    }
+    dt.to_unix_seconds()
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn parse_digits(s: &str, len: usize) -> Option<u32> {
+    if s.len() != len || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
@@ -0,0 +1,50 @@
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+mod common;
+use common::run_in_wasmtime;
+
+const LEN: usize = 256 * 1024;
+
+/// Reads a request head, ignoring its fields.
+fn read_head(reader: &mut BufReader<TcpStream>) -> Result<()> {
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        if line.trim_end().is_empty() {
+            return Ok(());
+        }
+    }
+}
+
+/// Answers each of the two requests with a chunked body and a trailer.
+fn serve(listener: std::net::TcpListener) -> Result<()> {
+    for _ in 0..2 {
+        let (mut stream, _) = listener.accept()?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        read_head(&mut reader)?;
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nTrailer: checksum\r\nConnection: close\r\n\r\n"
+        )?;
+        for chunk in vec![7u8; LEN].chunks(16 * 1024) {
+            write!(stream, "{:x}\r\n", chunk.len())?;
+            stream.write_all(chunk)?;
+            write!(stream, "\r\n")?;
+        }
+        write!(stream, "0\r\nchecksum: abc\r\n\r\n")?;
+    }
+    Ok(())
+}
+
+#[test_log::test]
+fn http_body_trailers() -> Result<()> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:8084").context("bind test server")?;
+    let server = std::thread::spawn(move || serve(listener));
+
+    println!("testing {}", test_programs_artifacts::HTTP_BODY_TRAILERS);
+    let wasm = std::fs::read(test_programs_artifacts::HTTP_BODY_TRAILERS).context("read wasm")?;
+    run_in_wasmtime(&wasm, None)?;
+    server.join().expect("test server panicked")
+}
@@ -0,0 +1,11 @@
+use anyhow::{Context, Result};
+
+mod common;
+use common::run_in_wasmtime;
+
+#[test_log::test]
+fn http_connect_tunnel() -> Result<()> {
+    println!("testing {}", test_programs_artifacts::HTTP_CONNECT_TUNNEL);
+    let wasm = std::fs::read(test_programs_artifacts::HTTP_CONNECT_TUNNEL).context("read wasm")?;
+    run_in_wasmtime(&wasm, None)
+}
@@ -0,0 +1,62 @@
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+
+mod common;
+use common::run_in_wasmtime;
+
+/// Reads a request head, returning its `Content-Length`.
+fn read_head(reader: &mut BufReader<TcpStream>) -> Result<usize> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = Some(value.trim().parse::<usize>()?);
+            }
+        }
+    }
+    content_length.context("request has a content-length")
+}
+
+fn respond(mut stream: &TcpStream, status: &str, reply: &str) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+        reply.len()
+    )?;
+    Ok(())
+}
+
+/// Rejects the first request without reading its body, then accepts the
+/// second one once its body has arrived.
+fn serve(listener: std::net::TcpListener) -> Result<()> {
+    let (stream, _) = listener.accept()?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    read_head(&mut reader)?;
+    respond(&stream, "413 Payload Too Large", "too large")?;
+    drop((reader, stream));
+
+    let (stream, _) = listener.accept()?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let len = read_head(&mut reader)?;
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body)?;
+    respond(&stream, "200 OK", &format!("received {len} bytes"))
+}
+
+#[test_log::test]
+fn http_expect_continue() -> Result<()> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:8083").context("bind test server")?;
+    let server = std::thread::spawn(move || serve(listener));
+
+    println!("testing {}", test_programs_artifacts::HTTP_EXPECT_CONTINUE);
+    let wasm = std::fs::read(test_programs_artifacts::HTTP_EXPECT_CONTINUE).context("read wasm")?;
+    run_in_wasmtime(&wasm, None)?;
+    server.join().expect("test server panicked")
+}
@@ -0,0 +1,53 @@
+use anyhow::{Context, Result};
+
+mod common;
+use common::run_in_wasmtime;
+
+/// Accepts a single request and checks that its body has the pattern sent by
+/// the test program.
+fn serve_one(listener: std::net::TcpListener) -> Result<()> {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let (stream, _) = listener.accept()?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = Some(value.trim().parse::<usize>()?);
+            }
+        }
+    }
+    let len = content_length.context("request has a content-length")?;
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body)?;
+    let intact = body.iter().enumerate().all(|(i, b)| *b == (i % 251) as u8);
+    let reply = if intact {
+        format!("received {len} bytes")
+    } else {
+        "corrupted body".to_owned()
+    };
+    write!(
+        &stream,
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+        reply.len()
+    )?;
+    Ok(())
+}
+
+#[test_log::test]
+fn http_post_large() -> Result<()> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:8081").context("bind test server")?;
+    let server = std::thread::spawn(move || serve_one(listener));
+
+    println!("testing {}", test_programs_artifacts::HTTP_POST_LARGE);
+    let wasm = std::fs::read(test_programs_artifacts::HTTP_POST_LARGE).context("read wasm")?;
+    run_in_wasmtime(&wasm, None)?;
+    server.join().expect("test server panicked")
+}
@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+mod common;
+use common::run_in_wasmtime;
+
+const LEN: usize = 100_000;
+
+/// Answers a single request for `/ranges`, which honors `Range` headers, or
+/// `/plain`, which ignores them.
+fn serve(stream: TcpStream) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (
+        parts.next().context("method")?,
+        parts.next().context("path")?,
+    );
+    let mut range_start = None;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("range") {
+                let start = value.trim().strip_prefix("bytes=").context("range unit")?;
+                let start = start.strip_suffix('-').context("open-ended range")?;
+                range_start = Some(start.parse::<usize>()?);
+            }
+        }
+    }
+
+    let body: Vec<u8> = (0..LEN).map(|i| (i % 251) as u8).collect();
+    let ranges = path == "/ranges";
+    let mut head = String::new();
+    let body = match range_start {
+        Some(start) if ranges && start >= LEN => {
+            head.push_str("HTTP/1.1 416 Range Not Satisfiable\r\n");
+            head.push_str(&format!("Content-Range: bytes */{LEN}\r\n"));
+            &body[..0]
+        }
+        Some(start) if ranges => {
+            head.push_str("HTTP/1.1 206 Partial Content\r\n");
+            head.push_str(&format!(
+                "Content-Range: bytes {start}-{}/{LEN}\r\n",
+                LEN - 1
+            ));
+            &body[start..]
+        }
+        _ => {
+            head.push_str("HTTP/1.1 200 OK\r\n");
+            &body[..]
+        }
+    };
+    if ranges {
+        head.push_str("Accept-Ranges: bytes\r\n");
+    }
+    let len = if method == "HEAD" { LEN } else { body.len() };
+    head.push_str(&format!(
+        "Content-Length: {len}\r\nConnection: close\r\n\r\n"
+    ));
+    let mut stream = stream;
+    stream.write_all(head.as_bytes())?;
+    if method != "HEAD" {
+        stream.write_all(body)?;
+    }
+    Ok(())
+}
+
+#[test_log::test]
+fn http_range_reader() -> Result<()> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:8082").context("bind test server")?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            // The client drops responses it only needs part of, so writes
+            // may fail; that's fine.
+            let _ = stream.map_err(Into::into).and_then(serve);
+        }
+    });
+
+    println!("testing {}", test_programs_artifacts::HTTP_RANGE_READER);
+    let wasm = std::fs::read(test_programs_artifacts::HTTP_RANGE_READER).context("read wasm")?;
+    run_in_wasmtime(&wasm, None)
+}
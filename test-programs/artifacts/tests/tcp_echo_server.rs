@@ -1,78 +1,7 @@
-use anyhow::{anyhow, Context, Result};
-use wasmtime::{
-    component::{Component, Linker, ResourceTable},
-    Config, Engine, Store,
-};
-use wasmtime_wasi::{pipe::MemoryOutputPipe, WasiCtx, WasiView};
-use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
+use anyhow::{Context, Result};
 
-struct Ctx {
-    table: ResourceTable,
-    wasi: WasiCtx,
-    http: WasiHttpCtx,
-}
-
-impl WasiView for Ctx {
-    fn table(&mut self) -> &mut ResourceTable {
-        &mut self.table
-    }
-    fn ctx(&mut self) -> &mut WasiCtx {
-        &mut self.wasi
-    }
-}
-
-impl WasiHttpView for Ctx {
-    fn table(&mut self) -> &mut ResourceTable {
-        &mut self.table
-    }
-    fn ctx(&mut self) -> &mut WasiHttpCtx {
-        &mut self.http
-    }
-}
-
-fn run_in_wasmtime(wasm: &[u8], stdout: Option<MemoryOutputPipe>) -> Result<()> {
-    let config = Config::default();
-    let engine = Engine::new(&config).context("creating engine")?;
-    let component = Component::new(&engine, wasm).context("loading component")?;
-
-    let mut linker: Linker<Ctx> = Linker::new(&engine);
-    wasmtime_wasi::add_to_linker_sync(&mut linker).context("add wasi to linker")?;
-    wasmtime_wasi_http::add_only_http_to_linker_sync(&mut linker)
-        .context("add wasi-http to linker")?;
-
-    let mut builder = WasiCtx::builder();
-    builder.inherit_stderr().inherit_network();
-    let wasi = match stdout {
-        Some(stdout) => builder.stdout(stdout).build(),
-        None => builder.inherit_stdout().build(),
-    };
-    let mut store = Store::new(
-        &engine,
-        Ctx {
-            table: ResourceTable::new(),
-            wasi,
-            http: WasiHttpCtx::new(),
-        },
-    );
-
-    let instance = linker.instantiate(&mut store, &component)?;
-    let run_interface = instance
-        .get_export(&mut store, None, "wasi:cli/run@0.2.0")
-        .ok_or_else(|| anyhow!("wasi:cli/run missing?"))?;
-    let run_func_export = instance
-        .get_export(&mut store, Some(&run_interface), "run")
-        .ok_or_else(|| anyhow!("run export missing?"))?;
-    let run_func = instance
-        .get_typed_func::<(), (Result<(), ()>,)>(&mut store, &run_func_export)
-        .context("run as typed func")?;
-
-    println!("entering wasm...");
-    let (runtime_result,) = run_func.call(&mut store, ())?;
-    runtime_result.map_err(|()| anyhow!("run returned an error"))?;
-    println!("done");
-
-    Ok(())
-}
+mod common;
+use common::run_in_wasmtime;
 
 #[test_log::test]
 fn tcp_echo_server() -> Result<()> {
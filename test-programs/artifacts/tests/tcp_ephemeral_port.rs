@@ -0,0 +1,11 @@
+use anyhow::{Context, Result};
+
+mod common;
+use common::run_in_wasmtime;
+
+#[test_log::test]
+fn tcp_ephemeral_port() -> Result<()> {
+    println!("testing {}", test_programs_artifacts::TCP_EPHEMERAL_PORT);
+    let wasm = std::fs::read(test_programs_artifacts::TCP_EPHEMERAL_PORT).context("read wasm")?;
+    run_in_wasmtime(&wasm, None)
+}
@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+
+mod common;
+use common::run_in_wasmtime;
+
+#[test_log::test]
+fn tcp_listener_set() -> Result<()> {
+    use std::io::Write;
+    use std::net::TcpStream;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    println!("testing {}", test_programs_artifacts::TCP_LISTENER_SET);
+    let wasm = std::fs::read(test_programs_artifacts::TCP_LISTENER_SET).context("read wasm")?;
+
+    let pipe = wasmtime_wasi::pipe::MemoryOutputPipe::new(1024 * 1024);
+    let write_end = pipe.clone();
+    let wasmtime_thread = std::thread::spawn(move || run_in_wasmtime(&wasm, Some(write_end)));
+
+    let (a, b) = 'wait: loop {
+        sleep(Duration::from_millis(100));
+        for line in String::from_utf8_lossy(&pipe.contents()).lines() {
+            if let Some(addrs) = line.strip_prefix("Listening on ") {
+                let (a, b) = addrs.split_once(' ').context("two addresses")?;
+                break 'wait (a.to_owned(), b.to_owned());
+            }
+        }
+    };
+
+    // Queue up both connections to the second listener before any to the
+    // first, so that accepting them in order would starve the first.
+    let mut streams = Vec::new();
+    for (addr, name) in [(&b, b"b"), (&b, b"b"), (&a, b"a"), (&a, b"a")] {
+        let mut stream = TcpStream::connect(addr).context("connect to listener")?;
+        stream.write_all(name).context("write to socket")?;
+        streams.push(stream);
+    }
+
+    wasmtime_thread.join().expect("wasmtime panicked")?;
+    Ok(())
+}
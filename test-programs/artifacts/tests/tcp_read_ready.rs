@@ -0,0 +1,11 @@
+use anyhow::{Context, Result};
+
+mod common;
+use common::run_in_wasmtime;
+
+#[test_log::test]
+fn tcp_read_ready() -> Result<()> {
+    println!("testing {}", test_programs_artifacts::TCP_READ_READY);
+    let wasm = std::fs::read(test_programs_artifacts::TCP_READ_READY).context("read wasm")?;
+    run_in_wasmtime(&wasm, None)
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+use wstd::http::{Client, Request};
+use wstd::io::{self, AsyncRead};
+use wstd::iter::AsyncIterator;
+use wstd::net::{TcpListener, TcpStream};
+use wstd::time::{timeout, Duration};
+
+const LEN: usize = 256 * 1024;
+
+#[wstd::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let client = Client::new();
+
+    // The trailers must resolve once the stream has been read to the end,
+    // while the stream itself is still alive.
+    let request = Request::get("http://127.0.0.1:8084/").body(io::empty())?;
+    let response = client.send(request).await?;
+    let (mut stream, trailers) = response.into_body().into_parts();
+    let mut body = Vec::new();
+    stream.read_to_end(&mut body).await?;
+    assert_eq!(body, vec![7u8; LEN]);
+    let trailers = timeout(Duration::from_secs(5), trailers).await??;
+    assert_eq!(trailers.unwrap()["checksum"], "abc");
+
+    // The same goes for a stream forwarded to another WASI stream by
+    // `io::copy`, as a proxy would.
+    let request = Request::get("http://127.0.0.1:8084/").body(io::empty())?;
+    let response = client.send(request).await?;
+    let (mut stream, trailers) = response.into_body().into_parts();
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?.to_string();
+    let mut incoming = listener.incoming();
+    let (client, server) =
+        futures_lite::future::zip(TcpStream::connect(&addr), incoming.next()).await;
+    let mut client = client?;
+    let mut server = server.unwrap()?;
+    let forward = async {
+        io::copy(&mut stream, &mut client).await?;
+        let trailers = timeout(Duration::from_secs(5), trailers).await??;
+        drop(client);
+        Ok::<_, Box<dyn Error>>(trailers)
+    };
+    let receive = async {
+        let mut body = Vec::new();
+        server.read_to_end(&mut body).await?;
+        io::Result::Ok(body)
+    };
+    let (trailers, body) = futures_lite::future::zip(forward, receive).await;
+    assert_eq!(trailers?.unwrap()["checksum"], "abc");
+    assert_eq!(body?, vec![7u8; LEN]);
+    drop(stream);
+    Ok(())
+}
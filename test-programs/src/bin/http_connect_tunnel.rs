@@ -0,0 +1,68 @@
+use futures_lite::future::zip;
+use wstd::http::tunnel::ConnectRequest;
+use wstd::io::{self, AsyncRead, AsyncWrite};
+use wstd::iter::AsyncIterator;
+use wstd::net::{TcpListener, TcpStream};
+
+const ESTABLISHED: &[u8] = b"HTTP/1.1 200 Connection Established\r\n\r\n";
+
+#[wstd::main]
+async fn main() -> io::Result<()> {
+    let target = TcpListener::bind("127.0.0.1:0").await?;
+    let target_addr = target.local_addr()?.to_string();
+    let proxy = TcpListener::bind("127.0.0.1:0").await?;
+    let proxy_addr = proxy.local_addr()?.to_string();
+    let mut proxy_incoming = proxy.incoming();
+
+    let echo = async {
+        let stream = target.incoming().next().await.unwrap()?;
+        io::copy(&stream, &stream).await
+    };
+    let tunnel = async {
+        let client = proxy_incoming.next().await.unwrap()?;
+        let request = ConnectRequest::read(client).await?;
+        assert_eq!(request.target().as_str(), target_addr);
+        request.accept().await?.splice().await
+    };
+    let client = async {
+        let mut client = TcpStream::connect(&proxy_addr).await?;
+        // The first bytes meant for the target are sent along with the
+        // request head, before the tunnel is established.
+        let head = format!("CONNECT {target_addr} HTTP/1.1\r\nHost: {target_addr}\r\n\r\nhello");
+        client.write_all(head.as_bytes()).await?;
+        client.flush().await?;
+        let mut buf = Vec::new();
+        while buf.len() < ESTABLISHED.len() + 5 {
+            let mut chunk = [0; 64];
+            let n = client.read(&mut chunk).await?;
+            assert_ne!(n, 0, "tunnel closed early");
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        assert_eq!(&buf[..ESTABLISHED.len()], ESTABLISHED);
+        assert_eq!(&buf[ESTABLISHED.len()..], b"hello");
+        io::Result::Ok(())
+    };
+    let ((echoed, tunneled), client) = zip(zip(echo, tunnel), client).await;
+    client?;
+    echoed?;
+    assert_eq!(tunneled?, (5, 5));
+
+    // Other methods are refused.
+    let client = async {
+        let mut client = TcpStream::connect(&proxy_addr).await?;
+        client.write_all(b"GET / HTTP/1.1\r\n\r\n").await?;
+        client.flush().await?;
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await?;
+        assert!(response.starts_with(b"HTTP/1.1 405 "));
+        io::Result::Ok(())
+    };
+    let refused = async {
+        let client = proxy_incoming.next().await.unwrap()?;
+        ConnectRequest::read(client).await
+    };
+    let (client, refused) = zip(client, refused).await;
+    client?;
+    assert_eq!(refused.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    Ok(())
+}
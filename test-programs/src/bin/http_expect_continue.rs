@@ -0,0 +1,37 @@
+use std::error::Error;
+use wstd::http::{Client, IntoBody, Request, StatusCode};
+use wstd::io::AsyncRead;
+use wstd::time::Duration;
+
+const LEN: usize = 1024 * 1024;
+
+#[wstd::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let client = Client::builder()
+        .expect_continue_timeout(Duration::from_millis(200))
+        .build();
+
+    // The server rejects the first upload as soon as it has the head, so the
+    // body is never sent.
+    let request = Request::post("http://127.0.0.1:8083/upload")
+        .header("expect", "100-continue")
+        .body(vec![7u8; LEN].into_body())?;
+    let mut response = client.send(request).await?;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let mut reply = Vec::new();
+    response.body_mut().read_to_end(&mut reply).await?;
+    assert_eq!(reply, b"too large");
+
+    // The server waits for the body of the second upload, which is sent once
+    // the timeout has passed.
+    let request = Request::post("http://127.0.0.1:8083/upload")
+        .header("expect", "100-continue")
+        .body(vec![7u8; 16].into_body())?;
+    let mut response = client.send(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut reply = Vec::new();
+    response.body_mut().read_to_end(&mut reply).await?;
+    assert_eq!(reply, b"received 16 bytes");
+    assert_eq!(response.body().remaining(), Some(0));
+    Ok(())
+}
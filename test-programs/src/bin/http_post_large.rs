@@ -0,0 +1,20 @@
+use std::error::Error;
+use wstd::http::{Client, IntoBody, Request};
+use wstd::io::AsyncRead;
+
+/// Larger than the buffer of a wasi-http output stream, so writing the body
+/// has to wait for the host to send earlier parts of it.
+const LEN: usize = 4 * 1024 * 1024;
+
+#[wstd::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let body: Vec<u8> = (0..LEN).map(|i| (i % 251) as u8).collect();
+    let request = Request::post("http://127.0.0.1:8081/upload").body(body.into_body())?;
+    let mut response = Client::new().send(request).await?;
+    assert!(response.status().is_success(), "{}", response.status());
+
+    let mut reply = Vec::new();
+    response.body_mut().read_to_end(&mut reply).await?;
+    assert_eq!(String::from_utf8(reply)?, format!("received {LEN} bytes"));
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::error::Error;
+use wstd::http::{Client, RangeReader};
+use wstd::io::{AsyncRead, AsyncSeek, SeekFrom};
+
+/// The length of the resource served by the test server.
+const LEN: u64 = 100_000;
+
+fn expected(start: u64, len: usize) -> Vec<u8> {
+    (start..start + len as u64)
+        .map(|i| (i % 251) as u8)
+        .collect()
+}
+
+async fn read_at(reader: &mut RangeReader, pos: SeekFrom, len: usize) -> std::io::Result<Vec<u8>> {
+    reader.seek(pos).await?;
+    let mut buf = Vec::new();
+    while buf.len() < len {
+        let mut chunk = vec![0; len - buf.len()];
+        let n = reader.read(&mut chunk).await?;
+        assert_ne!(n, 0, "unexpected end of resource");
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Ok(buf)
+}
+
+#[wstd::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    // `/ranges` honors range requests, `/plain` ignores them.
+    for (path, ranges) in [("ranges", true), ("plain", false)] {
+        let uri = format!("http://127.0.0.1:8082/{path}").parse()?;
+        let mut reader = RangeReader::new(Client::new(), uri).await?;
+        assert_eq!(reader.content_length(), Some(LEN));
+        assert_eq!(reader.accepts_ranges(), ranges);
+
+        let buf = read_at(&mut reader, SeekFrom::Start(50_000), 1_000).await?;
+        assert_eq!(buf, expected(50_000, 1_000));
+        let buf = read_at(&mut reader, SeekFrom::Current(-11_000), 1_000).await?;
+        assert_eq!(buf, expected(40_000, 1_000));
+        // Sequential reads continue the same response.
+        let buf = read_at(&mut reader, SeekFrom::Current(0), 1_000).await?;
+        assert_eq!(buf, expected(41_000, 1_000));
+
+        reader.seek(SeekFrom::End(-100)).await?;
+        let mut tail = Vec::new();
+        reader.read_to_end(&mut tail).await?;
+        assert_eq!(tail, expected(LEN - 100, 100));
+
+        reader.seek(SeekFrom::Start(LEN + 1)).await?;
+        assert_eq!(reader.read(&mut [0; 16]).await?, 0);
+        assert!(reader
+            .seek(SeekFrom::Current(-(LEN as i64) - 2))
+            .await
+            .is_err());
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use wstd::io::{self, AsyncInputStream, AsyncRead, AsyncWrite};
+use wstd::iter::AsyncIterator;
+use wstd::net::{TcpListener, TcpStream};
+use wstd::time::Duration;
+
+/// Counts the bytes passing through `read`, while still exposing the inner
+/// stream.
+struct Counting<R> {
+    inner: R,
+    count: usize,
+}
+
+impl<R: AsyncRead> AsyncRead for Counting<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf).await?;
+        self.count += n;
+        Ok(n)
+    }
+
+    fn as_async_input_stream(&self) -> Option<&AsyncInputStream> {
+        self.inner.as_async_input_stream()
+    }
+}
+
+#[wstd::main]
+async fn main() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?.to_string();
+
+    // Connect to the listener while it accepts the connection.
+    let mut incoming = listener.incoming();
+    let (client, server) =
+        futures_lite::future::zip(TcpStream::connect(&addr), incoming.next()).await;
+    let mut client = client?;
+    let mut server = server.unwrap()?;
+    client.write_all(b"hello").await?;
+    client.flush().await?;
+    drop(client);
+    let mut buf = Vec::new();
+    server.read_to_end(&mut buf).await?;
+    assert_eq!(buf, b"hello");
+
+    // Owned halves can be moved into separate futures. Dropping the write
+    // half shuts down only the sending direction, so the read half still
+    // receives the reply.
+    let (client, server) =
+        futures_lite::future::zip(TcpStream::connect(&addr), incoming.next()).await;
+    let (mut reader, mut writer) = client?.into_split();
+    let mut server = server.unwrap()?;
+    let send = async move {
+        writer.write_all(b"ping").await?;
+        writer.flush().await
+    };
+    let serve = async move {
+        let mut buf = Vec::new();
+        server.read_to_end(&mut buf).await?;
+        assert_eq!(buf, b"ping");
+        server.write_all(b"pong").await?;
+        server.flush().await
+    };
+    let (sent, served) = futures_lite::future::zip(send, serve).await;
+    sent?;
+    served?;
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).await?;
+    assert_eq!(buf, b"pong");
+
+    // The default `read_to_end` must go through the wrapper's `read`, even
+    // though the wrapper forwards the underlying stream.
+    let (client, server) =
+        futures_lite::future::zip(TcpStream::connect(&addr), incoming.next()).await;
+    let mut client = client?;
+    let server = server.unwrap()?;
+    client.write_all(b"counted").await?;
+    client.flush().await?;
+    drop(client);
+    let mut counting = Counting {
+        inner: server,
+        count: 0,
+    };
+    let mut buf = Vec::new();
+    counting.read_to_end(&mut buf).await?;
+    assert_eq!(buf, b"counted");
+    assert_eq!(counting.count, buf.len());
+
+    let stream = TcpStream::connect_timeout(&addr, Duration::from_secs(5)).await?;
+    drop(stream);
+
+    // Cancelling connection attempts to an unreachable host must not trap,
+    // whether the attempt is dropped by a timeout or by hand. Some sandboxes
+    // do answer on this address, so only a trap counts as a failure here.
+    let unreachable = "10.255.255.1:9";
+    match TcpStream::connect_timeout(unreachable, Duration::from_millis(100)).await {
+        Ok(_) => println!("connect to {unreachable}: connected"),
+        Err(err) => println!("connect to {unreachable}: {err}"),
+    }
+    let pending = TcpStream::start_connect(unreachable)?;
+    // Poll once so the attempt is registered with the reactor, then drop it.
+    let _ = futures_lite::future::poll_once(pending).await;
+    drop(TcpStream::start_connect(unreachable)?);
+
+    let refused = TcpStream::connect("127.0.0.1:1").await;
+    assert!(refused.is_err(), "nothing should listen on port 1");
+    Ok(())
+}
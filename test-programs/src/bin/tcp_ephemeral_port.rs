@@ -0,0 +1,35 @@
+use wstd::io;
+use wstd::net::TcpListener;
+use wstd::time::{Duration, Instant};
+
+#[wstd::main]
+async fn main() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    println!("Listening on {addr}");
+    assert!(addr.ip().is_loopback(), "bound to loopback, got {addr}");
+    assert_ne!(addr.port(), 0, "an ephemeral port should be assigned");
+
+    let listener = TcpListener::bind_timeout("127.0.0.1:0", Duration::from_secs(5)).await?;
+    let addr = listener.local_addr()?;
+    assert_ne!(addr.port(), 0, "an ephemeral port should be assigned");
+
+    // While `listener` holds the port, retrying gives up with `AddrInUse`.
+    let addr = addr.to_string();
+    let backoff = Duration::from_millis(10);
+    let start = Instant::now();
+    let err = TcpListener::bind_with_retry(&addr, 2, backoff)
+        .await
+        .expect_err("the address is still in use");
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+    let elapsed: std::time::Duration = start.elapsed().into();
+    assert!(
+        elapsed >= std::time::Duration::from_millis(30),
+        "backed off for {elapsed:?}"
+    );
+
+    drop(listener);
+    let listener = TcpListener::bind_with_retry(&addr, 2, backoff).await?;
+    assert_eq!(listener.local_addr()?.to_string(), addr);
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use wstd::io::{self, AsyncRead};
+use wstd::iter::AsyncIterator;
+use wstd::net::{TcpListener, TcpListenerSet};
+use wstd::task::sleep;
+use wstd::time::Duration;
+
+#[wstd::main]
+async fn main() -> io::Result<()> {
+    let mut listeners = TcpListenerSet::new();
+    listeners.add(TcpListener::bind("127.0.0.1:0").await?);
+    listeners.add(TcpListener::bind("127.0.0.1:0").await?);
+    let a = listeners.listeners()[0].local_addr()?;
+    let b = listeners.listeners()[1].local_addr()?;
+    println!("Listening on {a} {b}");
+
+    // Give the client time to queue up connections on both listeners.
+    sleep(Duration::from_millis(500)).await;
+
+    // Each client sends one byte naming the listener it connected to.
+    let mut order = String::new();
+    let mut incoming = listeners.incoming();
+    for _ in 0..4 {
+        let mut stream = incoming.next().await.unwrap()?;
+        let mut byte = [0];
+        stream.read(&mut byte).await?;
+        order.push(byte[0] as char);
+    }
+    println!("Accepted in order {order}");
+    assert_eq!(order, "abab", "listeners should take turns");
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use futures_lite::future::zip;
+use wstd::io::{self, AsyncRead, AsyncWrite};
+use wstd::iter::AsyncIterator;
+use wstd::net::{TcpListener, TcpStream};
+use wstd::task::sleep;
+use wstd::time::Duration;
+
+const CHUNKS: [&[u8]; 4] = [b"a", b"bc", b"def", b"ghij"];
+
+#[wstd::main]
+async fn main() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?.to_string();
+    let mut incoming = listener.incoming();
+    let (client, server) = zip(TcpStream::connect(&addr), incoming.next()).await;
+    let mut client = client?;
+    let mut server = server.unwrap()?;
+
+    // Bytes trickle in, so the socket keeps going from ready to not ready.
+    // A read must wait for more rather than report end-of-stream early.
+    let send = async {
+        for chunk in CHUNKS {
+            sleep(Duration::from_millis(20)).await;
+            client.write_all(chunk).await?;
+            client.flush().await?;
+        }
+        drop(client);
+        io::Result::Ok(())
+    };
+    let receive = async {
+        let mut received = Vec::new();
+        let mut buf = [0; 64];
+        loop {
+            let n = server.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            received.extend_from_slice(&buf[..n]);
+        }
+        io::Result::Ok(received)
+    };
+    let (sent, received) = zip(send, receive).await;
+    sent?;
+    assert_eq!(received?, CHUNKS.concat());
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use std::error::Error;
+use wstd::http::{Client, Request};
+use wstd::io::{self, AsyncRead};
+
+#[wstd::test]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let request = Request::post("https://postman-echo.com/post").body(io::empty())?;
+
+    let mut response = Client::new().send(request).await?;
+    assert!(response.status().is_success(), "{}", response.status());
+
+    let mut body_buf = Vec::new();
+    response.body_mut().read_to_end(&mut body_buf).await?;
+
+    let val: serde_json::Value = serde_json::from_slice(&body_buf)?;
+    let content_length = val
+        .get("headers")
+        .and_then(|headers| headers.get("content-length"))
+        .ok_or_else(|| format!("request should have had a content-length, got {val:?}"))?;
+    assert_eq!(content_length, "0");
+
+    Ok(())
+}